@@ -0,0 +1,3 @@
+DROP TABLE calendar_feed_tokens;
+ALTER TABLE reservations DROP COLUMN sequence;
+ALTER TABLE appointments DROP COLUMN sequence;
@@ -0,0 +1,10 @@
+-- SEQUENCE do iCalendar: incrementado a cada remarcação/cancelamento
+ALTER TABLE appointments ADD COLUMN sequence INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE reservations ADD COLUMN sequence INTEGER NOT NULL DEFAULT 0;
+
+-- Tokens secretos dos feeds .ics (apps de calendário não enviam Bearer)
+CREATE TABLE calendar_feed_tokens (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    token TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,2 @@
+DROP TABLE busy_blocks;
+DROP TABLE calendar_sources;
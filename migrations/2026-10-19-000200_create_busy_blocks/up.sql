@@ -0,0 +1,26 @@
+-- Calendários externos importados por profissional (location NULL = uploads manuais)
+CREATE TABLE calendar_sources (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    professional_id UUID NOT NULL REFERENCES professionals(id) ON DELETE CASCADE,
+    location TEXT,
+    last_synced_at TIMESTAMP,
+    last_error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX calendar_sources_upload_idx ON calendar_sources (professional_id) WHERE location IS NULL;
+
+-- Ocorrências (já expandidas) dos eventos importados; bloqueiam a agenda do profissional
+CREATE TABLE busy_blocks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    professional_id UUID NOT NULL REFERENCES professionals(id) ON DELETE CASCADE,
+    source_id UUID NOT NULL REFERENCES calendar_sources(id) ON DELETE CASCADE,
+    uid TEXT NOT NULL,
+    summary TEXT,
+    start_time TIMESTAMP NOT NULL,
+    end_time TIMESTAMP NOT NULL,
+    synced_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (source_id, uid, start_time)
+);
+
+CREATE INDEX busy_blocks_professional_time_idx ON busy_blocks (professional_id, start_time, end_time);
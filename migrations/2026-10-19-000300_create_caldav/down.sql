@@ -0,0 +1,7 @@
+DROP TABLE app_passwords;
+DROP TABLE caldav_objects;
+
+DROP INDEX calendar_sources_kind_idx;
+DELETE FROM calendar_sources WHERE kind = 'caldav';
+CREATE UNIQUE INDEX calendar_sources_upload_idx ON calendar_sources (professional_id) WHERE location IS NULL;
+ALTER TABLE calendar_sources DROP COLUMN kind;
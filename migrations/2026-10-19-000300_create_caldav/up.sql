@@ -0,0 +1,30 @@
+-- Tipo da origem: 'url' (arquivo/URL relido), 'upload' (uploads manuais) ou 'caldav' (eventos enviados via CalDAV)
+ALTER TABLE calendar_sources ADD COLUMN kind TEXT NOT NULL DEFAULT 'url';
+UPDATE calendar_sources SET kind = 'upload' WHERE location IS NULL;
+
+DROP INDEX calendar_sources_upload_idx;
+CREATE UNIQUE INDEX calendar_sources_kind_idx ON calendar_sources (professional_id, kind) WHERE kind <> 'url';
+
+-- Recursos (.ics) criados pelos clientes CalDAV na agenda do profissional
+CREATE TABLE caldav_objects (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    professional_id UUID NOT NULL REFERENCES professionals(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    uid TEXT NOT NULL,
+    ics_data TEXT NOT NULL,
+    etag TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (professional_id, name)
+);
+
+-- Senhas de aplicativo (HTTP Basic) usadas pelos clientes CalDAV
+CREATE TABLE app_passwords (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    password_hash TEXT NOT NULL,
+    last_used_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX app_passwords_user_idx ON app_passwords (user_id);
@@ -0,0 +1,10 @@
+-- Preferências de notificação por canal (opt-in: tudo desligado por padrão)
+CREATE TABLE notification_preferences (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    sms BOOLEAN NOT NULL DEFAULT FALSE,
+    email BOOLEAN NOT NULL DEFAULT FALSE,
+    whatsapp BOOLEAN NOT NULL DEFAULT FALSE,
+    email_address TEXT,
+    locale TEXT NOT NULL DEFAULT 'pt-BR',
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+DROP TABLE attendance_confirmations;
+ALTER TABLE salon_settings DROP COLUMN reminder_offsets_hours;
+DROP TABLE background_jobs;
@@ -0,0 +1,29 @@
+-- Fila persistente de jobs (workers buscam com FOR UPDATE SKIP LOCKED)
+CREATE TABLE background_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind TEXT NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}',
+    reference_id UUID,
+    status TEXT NOT NULL DEFAULT 'pending',
+    run_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 5,
+    last_error TEXT,
+    locked_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX background_jobs_due_idx ON background_jobs (run_at) WHERE status = 'pending';
+CREATE INDEX background_jobs_reference_idx ON background_jobs (kind, reference_id);
+
+-- Antecedência (em horas) dos lembretes de agendamento
+ALTER TABLE salon_settings ADD COLUMN reminder_offsets_hours INTEGER[] NOT NULL DEFAULT '{24,2}';
+
+-- Confirmação de presença pelo cliente (link no lembrete ou resposta por SMS/WhatsApp)
+CREATE TABLE attendance_confirmations (
+    appointment_id UUID PRIMARY KEY REFERENCES appointments(id) ON DELETE CASCADE,
+    token TEXT NOT NULL UNIQUE,
+    confirmed_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,2 @@
+DROP TABLE outbox_consumptions;
+DROP TABLE outbox_events;
@@ -0,0 +1,15 @@
+-- Respostas guardadas por `Idempotency-Key` (por usuário): novas tentativas com a mesma chave recebem a resposta original
+CREATE TABLE idempotency_keys (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    idempotency_key TEXT NOT NULL,
+    request_hash TEXT NOT NULL,      -- SHA-256 de método, caminho (sem o prefixo de versão) e corpo
+    response_status INTEGER,         -- NULL enquanto a requisição original está em andamento
+    response_headers JSONB,          -- Content-Type, Location, ETag e Content-Disposition da resposta original
+    response_body BYTEA,
+    locked_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMP,
+    PRIMARY KEY (user_id, idempotency_key)
+);
+
+CREATE INDEX idempotency_keys_created_at_idx ON idempotency_keys (created_at);
@@ -0,0 +1,13 @@
+DROP TRIGGER users_bump_version ON users;
+DROP TRIGGER professionals_bump_version ON professionals;
+DROP TRIGGER services_bump_version ON services;
+DROP TRIGGER salon_settings_bump_version ON salon_settings;
+DROP TRIGGER appointments_bump_version ON appointments;
+
+ALTER TABLE users DROP COLUMN version;
+ALTER TABLE professionals DROP COLUMN version;
+ALTER TABLE services DROP COLUMN version;
+ALTER TABLE salon_settings DROP COLUMN version;
+ALTER TABLE appointments DROP COLUMN version;
+
+DROP FUNCTION bump_row_version();
@@ -0,0 +1,20 @@
+-- Versão de cada linha editável (controle de concorrência otimista via `ETag` / `If-Match`)
+CREATE OR REPLACE FUNCTION bump_row_version() RETURNS trigger AS $$
+BEGIN
+    NEW.version := OLD.version + 1;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE appointments ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE salon_settings ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE services ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE professionals ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE users ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+
+-- Todo UPDATE incrementa a versão (handlers, importação em massa, workers), sem depender de quem altera
+CREATE TRIGGER appointments_bump_version BEFORE UPDATE ON appointments FOR EACH ROW EXECUTE FUNCTION bump_row_version();
+CREATE TRIGGER salon_settings_bump_version BEFORE UPDATE ON salon_settings FOR EACH ROW EXECUTE FUNCTION bump_row_version();
+CREATE TRIGGER services_bump_version BEFORE UPDATE ON services FOR EACH ROW EXECUTE FUNCTION bump_row_version();
+CREATE TRIGGER professionals_bump_version BEFORE UPDATE ON professionals FOR EACH ROW EXECUTE FUNCTION bump_row_version();
+CREATE TRIGGER users_bump_version BEFORE UPDATE ON users FOR EACH ROW EXECUTE FUNCTION bump_row_version();
@@ -0,0 +1,4 @@
+ALTER TABLE appointments DROP COLUMN deleted_at;
+ALTER TABLE services DROP COLUMN deleted_at;
+ALTER TABLE professionals DROP COLUMN deleted_at;
+ALTER TABLE users DROP COLUMN deleted_at;
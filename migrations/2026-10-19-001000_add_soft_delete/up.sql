@@ -0,0 +1,11 @@
+-- Exclusão lógica: DELETE na API preenche `deleted_at`; o job `soft_delete_purge` remove de vez após a retenção
+ALTER TABLE users ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE professionals ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE services ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE appointments ADD COLUMN deleted_at TIMESTAMP;
+
+-- Só as linhas excluídas entram nos índices (usados pela limpeza)
+CREATE INDEX users_deleted_at_idx ON users (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX professionals_deleted_at_idx ON professionals (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX services_deleted_at_idx ON services (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX appointments_deleted_at_idx ON appointments (deleted_at) WHERE deleted_at IS NOT NULL;
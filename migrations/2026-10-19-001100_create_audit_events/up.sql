@@ -0,0 +1,29 @@
+-- Trilha de auditoria: quem alterou o quê (ações administrativas e de agendamento), somente inserção
+CREATE TABLE audit_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    actor_id UUID,                   -- Sem FK: o registro sobrevive à remoção definitiva do usuário
+    actor_role TEXT,
+    action TEXT NOT NULL,            -- Ex: "update", "role_change" ou "POST /webhooks/:id/ping"
+    entity_type TEXT NOT NULL,       -- Ex: "services", "appointments"
+    entity_id UUID,
+    before JSONB,                    -- Apenas os campos alterados (valores anteriores)
+    after JSONB,                     -- Apenas os campos alterados (novos valores)
+    ip TEXT,
+    request_id TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX audit_events_created_at_idx ON audit_events (created_at);
+CREATE INDEX audit_events_actor_idx ON audit_events (actor_id, created_at);
+CREATE INDEX audit_events_entity_idx ON audit_events (entity_type, entity_id, created_at);
+
+-- Imutável: UPDATE/DELETE são rejeitados pelo banco
+CREATE FUNCTION reject_audit_event_change() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_events é somente inserção';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_events_immutable
+    BEFORE UPDATE OR DELETE ON audit_events
+    FOR EACH ROW EXECUTE FUNCTION reject_audit_event_change();
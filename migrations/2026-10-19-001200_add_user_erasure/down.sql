@@ -0,0 +1,7 @@
+CREATE OR REPLACE FUNCTION reject_audit_event_change() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_events é somente inserção';
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE users DROP COLUMN anonymized_at;
@@ -0,0 +1,18 @@
+-- Eliminação de dados pessoais (LGPD): o usuário fica anonimizado, mas a linha continua para os agendamentos e relatórios
+ALTER TABLE users ADD COLUMN anonymized_at TIMESTAMP;
+
+-- A trilha de auditoria continua imutável, exceto pela eliminação de dados pessoais: com `app.audit_erasure = on`
+-- (definido só dentro da transação da eliminação) `before`, `after` e `ip` podem ser mascarados
+CREATE OR REPLACE FUNCTION reject_audit_event_change() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'UPDATE'
+        AND current_setting('app.audit_erasure', true) = 'on'
+        AND (NEW.id, NEW.actor_id, NEW.actor_role, NEW.action, NEW.entity_type, NEW.entity_id, NEW.request_id, NEW.created_at)
+            IS NOT DISTINCT FROM
+            (OLD.id, OLD.actor_id, OLD.actor_role, OLD.action, OLD.entity_type, OLD.entity_id, OLD.request_id, OLD.created_at)
+    THEN
+        RETURN NEW;
+    END IF;
+    RAISE EXCEPTION 'audit_events é somente inserção';
+END;
+$$ LANGUAGE plpgsql;
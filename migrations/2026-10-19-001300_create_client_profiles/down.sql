@@ -0,0 +1,2 @@
+DROP TABLE client_notes;
+DROP TABLE client_profiles;
@@ -0,0 +1,22 @@
+-- Ficha do cliente: preferências e dados de atendimento (fórmula de coloração, alergias, profissionais preferidos)
+CREATE TABLE client_profiles (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    hair_formula TEXT,
+    allergies TEXT,
+    preferred_professional_ids UUID[] NOT NULL DEFAULT '{}',
+    preferred_contact_time TEXT,
+    updated_by UUID,                 -- Sem FK: quem editou pode ser removido depois
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- Anotações sobre o cliente: `staff` só a equipe vê; `shared` o cliente também vê
+CREATE TABLE client_notes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    client_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    author_id UUID,                  -- Sem FK: a anotação continua se o autor for removido
+    body TEXT NOT NULL,
+    visibility TEXT NOT NULL DEFAULT 'staff' CHECK (visibility IN ('staff', 'shared')),
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX client_notes_client_idx ON client_notes (client_id, created_at);
@@ -0,0 +1,15 @@
+-- Códigos de verificação por SMS do agendamento sem cadastro (convidado)
+CREATE TABLE guest_verifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    phone TEXT NOT NULL,             -- Só os dígitos
+    code_hash TEXT,                  -- SHA-256 do código enviado (NULL até o job gerar e enviar o código)
+    attempts INTEGER NOT NULL DEFAULT 0,
+    ip TEXT,                         -- Quem pediu o código (limite por IP)
+    expires_at TIMESTAMP NOT NULL,
+    consumed_at TIMESTAMP,           -- Código já usado num agendamento
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX guest_verifications_phone_idx ON guest_verifications (phone, created_at);
+CREATE INDEX guest_verifications_ip_idx ON guest_verifications (ip, created_at);
+CREATE INDEX guest_verifications_created_at_idx ON guest_verifications (created_at);
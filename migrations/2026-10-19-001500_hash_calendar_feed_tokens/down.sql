@@ -0,0 +1,4 @@
+-- O token original não pode ser recuperado do hash: os usuários precisam gerar um novo
+DELETE FROM calendar_feed_tokens;
+ALTER TABLE calendar_feed_tokens DROP COLUMN token_hash;
+ALTER TABLE calendar_feed_tokens ADD COLUMN token TEXT NOT NULL UNIQUE;
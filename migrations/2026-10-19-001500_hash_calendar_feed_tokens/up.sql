@@ -0,0 +1,6 @@
+-- Tokens dos feeds .ics guardados só como SHA-256 (hex); os links já distribuídos continuam válidos
+ALTER TABLE calendar_feed_tokens ADD COLUMN token_hash TEXT;
+UPDATE calendar_feed_tokens SET token_hash = encode(sha256(convert_to(token, 'UTF8')), 'hex');
+ALTER TABLE calendar_feed_tokens ALTER COLUMN token_hash SET NOT NULL;
+ALTER TABLE calendar_feed_tokens ADD CONSTRAINT calendar_feed_tokens_token_hash_key UNIQUE (token_hash);
+ALTER TABLE calendar_feed_tokens DROP COLUMN token;
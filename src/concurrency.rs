@@ -0,0 +1,119 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::models::{
+    appointment::Appointment, professional::Professional, salon_settings::SalonSetting, service::Service, user::User,
+};
+
+/// 🔹 Recursos com coluna `version` (incrementada pelo banco a cada UPDATE)
+pub trait Versioned {
+    fn version(&self) -> i32;
+}
+
+macro_rules! versioned {
+    ($($model:ty),*) => {
+        $(impl Versioned for $model {
+            fn version(&self) -> i32 {
+                self.version
+            }
+        })*
+    };
+}
+
+versioned!(Appointment, Professional, SalonSetting, Service, User);
+
+/// 🔹 `ETag` forte de uma versão (`"3"`)
+pub fn etag(version: i32) -> String {
+    format!("\"{}\"", version)
+}
+
+/// 🔹 Versões aceitas pelo cliente (`If-Match`), obrigatório nas atualizações
+#[derive(Debug, Clone, PartialEq)]
+pub enum IfMatch {
+    Any,               // `If-Match: *` → qualquer versão existente
+    Tags(Vec<String>), // `If-Match: "3"` ou `"3", "4"`
+}
+
+impl IfMatch {
+    pub fn parse(value: &str) -> IfMatch {
+        if value.trim() == "*" {
+            return IfMatch::Any;
+        }
+        IfMatch::Tags(value.split(',').map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect())
+    }
+
+    /// ✅ Comparação forte (RFC 9110): ETags fracos (`W/"3"`) nunca batem
+    pub fn matches(&self, version: i32) -> bool {
+        match self {
+            IfMatch::Any => true,
+            IfMatch::Tags(tags) => tags.iter().any(|tag| *tag == etag(version)),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for IfMatch {
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let values = parts
+            .headers
+            .get_all(header::IF_MATCH)
+            .iter()
+            .map(|value| value.to_str().map_err(|_| (StatusCode::BAD_REQUEST, "If-Match inválido".to_string())))
+            .collect::<Result<Vec<_>, _>>()?;
+        if values.is_empty() {
+            return Err((
+                StatusCode::PRECONDITION_REQUIRED,
+                "If-Match obrigatório: envie o ETag recebido ao buscar o recurso".to_string(),
+            ));
+        }
+        Ok(IfMatch::parse(&values.join(",")))
+    }
+}
+
+/// ✅ Resposta JSON com o `ETag` da versão do recurso
+pub struct Tagged<T>(pub T);
+
+impl<T: Serialize + Versioned> IntoResponse for Tagged<T> {
+    fn into_response(self) -> Response {
+        ([(header::ETAG, etag(self.0.version()))], Json(self.0)).into_response()
+    }
+}
+
+/// ✅ Resultado de uma atualização condicional: o handler trava a linha (`FOR UPDATE`),
+/// confere o `If-Match` contra a versão atual e só então aplica a alteração
+pub enum Conditional<T> {
+    Applied(T), // 200 com a nova versão
+    Stale(T),   // 412 com a representação atual, para o cliente refazer a edição
+}
+
+impl<T: Serialize + Versioned> IntoResponse for Conditional<T> {
+    fn into_response(self) -> Response {
+        match self {
+            Conditional::Applied(updated) => Tagged(updated).into_response(),
+            Conditional::Stale(current) => (StatusCode::PRECONDITION_FAILED, Tagged(current)).into_response(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_if_match_strong_comparison() {
+        assert!(IfMatch::parse("*").matches(7));
+        assert!(IfMatch::parse("\"3\"").matches(3));
+        assert!(IfMatch::parse("\"2\", \"3\"").matches(3));
+        assert!(!IfMatch::parse("\"2\"").matches(3));
+        assert!(!IfMatch::parse("W/\"3\"").matches(3));
+        assert!(!IfMatch::parse("3").matches(3));
+    }
+}
@@ -1,21 +1,44 @@
 use std::env;
+use std::net::IpAddr;
+use std::time::Duration;
+use chrono::{FixedOffset, NaiveDate};
 use dotenvy::dotenv;
 use tracing::error;
-use log4rs;
+
+use crate::logging::LogFormat;
 
 #[derive(Clone)]
 pub struct Config {
     pub database_url: String,
     pub secret_key: String,
+    pub log_level: String,          // Filtro do `tracing` (ex: "info,scheduling=debug")
+    pub log_format: LogFormat,      // "text" ou "json"
+    pub log_file: Option<String>,   // Arquivo de log (vazio desativa)
+    pub shutdown_grace: Duration,         // Espera entre derrubar o readiness e parar de aceitar conexões
+    pub shutdown_drain_timeout: Duration, // Tempo máximo para concluir requisições em andamento
+    pub calendar_utc_offset: FixedOffset, // Fuso do salão, usado para converter horários UTC de calendários importados
+    pub calendar_sync_interval: Duration, // Intervalo de releitura dos calendários externos
+    pub sms_gateway_url: Option<String>,       // Gateway HTTP de SMS (vazio desativa o canal)
+    pub sms_gateway_token: Option<String>,
+    pub smtp_server: Option<String>,           // "host:porta" do relay SMTP (vazio desativa o canal)
+    pub smtp_from: String,
+    pub whatsapp_webhook_url: Option<String>,  // Webhook de envio do WhatsApp (vazio desativa o canal)
+    pub whatsapp_token: Option<String>,
+    pub public_base_url: String,               // URL pública da API (links enviados aos clientes)
+    pub job_poll_interval: Duration,           // Intervalo de polling da fila de jobs
+    pub outbox_poll_interval: Duration,        // Intervalo de polling do outbox de eventos de domínio
+    pub inbound_message_token: Option<String>, // Segredo do webhook de respostas SMS/WhatsApp (vazio desativa)
+    pub metrics_token: Option<String>,         // Bearer exigido em `/metrics` (vazio desativa o endpoint)
+    pub trusted_proxies: Vec<IpAddr>,          // Proxies reversos cujos `X-Forwarded-For`/`X-Real-IP` são aceitos
+    pub legacy_api_deprecated_at: NaiveDate,   // Desde quando as rotas sem versão (aliases de `/v1`) são obsoletas
+    pub legacy_api_sunset: NaiveDate,          // Data prevista para remover as rotas sem versão
+    pub soft_delete_retention_days: i64,       // Dias até registros excluídos logicamente serem removidos de vez
 }
 
 impl Config {
     pub fn from_env() -> Result<Self, String> {
         dotenv().ok();
 
-        // ✅ Configuração de log com `log4rs`
-        log4rs::init_file("log4rs.yaml", Default::default()).unwrap();
-
         let database_url = env::var("DATABASE_URL").map_err(|_| {
             error!("❌ DATABASE_URL must be set in the environment");
             "DATABASE_URL must be set".to_string()
@@ -26,9 +49,167 @@ impl Config {
             "SECRET_KEY must be set".to_string()
         })?;
 
+        // ✅ Configuração do pipeline de logs
+        let log_level = env::var("LOG_LEVEL")
+            .or_else(|_| env::var("RUST_LOG"))
+            .unwrap_or_else(|_| "info,scheduling=debug".to_string());
+
+        let log_format = env::var("LOG_FORMAT")
+            .unwrap_or_else(|_| "text".to_string())
+            .parse::<LogFormat>()?;
+
+        let log_file = match env::var("LOG_FILE") {
+            Ok(path) if path.trim().is_empty() => None,
+            Ok(path) => Some(path),
+            Err(_) => Some("logs/app.log".to_string()),
+        };
+
+        let shutdown_grace = env::var("SHUTDOWN_GRACE_SECS")
+            .ok()
+            .map(|secs| secs.parse::<u64>().map_err(|_| "SHUTDOWN_GRACE_SECS must be a number".to_string()))
+            .transpose()?
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(5));
+
+        let shutdown_drain_timeout = env::var("SHUTDOWN_DRAIN_TIMEOUT_SECS")
+            .ok()
+            .map(|secs| secs.parse::<u64>().map_err(|_| "SHUTDOWN_DRAIN_TIMEOUT_SECS must be a number".to_string()))
+            .transpose()?
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(30));
+
+        // ✅ Importação de calendários externos (.ics)
+        let calendar_utc_offset = env::var("CALENDAR_UTC_OFFSET")
+            .unwrap_or_else(|_| "-03:00".to_string())
+            .parse::<FixedOffset>()
+            .map_err(|_| "CALENDAR_UTC_OFFSET must look like -03:00".to_string())?;
+
+        let calendar_sync_interval = env::var("CALENDAR_SYNC_INTERVAL_SECS")
+            .ok()
+            .map(|secs| secs.parse::<u64>().map_err(|_| "CALENDAR_SYNC_INTERVAL_SECS must be a number".to_string()))
+            .transpose()?
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(900));
+
+        // ✅ Canais de notificação
+        let sms_gateway_url = optional_env("SMS_GATEWAY_URL");
+        let sms_gateway_token = optional_env("SMS_GATEWAY_TOKEN");
+        let smtp_server = optional_env("SMTP_SERVER");
+        let smtp_from = optional_env("SMTP_FROM").unwrap_or_else(|| "agenda@localhost".to_string());
+        let whatsapp_webhook_url = optional_env("WHATSAPP_WEBHOOK_URL");
+        let whatsapp_token = optional_env("WHATSAPP_TOKEN");
+
+        // ✅ Fila de jobs e lembretes
+        let public_base_url = optional_env("PUBLIC_BASE_URL").unwrap_or_else(|| "http://127.0.0.1:3000".to_string());
+        let job_poll_interval = env::var("JOB_POLL_INTERVAL_SECS")
+            .ok()
+            .map(|secs| secs.parse::<u64>().map_err(|_| "JOB_POLL_INTERVAL_SECS must be a number".to_string()))
+            .transpose()?
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(5));
+        let outbox_poll_interval = env::var("OUTBOX_POLL_INTERVAL_MS")
+            .ok()
+            .map(|ms| ms.parse::<u64>().map_err(|_| "OUTBOX_POLL_INTERVAL_MS must be a number".to_string()))
+            .transpose()?
+            .map(Duration::from_millis)
+            .unwrap_or(Duration::from_millis(1000));
+        let inbound_message_token = optional_env("INBOUND_MESSAGE_TOKEN");
+        let metrics_token = optional_env("METRICS_TOKEN");
+        let trusted_proxies = optional_env("TRUSTED_PROXIES")
+            .map(|proxies| {
+                proxies
+                    .split(',')
+                    .map(|proxy| proxy.trim().parse::<IpAddr>().map_err(|_| format!("TRUSTED_PROXIES: IP inválido: {}", proxy.trim())))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        // ✅ Rotas sem versão (headers `Deprecation`/`Sunset`)
+        let legacy_api_deprecated_at = env_date("LEGACY_API_DEPRECATED_AT", "2026-10-19")?;
+        let legacy_api_sunset = env_date("LEGACY_API_SUNSET", "2027-04-19")?;
+
+        // ✅ Exclusão lógica (retenção antes da remoção definitiva)
+        let soft_delete_retention_days = env::var("SOFT_DELETE_RETENTION_DAYS")
+            .ok()
+            .map(|days| days.parse::<i64>().map_err(|_| "SOFT_DELETE_RETENTION_DAYS must be a number".to_string()))
+            .transpose()?
+            .unwrap_or(90);
+        if soft_delete_retention_days < 1 {
+            return Err("SOFT_DELETE_RETENTION_DAYS must be at least 1".to_string());
+        }
+
         Ok(Self {
             database_url,
             secret_key,
+            log_level,
+            log_format,
+            log_file,
+            shutdown_grace,
+            shutdown_drain_timeout,
+            calendar_utc_offset,
+            calendar_sync_interval,
+            sms_gateway_url,
+            sms_gateway_token,
+            smtp_server,
+            smtp_from,
+            whatsapp_webhook_url,
+            whatsapp_token,
+            public_base_url,
+            job_poll_interval,
+            outbox_poll_interval,
+            inbound_message_token,
+            metrics_token,
+            trusted_proxies,
+            legacy_api_deprecated_at,
+            legacy_api_sunset,
+            soft_delete_retention_days,
         })
     }
-}
\ No newline at end of file
+}
+
+/// 🔹 Data `YYYY-MM-DD` com valor padrão
+fn env_date(name: &str, default: &str) -> Result<NaiveDate, String> {
+    optional_env(name)
+        .unwrap_or_else(|| default.to_string())
+        .parse::<NaiveDate>()
+        .map_err(|_| format!("{} must look like YYYY-MM-DD", name))
+}
+
+/// 🔹 Variável opcional: ausente ou vazia → `None`
+fn optional_env(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+#[cfg(test)]
+impl Config {
+    /// ✅ Configuração fixa para testes (sem ler o ambiente)
+    pub fn for_tests() -> Self {
+        Self {
+            database_url: "postgres://localhost/scheduling_test".to_string(),
+            secret_key: "segredo-de-teste".to_string(),
+            log_level: "info".to_string(),
+            log_format: LogFormat::Text,
+            log_file: None,
+            shutdown_grace: Duration::ZERO,
+            shutdown_drain_timeout: Duration::from_secs(1),
+            calendar_utc_offset: FixedOffset::west_opt(3 * 3600).unwrap(),
+            calendar_sync_interval: Duration::from_secs(900),
+            sms_gateway_url: Some("http://127.0.0.1:9/sms".to_string()),
+            sms_gateway_token: None,
+            smtp_server: None,
+            smtp_from: "agenda@localhost".to_string(),
+            whatsapp_webhook_url: None,
+            whatsapp_token: None,
+            public_base_url: "http://127.0.0.1:3000".to_string(),
+            job_poll_interval: Duration::from_secs(5),
+            outbox_poll_interval: Duration::from_millis(1000),
+            inbound_message_token: None,
+            metrics_token: Some("token-de-metricas".to_string()),
+            trusted_proxies: vec![IpAddr::from([10, 0, 0, 2])],
+            legacy_api_deprecated_at: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+            legacy_api_sunset: NaiveDate::from_ymd_opt(2027, 4, 19).unwrap(),
+            soft_delete_retention_days: 90,
+        }
+    }
+}
@@ -1,13 +1,19 @@
 use diesel::pg::PgConnection;
 use diesel::r2d2::{ConnectionManager, Pool as R2D2Pool};
 use crate::config::Config;
+use crate::telemetry::PoolMetrics;
 use std::sync::Arc;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations};
 
 pub type Pool = R2D2Pool<ConnectionManager<PgConnection>>;
 
+/// 🔹 Migrações embutidas no binário (usadas pelo readiness check)
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
 pub fn init_db(config: &Config) -> Arc<Pool> {
     let manager = ConnectionManager::<PgConnection>::new(&config.database_url);
     let pool = R2D2Pool::builder()
+        .event_handler(Box::new(PoolMetrics)) // ✅ Métricas de espera/timeout do pool
         .build(manager)
         .expect("Failed to create pool.");
 
@@ -0,0 +1,50 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::middleware::request_id::current_request_id;
+
+/// 🔹 Erro padrão da API → `{ "error": "...", "request_id": "..." }`
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+/// 🔹 Corpo JSON retornado para o cliente
+#[derive(Debug, Serialize)]
+struct ApiErrorBody {
+    error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// ✅ Compatibilidade com os handlers que retornam `(StatusCode, String)`
+impl From<(StatusCode, String)> for ApiError {
+    fn from((status, message): (StatusCode, String)) -> Self {
+        Self::new(status, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ApiErrorBody {
+            error: self.message,
+            request_id: current_request_id(),
+        };
+
+        (self.status, Json(body)).into_response()
+    }
+}
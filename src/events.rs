@@ -0,0 +1,273 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::models::appointment::Appointment;
+use crate::models::availability::Availability;
+use crate::models::reservation::Reservation;
+
+/// 🔹 Eventos pendentes por assinante antes de os mais antigos serem descartados
+const BUS_CAPACITY: usize = 1024;
+
+/// 🔹 O que aconteceu com o agendamento/reserva
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Created,
+    Confirmed,
+    Rescheduled,
+    Canceled,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Created => "created",
+            EventKind::Confirmed => "confirmed",
+            EventKind::Rescheduled => "rescheduled",
+            EventKind::Canceled => "canceled",
+        }
+    }
+
+    /// ✅ Evento gerado por uma atualização (status e/ou horário); `None` se nada relevante mudou
+    pub fn from_transition(
+        previous_status: &str,
+        previous_time: NaiveDateTime,
+        status: &str,
+        time: NaiveDateTime,
+    ) -> Option<Self> {
+        if status != previous_status {
+            match status {
+                "canceled" => return Some(EventKind::Canceled),
+                "confirmed" => return Some(EventKind::Confirmed),
+                _ => {}
+            }
+        }
+        (time != previous_time && status != "canceled").then_some(EventKind::Rescheduled)
+    }
+}
+
+/// 🔹 Tipo de registro afetado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookingKind {
+    Appointment,
+    Reservation,
+}
+
+impl BookingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BookingKind::Appointment => "appointment",
+            BookingKind::Reservation => "reservation",
+        }
+    }
+}
+
+/// 🔹 Evento de domínio emitido pelos handlers de agendamentos e reservas (gravado no outbox)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainEvent {
+    pub kind: EventKind,
+    pub booking: BookingKind,
+    pub booking_id: Uuid,
+    pub user_id: Uuid,                 // Cliente dono do agendamento/reserva
+    pub professional_id: Option<Uuid>, // Apenas agendamentos têm profissional
+    pub service: String,               // Nome do serviço
+    pub starts_at: NaiveDateTime,
+    pub occurred_at: NaiveDateTime,
+}
+
+impl DomainEvent {
+    pub fn from_appointment(kind: EventKind, appointment: &Appointment, service: &str) -> Self {
+        Self {
+            kind,
+            booking: BookingKind::Appointment,
+            booking_id: appointment.id,
+            user_id: appointment.client_id,
+            professional_id: Some(appointment.professional_id),
+            service: service.to_string(),
+            starts_at: appointment.appointment_time,
+            occurred_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    pub fn from_reservation(kind: EventKind, reservation: &Reservation) -> Self {
+        Self {
+            kind,
+            booking: BookingKind::Reservation,
+            booking_id: reservation.id,
+            user_id: reservation.user_id,
+            professional_id: None,
+            service: reservation.service.clone(),
+            starts_at: reservation.appointment_time,
+            occurred_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    /// ✅ Nome do evento no formato `<registro>.<tipo>` (ex: `appointment.canceled`)
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.booking.as_str(), self.kind.as_str())
+    }
+}
+
+/// 🔹 Registro da agenda alterado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resource {
+    Appointment,
+    Reservation,
+    Availability,
+}
+
+/// 🔹 Tipo de alteração
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeAction {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// 🔹 Alteração de horários da agenda, enviada ao vivo (SSE) para quem acompanha a agenda
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarChange {
+    pub resource: Resource,
+    pub action: ChangeAction,
+    pub id: Option<Uuid>,              // Omitido para quem não pode ver o registro
+    pub professional_id: Option<Uuid>, // Reservas não têm profissional
+    pub user_id: Option<Uuid>,         // Cliente dono (omitido para quem não pode ver)
+    pub starts_at: NaiveDateTime,
+    pub ends_at: Option<NaiveDateTime>,
+    pub previous_starts_at: Option<NaiveDateTime>, // Horário anterior em remarcações
+    pub occurred_at: NaiveDateTime,
+}
+
+impl CalendarChange {
+    pub fn appointment(action: ChangeAction, appointment: &Appointment, previous: Option<&Appointment>) -> Self {
+        Self {
+            resource: Resource::Appointment,
+            action,
+            id: Some(appointment.id),
+            professional_id: Some(appointment.professional_id),
+            user_id: Some(appointment.client_id),
+            starts_at: appointment.appointment_time,
+            ends_at: None,
+            previous_starts_at: previous
+                .map(|previous| previous.appointment_time)
+                .filter(|time| *time != appointment.appointment_time),
+            occurred_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    pub fn reservation(action: ChangeAction, reservation: &Reservation, previous: Option<&Reservation>) -> Self {
+        Self {
+            resource: Resource::Reservation,
+            action,
+            id: Some(reservation.id),
+            professional_id: None,
+            user_id: Some(reservation.user_id),
+            starts_at: reservation.appointment_time,
+            ends_at: None,
+            previous_starts_at: previous
+                .map(|previous| previous.appointment_time)
+                .filter(|time| *time != reservation.appointment_time),
+            occurred_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    pub fn availability(action: ChangeAction, availability: &Availability, previous: Option<&Availability>) -> Self {
+        let starts_at = availability.date.and_time(availability.start_time);
+        Self {
+            resource: Resource::Availability,
+            action,
+            id: Some(availability.id),
+            professional_id: Some(availability.professional_id),
+            user_id: None,
+            starts_at,
+            ends_at: Some(availability.date.and_time(availability.end_time)),
+            previous_starts_at: previous
+                .map(|previous| previous.date.and_time(previous.start_time))
+                .filter(|time| *time != starts_at),
+            occurred_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    /// ✅ Nome do evento SSE (ex: `appointment.updated`)
+    pub fn name(&self) -> String {
+        let resource = match self.resource {
+            Resource::Appointment => "appointment",
+            Resource::Reservation => "reservation",
+            Resource::Availability => "availability",
+        };
+        let action = match self.action {
+            ChangeAction::Created => "created",
+            ChangeAction::Updated => "updated",
+            ChangeAction::Deleted => "deleted",
+        };
+        format!("{}.{}", resource, action)
+    }
+
+    /// ✅ A alteração afeta o dia (horário atual ou anterior)?
+    pub fn touches_date(&self, date: NaiveDate) -> bool {
+        self.starts_at.date() == date || self.previous_starts_at.is_some_and(|previous| previous.date() == date)
+    }
+
+    /// ✅ Versão sem identificação do registro e do cliente (para quem só pode ver que o horário mudou)
+    pub fn redacted(&self) -> Self {
+        Self {
+            id: None,
+            user_id: None,
+            ..self.clone()
+        }
+    }
+}
+
+/// 🔹 Barramento em memória: handlers publicam alterações da agenda depois do commit, streams SSE assinam
+/// (entrega "no máximo uma vez"; eventos de domínio confiáveis passam pelo outbox)
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<CalendarChange>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(BUS_CAPACITY);
+        Self { sender }
+    }
+
+    /// ✅ Publica a alteração (sem assinantes, ela é apenas descartada)
+    pub fn publish(&self, change: CalendarChange) {
+        debug!(event = %change.name(), "📣 Alteração na agenda");
+        let _ = self.sender.send(change);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<CalendarChange> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_event_kind_from_transition() {
+        let at = NaiveDate::from_ymd_opt(2025, 5, 10).unwrap().and_hms_opt(14, 0, 0).unwrap();
+        let later = at + chrono::Duration::hours(2);
+
+        assert_eq!(EventKind::from_transition("pending", at, "confirmed", at), Some(EventKind::Confirmed));
+        assert_eq!(EventKind::from_transition("confirmed", at, "canceled", later), Some(EventKind::Canceled));
+        assert_eq!(EventKind::from_transition("confirmed", at, "confirmed", later), Some(EventKind::Rescheduled));
+        assert_eq!(EventKind::from_transition("pending", at, "completed", at), None);
+        assert_eq!(EventKind::from_transition("pending", at, "pending", at), None);
+    }
+}
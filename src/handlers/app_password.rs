@@ -0,0 +1,117 @@
+use axum::{
+    extract::{Extension, Json, Path},
+    http::StatusCode,
+};
+use diesel::prelude::*;
+use rand::{distributions::Alphanumeric, rngs::OsRng, Rng};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    db::Pool,
+    middleware::auth_middleware::Claims,
+    models::app_password::{AppPassword, NewAppPassword},
+    schema::app_passwords,
+    services::auth_service::hash_password,
+};
+
+const PASSWORD_LENGTH: usize = 24;
+
+/// 🔹 Corpo do POST `/users/:id/app-passwords`
+#[derive(Deserialize)]
+pub struct CreateAppPasswordRequest {
+    pub name: String,
+}
+
+/// 🔹 Resposta da criação: a senha em texto puro só é exibida aqui
+#[derive(Serialize)]
+pub struct CreatedAppPassword {
+    #[serde(flatten)]
+    pub app_password: AppPassword,
+    pub password: String,
+}
+
+/// 🔹 Endpoint POST `/users/:id/app-passwords` (self ou admin)
+/// Gera uma senha aleatória para clientes CalDAV; apenas o hash é armazenado.
+pub async fn create_app_password(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(target_id): Path<Uuid>,
+    Json(payload): Json<CreateAppPasswordRequest>,
+) -> Result<(StatusCode, Json<CreatedAppPassword>), (StatusCode, String)> {
+    authorize(&claims, target_id)?;
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "name é obrigatório".to_string()));
+    }
+
+    let password: String = OsRng.sample_iter(&Alphanumeric).take(PASSWORD_LENGTH).map(char::from).collect();
+    let password_hash = hash_password(&password).map_err(internal_error)?;
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    let app_password = diesel::insert_into(app_passwords::table)
+        .values(&NewAppPassword {
+            user_id: target_id,
+            name: name.to_string(),
+            password_hash,
+        })
+        .get_result::<AppPassword>(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok((StatusCode::CREATED, Json(CreatedAppPassword { app_password, password })))
+}
+
+/// 🔹 Endpoint GET `/users/:id/app-passwords` (self ou admin)
+pub async fn list_app_passwords(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(target_id): Path<Uuid>,
+) -> Result<Json<Vec<AppPassword>>, (StatusCode, String)> {
+    authorize(&claims, target_id)?;
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let passwords = app_passwords::table
+        .filter(app_passwords::user_id.eq(target_id))
+        .order(app_passwords::created_at.asc())
+        .load::<AppPassword>(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok(Json(passwords))
+}
+
+/// 🔹 Endpoint DELETE `/users/:id/app-passwords/:password_id` (self ou admin)
+pub async fn delete_app_password(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path((target_id, password_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    authorize(&claims, target_id)?;
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let deleted = diesel::delete(
+        app_passwords::table
+            .find(password_id)
+            .filter(app_passwords::user_id.eq(target_id)),
+    )
+    .execute(&mut conn)
+    .map_err(internal_error)?;
+
+    if deleted == 0 {
+        return Err((StatusCode::NOT_FOUND, "Senha de aplicativo não encontrada".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn authorize(claims: &Claims, target_id: Uuid) -> Result<(), (StatusCode, String)> {
+    if claims.sub != target_id.to_string() && claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    Ok(())
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
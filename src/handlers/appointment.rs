@@ -10,76 +10,393 @@ use uuid::Uuid;
 use chrono::NaiveDateTime;
 
 use crate::{
+    concurrency::{Conditional, IfMatch, Tagged},
+    config::Config,
+    middleware::{audit::Audit, auth_middleware::Claims},
     db::Pool,
+    events::{CalendarChange, ChangeAction, DomainEvent, EventBus, EventKind},
     models::appointment::{Appointment, NewAppointment, UpdateAppointment},
+    pagination::{keyset, ListParams, Page, SortOrder},
     schema::appointments::dsl::*,
-    schema::appointments,
+    schema::{professionals, services},
+    services::{busy_block_service, job_queue, outbox, reminder_service},
+    telemetry,
 };
 
 /// 🔹 Cria um novo agendamento
 pub async fn create_appointment(
     Extension(pool): Extension<Arc<Pool>>,  // Usando Arc<Pool> para garantir que a pool seja compartilhada
+    Extension(config): Extension<Arc<Config>>,  // Fuso do salão (horário dos lembretes)
+    Extension(events): Extension<EventBus>,  // Alterações da agenda ao vivo (SSE)
     Json(payload): Json<NewAppointment>,  // Recebendo dados de agendamento
-) -> Result<Json<Appointment>, (StatusCode, String)> {
+) -> Result<(Audit, Json<Appointment>), (StatusCode, String)> {
     // Obtendo conexão do pool
     let mut conn = pool.get().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    // Criando novo agendamento
-    let new_appointment = diesel::insert_into(appointments)
-        .values(&payload)
-        .get_result::<Appointment>(&mut conn)
+    let service_name = check_slot(&mut conn, payload.service_id, payload.professional_id, payload.appointment_time)?;
+
+    // Criando novo agendamento junto com os lembretes e o evento (outbox)
+    let new_appointment = conn
+        .transaction(|conn| insert_booking(conn, &config, &payload, &service_name))
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    Ok(Json(new_appointment))  // Retorna o agendamento criado
+    telemetry::appointment_created();
+    events.publish(CalendarChange::appointment(ChangeAction::Created, &new_appointment, None));
+    Ok((Audit::created("appointments", new_appointment.id, &new_appointment), Json(new_appointment)))  // Retorna o agendamento criado
 }
 
-/// 🔹 Lista todos os agendamentos de um cliente
+/// 🔹 Lista os agendamentos de um cliente, paginado
+/// Filtros: `from`/`to`, `status`, `professional_id`, `service_id`, `include_deleted` (admin). Ordenação: `appointment_time` (padrão, asc).
 pub async fn list_appointments_by_client(
     Extension(pool): Extension<Arc<Pool>>,  // Usando Arc<Pool> para garantir que a pool seja compartilhada
+    Extension(claims): Extension<Claims>,
     Path(client_id_from_path): Path<Uuid>,  // Mudando o nome da variável para evitar conflito
-) -> Result<Json<Vec<Appointment>>, (StatusCode, String)> {
+    params: ListParams,
+) -> Result<Json<Page<Appointment>>, (StatusCode, String)> {
+    let sort = params.accept(
+        &["from", "to", "status", "professional_id", "service_id", "include_deleted"],
+        &["appointment_time"],
+        SortOrder::Asc,
+    )?;
+    let include_deleted = params.filters.include_deleted.unwrap_or(false);
+    if include_deleted && claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "`include_deleted` é restrito a administradores".to_string()));
+    }
+
     // Obtendo conexão do pool
     let mut conn = pool.get().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    // Buscando todos os agendamentos para o cliente
-    let appointments_list = appointments
-        .filter(client_id.eq(client_id_from_path))  // Usando o client_id extraído do path
+    // Agendamentos do cliente (extraído do path) com os filtros pedidos
+    let filtered = || {
+        let mut query = appointments.filter(client_id.eq(client_id_from_path)).into_boxed();
+        if let Some(start) = params.filters.starts_at() {
+            query = query.filter(appointment_time.ge(start));
+        }
+        if let Some(end) = params.filters.ends_before() {
+            query = query.filter(appointment_time.lt(end));
+        }
+        if let Some(wanted) = params.filters.status.clone() {
+            query = query.filter(status.eq(wanted));
+        }
+        if let Some(wanted) = params.filters.professional_id {
+            query = query.filter(professional_id.eq(wanted));
+        }
+        if let Some(wanted) = params.filters.service_id {
+            query = query.filter(service_id.eq(wanted));
+        }
+        if !include_deleted {
+            query = query.filter(deleted_at.is_null());
+        }
+        query
+    };
+
+    let total = match params.cursor {
+        None => Some(filtered().count().get_result::<i64>(&mut conn).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?),
+        Some(_) => None,
+    };
+    let appointments_list = keyset!(filtered(), appointment_time, NaiveDateTime, id, params, sort)
         .load::<Appointment>(&mut conn)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    Ok(Json(appointments_list))  // Retorna a lista de agendamentos
+    Ok(Json(Page::new(appointments_list, &params, sort, total, |appointment| {
+        (serde_json::json!(appointment.appointment_time), appointment.id)
+    })))
+}
+
+/// 🔹 Busca um agendamento pelo ID (com `ETag`, usado no `If-Match` da atualização)
+pub async fn get_appointment(
+    Extension(pool): Extension<Arc<Pool>>,  // Usando Arc<Pool> para garantir que a pool seja compartilhada
+    Path(appointment_id): Path<Uuid>,  // Obtém o appointment_id a partir do path
+) -> Result<Tagged<Appointment>, (StatusCode, String)> {
+    // Obtendo conexão do pool
+    let mut conn = pool.get().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let appointment = appointments
+        .filter(id.eq(appointment_id))
+        .filter(deleted_at.is_null())
+        .first::<Appointment>(&mut conn)
+        .map_err(|_| (StatusCode::NOT_FOUND, "Agendamento não encontrado".to_string()))?;
+
+    Ok(Tagged(appointment))
 }
 
 /// 🔹 Atualiza o status de um agendamento
+/// Exige `If-Match` com a versão lida; se outra pessoa alterou antes, responde 412 com o agendamento atual.
+/// Remarcações passam pelas mesmas validações da criação (serviço/profissional ativos, compromissos externos).
 pub async fn update_appointment(
     Extension(pool): Extension<Arc<Pool>>,  // Usando Arc<Pool> para garantir que a pool seja compartilhada
+    Extension(config): Extension<Arc<Config>>,  // Fuso do salão (horário dos lembretes)
+    Extension(events): Extension<EventBus>,  // Alterações da agenda ao vivo (SSE)
     Path(appointment_id): Path<Uuid>,  // Obtém o appointment_id a partir do path
-    Json(update): Json<UpdateAppointment>,  // Dados para atualização
-) -> Result<Json<Appointment>, (StatusCode, String)> {
+    if_match: IfMatch,  // Versão que o cliente editou
+    Json(mut update): Json<UpdateAppointment>,  // Dados para atualização
+) -> Result<(Option<Audit>, Conditional<Appointment>), (StatusCode, String)> {
     // Obtendo conexão do pool
     let mut conn = pool.get().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    // Atualizando o agendamento no banco
-    let updated_appointment = diesel::update(appointments.filter(id.eq(appointment_id)))  // Filtra pelo ID
-        .set(update)  // Atualiza com os dados recebidos
-        .get_result::<Appointment>(&mut conn)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let new_status = update.status.clone();
 
-    Ok(Json(updated_appointment))  // Retorna o agendamento atualizado
+    // Atualizando o agendamento no banco; remarcações e cancelamentos refazem os lembretes
+    let (existing, updated_appointment) = conn
+        .transaction(|conn| {
+            // Agendamento atual (travado até o fim da transação) para conferir a versão e versionar o evento no iCalendar
+            let existing = appointments
+                .filter(id.eq(appointment_id))
+                .filter(deleted_at.is_null())
+                .for_update()
+                .first::<Appointment>(conn)?;
+            if !if_match.matches(existing.version) {
+                return Ok(Ok((existing, None)));
+            }
+            if let Some(new_time) = update.appointment_time.filter(|time| *time != existing.appointment_time) {
+                if let Err(rejected) = check_slot(conn, existing.service_id, existing.professional_id, new_time) {
+                    return Ok(Err(rejected));
+                }
+            }
+            update.bump_sequence(&existing);
+
+            let updated = diesel::update(appointments.filter(id.eq(appointment_id)))  // Filtra pelo ID
+                .set(update)  // Atualiza com os dados recebidos
+                .get_result::<Appointment>(conn)?;
+
+            let rescheduled = updated.appointment_time != existing.appointment_time;
+            if rescheduled {
+                reminder_service::reset_attendance(conn, updated.id)?;
+            }
+            if rescheduled || updated.status != existing.status {
+                reminder_service::schedule_reminders(conn, &updated, config.calendar_utc_offset, chrono::Utc::now().naive_utc())?;
+            }
+
+            // Confirmação, cancelamento ou remarcação viram notificação para o cliente
+            if let Some(kind) = EventKind::from_transition(
+                &existing.status,
+                existing.appointment_time,
+                &updated.status,
+                updated.appointment_time,
+            ) {
+                record_event(conn, kind, &updated)?;
+            }
+            Ok::<_, diesel::result::Error>(Ok((existing, Some(updated))))
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => (StatusCode::NOT_FOUND, "Agendamento não encontrado".to_string()),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })??;
+
+    // Versão desatualizada: nada foi alterado, o cliente recebe o agendamento atual
+    let Some(updated_appointment) = updated_appointment else {
+        return Ok((None, Conditional::Stale(existing)));
+    };
+
+    if let Some(new_status) = new_status {
+        telemetry::appointment_status_changed(&new_status);
+    }
+    events.publish(CalendarChange::appointment(ChangeAction::Updated, &updated_appointment, Some(&existing)));
+
+    // Remarcação fica destacada na trilha de auditoria
+    let action = if updated_appointment.appointment_time != existing.appointment_time { "reschedule" } else { "update" };
+    let audit = Audit::changed(action, "appointments", appointment_id, &existing, &updated_appointment);
+
+    Ok((Some(audit), Conditional::Applied(updated_appointment)))  // Retorna o agendamento atualizado
 }
 
-/// 🔹 Deleta um agendamento
+/// 🔹 Exclui um agendamento (exclusão lógica; os lembretes pendentes são cancelados)
 pub async fn delete_appointment(
     Extension(pool): Extension<Arc<Pool>>,  // Usando Arc<Pool> para garantir que a pool seja compartilhada
+    Extension(events): Extension<EventBus>,  // Alterações da agenda ao vivo (SSE)
     Path(appointment_id): Path<Uuid>,  // Obtém o appointment_id a partir do path
-) -> Result<StatusCode, (StatusCode, String)> {
+) -> Result<(Option<Audit>, StatusCode), (StatusCode, String)> {
     // Obtendo conexão do pool
     let mut conn = pool.get().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    // Deletando o agendamento do banco de dados
-    diesel::delete(appointments.filter(id.eq(appointment_id)))  // Filtra pelo ID do agendamento
-        .execute(&mut conn)
+    // Marcando o agendamento como excluído (e cancelando os lembretes pendentes)
+    let deleted = conn
+        .transaction(|conn| {
+            let deleted = diesel::update(appointments.filter(id.eq(appointment_id)).filter(deleted_at.is_null()))  // Filtra pelo ID do agendamento
+                .set(deleted_at.eq(chrono::Utc::now().naive_utc()))
+                .get_result::<Appointment>(conn)
+                .optional()?;
+            job_queue::cancel_pending(conn, reminder_service::REMINDER_JOB, appointment_id)?;
+
+            // Excluir um agendamento ativo equivale a cancelá-lo para o cliente
+            if let Some(deleted) = deleted.as_ref().filter(|deleted| deleted.status != "canceled") {
+                record_event(conn, EventKind::Canceled, deleted)?;
+            }
+            Ok::<_, diesel::result::Error>(deleted)
+        })
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    Ok(StatusCode::NO_CONTENT)  // Retorna o status de sucesso (204 No Content)
+    if let Some(deleted) = &deleted {
+        events.publish(CalendarChange::appointment(ChangeAction::Deleted, deleted, None));
+    }
+
+    let audit = deleted.map(|_| Audit::new("delete", "appointments", appointment_id));
+    Ok((audit, StatusCode::NO_CONTENT))  // Retorna o status de sucesso (204 No Content)
+}
+
+/// 🔹 Restaura um agendamento excluído (apenas admin): volta para a agenda e, se ativo, ganha novos lembretes
+pub async fn restore_appointment(
+    Extension(pool): Extension<Arc<Pool>>,  // Usando Arc<Pool> para garantir que a pool seja compartilhada
+    Extension(config): Extension<Arc<Config>>,  // Fuso do salão (horário dos lembretes)
+    Extension(events): Extension<EventBus>,  // Alterações da agenda ao vivo (SSE)
+    Extension(claims): Extension<Claims>,
+    Path(appointment_id): Path<Uuid>,  // Obtém o appointment_id a partir do path
+) -> Result<(Option<Audit>, Tagged<Appointment>), (StatusCode, String)> {
+    if claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    // Obtendo conexão do pool
+    let mut conn = pool.get().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let (appointment, restored) = conn
+        .transaction(|conn| {
+            let restored = diesel::update(appointments.filter(id.eq(appointment_id)).filter(deleted_at.is_not_null()))
+                .set(deleted_at.eq(None::<NaiveDateTime>))
+                .get_result::<Appointment>(conn)
+                .optional()?;
+            let Some(restored) = restored else {
+                // Não estava excluído: devolve o agendamento como está
+                return Ok((appointments.filter(id.eq(appointment_id)).first::<Appointment>(conn)?, false));
+            };
+
+            reminder_service::schedule_reminders(conn, &restored, config.calendar_utc_offset, chrono::Utc::now().naive_utc())?;
+            if restored.status != "canceled" {
+                record_event(conn, EventKind::Created, &restored)?;
+            }
+            Ok::<_, diesel::result::Error>((restored, true))
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => (StatusCode::NOT_FOUND, "Agendamento não encontrado".to_string()),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
+
+    if restored {
+        events.publish(CalendarChange::appointment(ChangeAction::Created, &appointment, None));
+    }
+
+    Ok((restored.then(|| Audit::new("restore", "appointments", appointment_id)), Tagged(appointment)))
+}
+
+/// 🔹 Confere se o serviço e o profissional estão ativos e se o horário não cai num compromisso externo.
+/// Devolve o nome do serviço (usado no evento do outbox).
+pub(crate) fn check_slot(
+    conn: &mut PgConnection,
+    wanted_service: Uuid,
+    wanted_professional: Uuid,
+    starts_at: NaiveDateTime,
+) -> Result<String, (StatusCode, String)> {
+    // Recusando horários bloqueados por compromissos externos do profissional
+    let (service_name, duration) = services::table
+        .find(wanted_service)
+        .filter(services::deleted_at.is_null())
+        .select((services::nome, services::duracao_min))
+        .first::<(String, i32)>(conn)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Serviço não encontrado".to_string()))?;
+    let professional_active = professionals::table
+        .find(wanted_professional)
+        .filter(professionals::deleted_at.is_null())
+        .count()
+        .get_result::<i64>(conn)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if professional_active == 0 {
+        return Err((StatusCode::BAD_REQUEST, "Profissional não encontrado".to_string()));
+    }
+    let end = starts_at + chrono::Duration::minutes(duration.into());
+    if busy_block_service::has_overlap(conn, wanted_professional, starts_at, end)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        return Err((StatusCode::CONFLICT, "Profissional ocupado neste horário".to_string()));
+    }
+    Ok(service_name)
+}
+
+/// 🔹 Grava o agendamento com os lembretes e o evento `appointment.created` (chamar dentro de uma transação)
+pub(crate) fn insert_booking(
+    conn: &mut PgConnection,
+    config: &Config,
+    payload: &NewAppointment,
+    service_name: &str,
+) -> QueryResult<Appointment> {
+    let appointment = diesel::insert_into(appointments)
+        .values(payload)
+        .get_result::<Appointment>(conn)?;
+    reminder_service::schedule_reminders(conn, &appointment, config.calendar_utc_offset, chrono::Utc::now().naive_utc())?;
+    outbox::record_event(conn, &DomainEvent::from_appointment(EventKind::Created, &appointment, service_name))?;
+    Ok(appointment)
+}
+
+/// 🔹 Grava o evento no outbox (na transação da alteração) com o nome do serviço
+pub(crate) fn record_event(conn: &mut PgConnection, kind: EventKind, appointment: &Appointment) -> QueryResult<Uuid> {
+    let service_name = services::table
+        .find(appointment.service_id)
+        .select(services::nome)
+        .first::<String>(conn)
+        .optional()?
+        .unwrap_or_default();
+    outbox::record_event(conn, &DomainEvent::from_appointment(kind, appointment, &service_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{busy_blocks, calendar_sources};
+    use crate::test_db::{self, fixtures};
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn test_reschedule_checks_slot_like_create() {
+        let Some(db) = test_db::setup() else { return };
+        let config = Arc::new(Config::for_tests());
+        let mut conn = db.pool.get().unwrap();
+        let client = fixtures::user(&mut conn, "client", "5511900000060");
+        let professional = fixtures::professional(&mut conn, "5511900000061");
+        let service = fixtures::service(&mut conn, 60);
+        let starts_at = (chrono::Utc::now() + Duration::days(2)).date_naive().and_hms_opt(10, 0, 0).unwrap();
+        let appointment_id = fixtures::appointment(&mut conn, client, professional, service, starts_at);
+
+        // Compromisso externo do profissional duas horas depois
+        let source = diesel::insert_into(calendar_sources::table)
+            .values((calendar_sources::professional_id.eq(professional), calendar_sources::kind.eq("upload")))
+            .returning(calendar_sources::id)
+            .get_result::<Uuid>(&mut conn)
+            .unwrap();
+        diesel::insert_into(busy_blocks::table)
+            .values((
+                busy_blocks::professional_id.eq(professional),
+                busy_blocks::source_id.eq(source),
+                busy_blocks::uid.eq("externo"),
+                busy_blocks::start_time.eq(starts_at + Duration::hours(2)),
+                busy_blocks::end_time.eq(starts_at + Duration::hours(3)),
+            ))
+            .execute(&mut conn)
+            .unwrap();
+
+        let reschedule = |hours: i64| UpdateAppointment { appointment_time: Some(starts_at + Duration::hours(hours)), status: None, sequence: None };
+        let busy = update_appointment(
+            Extension(db.pool.clone()),
+            Extension(config.clone()),
+            Extension(EventBus::new()),
+            Path(appointment_id),
+            IfMatch::Any,
+            Json(reschedule(2)),
+        )
+        .await;
+        assert_eq!(busy.err().map(|(code, _)| code), Some(StatusCode::CONFLICT));
+        let unchanged = appointments.find(appointment_id).first::<Appointment>(&mut conn).unwrap();
+        assert_eq!((unchanged.appointment_time, unchanged.sequence), (starts_at, 0));
+
+        let (_, applied) = update_appointment(
+            Extension(db.pool.clone()),
+            Extension(config.clone()),
+            Extension(EventBus::new()),
+            Path(appointment_id),
+            IfMatch::Any,
+            Json(reschedule(4)),
+        )
+        .await
+        .unwrap();
+        let Conditional::Applied(moved) = applied else { panic!("remarcação para horário livre deveria ser aplicada") };
+        assert_eq!((moved.appointment_time, moved.sequence), (starts_at + Duration::hours(4), 1));
+    }
 }
@@ -0,0 +1,169 @@
+use axum::{
+    extract::{Extension, Json, Path, Query},
+    http::StatusCode,
+    routing::{get, post},
+    Router,
+};
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::Pool,
+    events::{CalendarChange, ChangeAction, DomainEvent, EventBus, EventKind},
+    middleware::audit::Audit,
+    models::{appointment::Appointment, attendance_confirmation::AttendanceConfirmation},
+    schema::{appointments, attendance_confirmations, services, users},
+    services::auth_service::secrets_match,
+    services::outbox,
+    services::reminder_service::{confirm_attendance, is_confirmation_reply},
+};
+
+/// 🔹 Mensagem recebida do gateway de SMS/WhatsApp
+#[derive(Deserialize)]
+pub struct InboundReply {
+    pub from: String, // Telefone do cliente
+    pub text: String,
+}
+
+/// 🔹 Segredo do webhook via query string (`?token=...`)
+#[derive(Deserialize)]
+pub struct InboundQuery {
+    pub token: String,
+}
+
+/// 🔹 Resultado do processamento de uma resposta
+#[derive(Serialize)]
+pub struct ReplyOutcome {
+    pub confirmed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appointment_id: Option<Uuid>,
+}
+
+/// 🔹 Endpoint GET `/attendance/:token` (link enviado no lembrete)
+pub async fn confirm_by_link(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(events): Extension<EventBus>,
+    Path(token): Path<String>,
+) -> Result<(Audit, String), (StatusCode, String)> {
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let attendance = attendance_confirmations::table
+        .filter(attendance_confirmations::token.eq(&token))
+        .first::<AttendanceConfirmation>(&mut conn)
+        .optional()
+        .map_err(internal_error)?
+        .ok_or_else(invalid_link)?;
+
+    let appointment = confirm(&mut conn, &events, attendance.appointment_id)?.ok_or_else(invalid_link)?;
+
+    Ok((
+        Audit::new("attendance_confirm", "appointments", appointment.id),
+        format!("Presença confirmada para {}. Obrigado!", appointment.appointment_time.format("%d/%m/%Y às %H:%M")),
+    ))
+}
+
+/// 🔹 Endpoint POST `/attendance/replies?token=...` (webhook do gateway de SMS/WhatsApp)
+/// Uma resposta afirmativa ("SIM", "yes"...) confirma o próximo agendamento ainda não confirmado do remetente.
+pub async fn confirm_by_reply(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(events): Extension<EventBus>,
+    Query(query): Query<InboundQuery>,
+    Json(reply): Json<InboundReply>,
+) -> Result<(Option<Audit>, Json<ReplyOutcome>), (StatusCode, String)> {
+    match &config.inbound_message_token {
+        Some(expected) if secrets_match(expected, &query.token) => {}
+        Some(_) => return Err((StatusCode::UNAUTHORIZED, "Token inválido".to_string())),
+        None => return Err((StatusCode::NOT_FOUND, "Respostas por mensagem não estão habilitadas".to_string())),
+    }
+
+    let unmatched = (None, Json(ReplyOutcome { confirmed: false, appointment_id: None }));
+    if !is_confirmation_reply(&reply.text) {
+        return Ok(unmatched);
+    }
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    let digits: String = reply.from.chars().filter(char::is_ascii_digit).collect();
+    let phones = [reply.from.trim().to_string(), digits.clone(), format!("+{}", digits)];
+
+    // Horários dos agendamentos estão no fuso do salão
+    let local_now = Utc::now().naive_utc() + Duration::seconds(config.calendar_utc_offset.local_minus_utc().into());
+    let next = appointments::table
+        .inner_join(users::table.on(users::id.eq(appointments::client_id)))
+        .inner_join(attendance_confirmations::table)
+        .filter(users::phone.eq_any(&phones))
+        .filter(appointments::appointment_time.ge(local_now))
+        .filter(appointments::status.eq_any(["pending", "confirmed"]))
+        .filter(appointments::deleted_at.is_null())
+        .filter(attendance_confirmations::confirmed_at.is_null())
+        .order(appointments::appointment_time.asc())
+        .select(appointments::id)
+        .first::<Uuid>(&mut conn)
+        .optional()
+        .map_err(internal_error)?;
+
+    let Some(appointment_id) = next else {
+        return Ok(unmatched);
+    };
+    let confirmed = confirm(&mut conn, &events, appointment_id)?;
+
+    Ok((
+        confirmed.as_ref().map(|appointment| Audit::new("attendance_confirm", "appointments", appointment.id)),
+        Json(ReplyOutcome {
+            confirmed: confirmed.is_some(),
+            appointment_id: confirmed.map(|appointment| appointment.id),
+        }),
+    ))
+}
+
+/// 🔹 Confirma e grava `appointment.confirmed` no outbox quando o status muda (mesma transação);
+/// depois do commit, avisa a agenda ao vivo
+fn confirm(conn: &mut PgConnection, events: &EventBus, appointment_id: Uuid) -> Result<Option<Appointment>, (StatusCode, String)> {
+    let confirmed = conn.transaction(|conn| {
+        let Some((appointment, status_changed)) = confirm_attendance(conn, appointment_id, Utc::now().naive_utc())? else {
+            return Ok(None);
+        };
+
+        if status_changed {
+            let service_name = services::table
+                .find(appointment.service_id)
+                .select(services::nome)
+                .first::<String>(conn)
+                .optional()?
+                .unwrap_or_default();
+            outbox::record_event(conn, &DomainEvent::from_appointment(EventKind::Confirmed, &appointment, &service_name))?;
+        }
+        Ok::<_, diesel::result::Error>(Some((appointment, status_changed)))
+    })
+    .map_err(internal_error)?;
+
+    let Some((appointment, status_changed)) = confirmed else {
+        return Ok(None);
+    };
+    if status_changed {
+        events.publish(CalendarChange::appointment(ChangeAction::Updated, &appointment, None));
+    }
+    Ok(Some(appointment))
+}
+
+/// ✅ Rotas públicas de confirmação de presença (token no link / segredo do webhook)
+pub fn attendance_router(pool: Arc<Pool>, config: Arc<Config>) -> Router {
+    Router::new()
+        .route("/attendance/replies", post(confirm_by_reply))
+        .route("/attendance/:token", get(confirm_by_link))
+        .layer(Extension(pool))
+        .layer(Extension(config))
+}
+
+fn invalid_link() -> (StatusCode, String) {
+    (StatusCode::NOT_FOUND, "Link de confirmação inválido ou expirado".to_string())
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
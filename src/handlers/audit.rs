@@ -0,0 +1,67 @@
+use axum::{
+    extract::{Extension, Json},
+    http::StatusCode,
+};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use std::sync::Arc;
+
+use crate::{
+    db::Pool,
+    middleware::auth_middleware::Claims,
+    models::audit_event::AuditEvent,
+    pagination::{keyset, ListParams, Page, SortOrder},
+    schema::audit_events::dsl::*,
+};
+
+/// 🔹 Consulta a trilha de auditoria (apenas admin), paginada, mais recentes primeiro
+/// Filtros: `actor_id`, `entity_type`, `entity_id`, `action`, `from`/`to` (data do registro). Ordenação: `created_at`.
+pub async fn list_audit_events(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    params: ListParams,
+) -> Result<Json<Page<AuditEvent>>, (StatusCode, String)> {
+    if claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    let sort = params.accept(&["actor_id", "entity_type", "entity_id", "action", "from", "to"], &["created_at"], SortOrder::Desc)?;
+
+    let filtered = || {
+        let mut query = audit_events.into_boxed();
+        if let Some(actor) = params.filters.actor_id {
+            query = query.filter(actor_id.eq(actor));
+        }
+        if let Some(entity) = &params.filters.entity_type {
+            query = query.filter(entity_type.eq(entity.clone()));
+        }
+        if let Some(entity) = params.filters.entity_id {
+            query = query.filter(entity_id.eq(entity));
+        }
+        if let Some(name) = &params.filters.action {
+            query = query.filter(action.eq(name.clone()));
+        }
+        if let Some(start) = params.filters.starts_at() {
+            query = query.filter(created_at.ge(start));
+        }
+        if let Some(end) = params.filters.ends_before() {
+            query = query.filter(created_at.lt(end));
+        }
+        query
+    };
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    let total = match params.cursor {
+        None => Some(filtered().count().get_result::<i64>(&mut conn).map_err(internal_error)?),
+        Some(_) => None,
+    };
+    let results = keyset!(filtered(), created_at, NaiveDateTime, id, params, sort)
+        .load::<AuditEvent>(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok(Json(Page::new(results, &params, sort, total, |event| (serde_json::json!(event.created_at), event.id))))
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
@@ -6,7 +6,9 @@ use diesel::prelude::*;
 use std::sync::Arc;
 use crate::db::Pool;
 use crate::config::Config;
+use crate::telemetry;
 use crate::services::auth_service::{hash_password, verify_password, generate_jwt};
+use crate::services::outbox;
 use crate::models::user::{User, NewUser};
 use crate::schema::users::dsl::*;
 use crate::middleware::auth_middleware::Claims;
@@ -65,9 +67,19 @@ pub async fn register_user(
         return Err((StatusCode::CONFLICT, "Usuário já cadastrado".to_string()));
     }
 
-    let saved_user: User = diesel::insert_into(users)
-        .values(&payload)
-        .get_result(&mut conn)
+    // Cadastro e evento `user.registered` (outbox) na mesma transação
+    let saved_user: User = conn
+        .transaction(|conn| {
+            let saved_user: User = diesel::insert_into(users).values(&payload).get_result(conn)?;
+            outbox::record(conn, "user.registered", serde_json::json!({
+                "id": saved_user.id,
+                "name": saved_user.name,
+                "phone": saved_user.phone,
+                "role": saved_user.role,
+                "created_at": saved_user.created_at,
+            }))?;
+            Ok::<_, diesel::result::Error>(saved_user)
+        })
         .map_err(|e| {
             error!("Falha no registro: {:?}", e);
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
@@ -89,10 +101,11 @@ pub async fn login_user(
         (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
     })?;
 
-    info!("Tentativa de login: {}", payload.phone);
+    info!("Tentativa de login recebida");
 
     let user = users
         .filter(phone.eq(&payload.phone))
+        .filter(deleted_at.is_null())  // Contas excluídas não entram
         .first::<User>(&mut conn)
         .optional()
         .map_err(|e| {
@@ -100,12 +113,14 @@ pub async fn login_user(
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
         })?
         .ok_or_else(|| {
-            error!("Telefone não encontrado: {}", payload.phone);
+            telemetry::login_attempt(false);
+            error!("Login recusado: telefone não cadastrado");
             (StatusCode::UNAUTHORIZED, "Credenciais inválidas".to_string())
         })?;
 
     if !verify_password(&user.password_hash, &payload.password) {
-        error!("Senha incorreta para: {}", payload.phone);
+        telemetry::login_attempt(false);
+        error!("Senha incorreta para usuário: {}", user.id);
         return Err((StatusCode::UNAUTHORIZED, "Credenciais inválidas".to_string()));
     }
 
@@ -114,7 +129,8 @@ pub async fn login_user(
         (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
     })?;
 
-    info!("Login bem-sucedido: {}", payload.phone);
+    telemetry::login_attempt(true);
+    info!("Login bem-sucedido: {}", user.id);
     Ok(Json(LoginResponse {
         token,
         user_id: user.id,
@@ -140,6 +156,7 @@ pub async fn me(
 
     let user = users
         .filter(id.eq(user_id))
+        .filter(deleted_at.is_null())
         .first::<User>(&mut conn)
         .map_err(|e| {
             error!("Usuário não encontrado: {} - {:?}", user_id, e);
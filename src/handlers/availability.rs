@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Extension, Json, Path},
+    extract::{Extension, Json, Path, Query},
     http::StatusCode,
     routing::{get, post, put, delete},
     Router,
@@ -7,17 +7,34 @@ use axum::{
 use diesel::prelude::*;
 use std::sync::Arc;
 use uuid::Uuid;
-use chrono::{NaiveDate, NaiveTime};
+use chrono::{NaiveDate, NaiveTime, NaiveDateTime, Duration};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     db::Pool,
+    events::{CalendarChange, ChangeAction, EventBus},
     models::availability::{Availability, NewAvailability, UpdateAvailability},
     schema::availabilities::dsl::*,
+    services::busy_block_service,
 };
 
+/// 🔹 Query `?date=YYYY-MM-DD` dos horários livres
+#[derive(Deserialize)]
+pub struct FreeQuery {
+    pub date: NaiveDate,
+}
+
+/// 🔹 Intervalo livre (disponibilidade menos agendamentos e compromissos externos)
+#[derive(Serialize)]
+pub struct FreeInterval {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
 // 🔹 Cria um novo horário disponível
 pub async fn create_availability(
     Extension(pool): Extension<Pool>,
+    Extension(events): Extension<EventBus>,
     Json(payload): Json<NewAvailability>,
 ) -> Result<Json<Availability>, (StatusCode, String)> {
     let mut conn = pool.get().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -27,6 +44,7 @@ pub async fn create_availability(
         .get_result::<Availability>(&mut conn)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    events.publish(CalendarChange::availability(ChangeAction::Created, &new_availability, None));
     Ok(Json(new_availability))
 }
 
@@ -45,33 +63,80 @@ pub async fn list_availabilities_by_professional(
     Ok(Json(availability_list))
 }
 
+// 🔹 Horários livres de um profissional numa data: disponibilidades menos agendamentos existentes e blocos ocupados importados
+pub async fn list_free_intervals(
+    Extension(pool): Extension<Arc<Pool>>,
+    Path(professional_uuid): Path<Uuid>,
+    Query(query): Query<FreeQuery>,
+) -> Result<Json<Vec<FreeInterval>>, (StatusCode, String)> {
+    let mut conn = pool.get().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let windows: Vec<(NaiveDateTime, NaiveDateTime)> = availabilities
+        .filter(professional_id.eq(professional_uuid))
+        .filter(date.eq(query.date))
+        .order(start_time.asc())
+        .load::<Availability>(&mut conn)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .iter()
+        .map(|window| (window.date.and_time(window.start_time), window.date.and_time(window.end_time)))
+        .collect();
+
+    let day_start = query.date.and_time(NaiveTime::MIN);
+    let day_end = day_start + Duration::days(1);
+    let mut busy = busy_block_service::busy_between(&mut conn, professional_uuid, day_start, day_end)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    busy.extend(
+        busy_block_service::booked_between(&mut conn, professional_uuid, day_start, day_end)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?,
+    );
+
+    let free = busy_block_service::subtract_intervals(&windows, &busy)
+        .into_iter()
+        .map(|(start, end)| FreeInterval { start, end })
+        .collect();
+
+    Ok(Json(free))
+}
+
 // 🔹 Atualiza um horário disponível
 pub async fn update_availability(
     Extension(pool): Extension<Pool>,
+    Extension(events): Extension<EventBus>,
     Path(availability_id): Path<Uuid>,
     Json(update): Json<UpdateAvailability>,
 ) -> Result<Json<Availability>, (StatusCode, String)> {
     let mut conn = pool.get().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    let existing = availabilities
+        .filter(id.eq(availability_id))
+        .first::<Availability>(&mut conn)
+        .map_err(|_| (StatusCode::NOT_FOUND, "Disponibilidade não encontrada".to_string()))?;
+
     let updated_availability = diesel::update(availabilities.filter(id.eq(availability_id)))
         .set(update)
         .get_result::<Availability>(&mut conn)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    events.publish(CalendarChange::availability(ChangeAction::Updated, &updated_availability, Some(&existing)));
     Ok(Json(updated_availability))
 }
 
 // 🔹 Deleta um horário disponível
 pub async fn delete_availability(
     Extension(pool): Extension<Pool>,
+    Extension(events): Extension<EventBus>,
     Path(availability_id): Path<Uuid>,
 ) -> Result<StatusCode, (StatusCode, String)> {
     let mut conn = pool.get().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    diesel::delete(availabilities.filter(id.eq(availability_id)))
-        .execute(&mut conn)
+    let deleted = diesel::delete(availabilities.filter(id.eq(availability_id)))
+        .get_result::<Availability>(&mut conn)
+        .optional()
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    if let Some(deleted) = deleted {
+        events.publish(CalendarChange::availability(ChangeAction::Deleted, &deleted, None));
+    }
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -82,4 +147,60 @@ pub fn availability_routes() -> Router {
         .route("/availabilities/:professional_id", get(list_availabilities_by_professional))
         .route("/availabilities/:id", put(update_availability))
         .route("/availabilities/:id", delete(delete_availability))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{appointments, busy_blocks, calendar_sources};
+    use crate::test_db::{self, fixtures};
+
+    #[tokio::test]
+    async fn test_free_intervals_exclude_appointments_and_busy_blocks() {
+        let Some(db) = test_db::setup() else { return };
+        let mut conn = db.pool.get().unwrap();
+        let professional = fixtures::professional(&mut conn, "5511900000060");
+        let client = fixtures::user(&mut conn, "client", "5511900000061");
+        let service = fixtures::service(&mut conn, 60);
+        let day = NaiveDate::from_ymd_opt(2026, 11, 3).unwrap();
+        let at = |hour| day.and_hms_opt(hour, 0, 0).unwrap();
+
+        diesel::insert_into(availabilities)
+            .values(&NewAvailability {
+                professional_id: professional,
+                date: day,
+                start_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                end_time: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+            })
+            .execute(&mut conn)
+            .unwrap();
+        fixtures::appointment(&mut conn, client, professional, service, at(9));
+        let canceled = fixtures::appointment(&mut conn, client, professional, service, at(15));
+        diesel::update(appointments::table.find(canceled))
+            .set(appointments::status.eq("canceled"))
+            .execute(&mut conn)
+            .unwrap();
+        let source: Uuid = diesel::insert_into(calendar_sources::table)
+            .values(calendar_sources::professional_id.eq(professional))
+            .returning(calendar_sources::id)
+            .get_result(&mut conn)
+            .unwrap();
+        diesel::insert_into(busy_blocks::table)
+            .values((
+                busy_blocks::professional_id.eq(professional),
+                busy_blocks::source_id.eq(source),
+                busy_blocks::uid.eq("consulta@externo"),
+                busy_blocks::start_time.eq(at(12)),
+                busy_blocks::end_time.eq(at(13)),
+            ))
+            .execute(&mut conn)
+            .unwrap();
+        drop(conn);
+
+        let Json(free) = list_free_intervals(Extension(db.pool.clone()), Path(professional), Query(FreeQuery { date: day }))
+            .await
+            .unwrap();
+        let free: Vec<_> = free.into_iter().map(|interval| (interval.start, interval.end)).collect();
+        assert_eq!(free, vec![(at(8), at(9)), (at(10), at(12)), (at(13), at(18))]);
+    }
+}
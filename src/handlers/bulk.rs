@@ -0,0 +1,107 @@
+use axum::{
+    extract::{Extension, Path, Query},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::{
+    db::Pool,
+    middleware::auth_middleware::Claims,
+    services::bulk_service::{self, BulkEntity, BulkFormat, ImportOptions, ImportReport},
+};
+
+/// 🔹 Parâmetros da importação (`?format=csv&dry_run=true&atomic=false`)
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    pub format: Option<BulkFormat>, // Sem `format`: CSV se o `Content-Type` for `text/csv`, senão JSON
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default = "default_atomic")]
+    pub atomic: bool,
+}
+
+fn default_atomic() -> bool {
+    true
+}
+
+/// 🔹 Parâmetros da exportação (`?format=csv`)
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: BulkFormat,
+}
+
+/// 🔹 Endpoint POST `/bulk/:entity/import` (somente admin): `services`, `professionals` ou `clients`
+/// Responde 200 com o relatório por linha; 422 quando a importação atômica foi desfeita por erros.
+pub async fn import(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(entity): Path<BulkEntity>,
+    Query(query): Query<ImportQuery>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<(StatusCode, Json<ImportReport>), (StatusCode, String)> {
+    authorize(&claims)?;
+    let format = query.format.unwrap_or_else(|| {
+        let content_type = headers.get(header::CONTENT_TYPE).and_then(|value| value.to_str().ok()).unwrap_or_default();
+        if content_type.starts_with("text/csv") { BulkFormat::Csv } else { BulkFormat::Json }
+    });
+    let options = ImportOptions { dry_run: query.dry_run, atomic: query.atomic };
+
+    let report = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get().map_err(internal_error)?;
+        bulk_service::import(&mut conn, entity, format, &body, options).map_err(|e| (StatusCode::BAD_REQUEST, e))
+    })
+    .await
+    .map_err(internal_error)??;
+
+    let status = if report.atomic && report.failed > 0 { StatusCode::UNPROCESSABLE_ENTITY } else { StatusCode::OK };
+    Ok((status, Json(report)))
+}
+
+/// 🔹 Endpoint GET `/bulk/:entity/export` (somente admin): arquivo no formato aceito pela importação
+pub async fn export(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(entity): Path<BulkEntity>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    authorize(&claims)?;
+    let format = query.format;
+
+    let body = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get().map_err(internal_error)?;
+        bulk_service::export(&mut conn, entity, format).map_err(internal_error)
+    })
+    .await
+    .map_err(internal_error)??;
+
+    let name = serde_json::to_value(entity).ok().and_then(|name| name.as_str().map(str::to_string)).unwrap_or_default();
+    let (content_type, extension) = match format {
+        BulkFormat::Json => ("application/json", "json"),
+        BulkFormat::Csv => ("text/csv; charset=utf-8", "csv"),
+    };
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.{}\"", name, extension)),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+fn authorize(claims: &Claims) -> Result<(), (StatusCode, String)> {
+    if claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    Ok(())
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
@@ -0,0 +1,194 @@
+use axum::{
+    extract::{Extension, Json, Path},
+    http::StatusCode,
+};
+use chrono::Utc;
+use diesel::prelude::*;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::Pool,
+    middleware::auth_middleware::Claims,
+    models::busy_block::{BusyBlock, CalendarSource, NewCalendarSource},
+    schema::{busy_blocks, calendar_sources, professionals},
+    services::busy_block_service::{self, ImportSummary},
+    services::http_client,
+    services::ical_service::parse_busy_intervals,
+};
+
+/// 🔹 Endpoint POST `/professionals/:id/busy-blocks/import` (corpo: arquivo .ics)
+/// Cada upload substitui o anterior: eventos são casados pelo UID.
+pub async fn import_busy_blocks(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(claims): Extension<Claims>,
+    Path(professional_id): Path<Uuid>,
+    body: String,
+) -> Result<Json<ImportSummary>, (StatusCode, String)> {
+    let mut conn = pool.get().map_err(internal_error)?;
+    authorize(&mut conn, &claims, professional_id)?;
+
+    let now = Utc::now().naive_utc();
+    let (window_start, window_end) = busy_block_service::import_window(now);
+    let intervals = parse_busy_intervals(&body, window_start, window_end, config.calendar_utc_offset)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let source = busy_block_service::managed_source(&mut conn, professional_id, busy_block_service::SOURCE_UPLOAD)
+        .map_err(internal_error)?;
+    let summary = busy_block_service::replace_blocks(&mut conn, &source, &intervals, now).map_err(internal_error)?;
+
+    Ok(Json(summary))
+}
+
+/// 🔹 Endpoint GET `/professionals/:id/busy-blocks`
+pub async fn list_busy_blocks(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(professional_id): Path<Uuid>,
+) -> Result<Json<Vec<BusyBlock>>, (StatusCode, String)> {
+    let mut conn = pool.get().map_err(internal_error)?;
+    authorize(&mut conn, &claims, professional_id)?;
+
+    let blocks = busy_blocks::table
+        .filter(busy_blocks::professional_id.eq(professional_id))
+        .filter(busy_blocks::end_time.gt(Utc::now().naive_utc()))
+        .order(busy_blocks::start_time.asc())
+        .load::<BusyBlock>(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok(Json(blocks))
+}
+
+/// 🔹 Endpoint POST `/professionals/:id/calendar-sources` (`{"location": "URL http(s)"}`)
+/// Cadastra o calendário e já faz a primeira leitura; o worker relê periodicamente.
+/// Só URLs com host público: caminhos locais e endereços internos são recusados (400).
+pub async fn create_calendar_source(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(claims): Extension<Claims>,
+    Path(professional_id): Path<Uuid>,
+    Json(mut payload): Json<NewCalendarSource>,
+) -> Result<(StatusCode, Json<CalendarSource>), (StatusCode, String)> {
+    let Some(location) = payload.location.as_deref().map(str::trim).filter(|location| !location.is_empty()).map(str::to_string) else {
+        return Err((StatusCode::BAD_REQUEST, "location é obrigatório".to_string()));
+    };
+    payload.professional_id = professional_id;
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    authorize(&mut conn, &claims, professional_id)?;
+    drop(conn);
+
+    http_client::check_public_url(&location).await.map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    payload.location = Some(location);
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let source = diesel::insert_into(calendar_sources::table)
+        .values(&payload)
+        .get_result::<CalendarSource>(&mut conn)
+        .map_err(internal_error)?;
+    let source_id = source.id;
+    drop(conn);
+
+    // Erros da primeira leitura ficam registrados em `last_error`
+    let _ = busy_block_service::sync_source(pool.clone(), source, config.calendar_utc_offset).await;
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    let source = calendar_sources::table
+        .find(source_id)
+        .first::<CalendarSource>(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok((StatusCode::CREATED, Json(source)))
+}
+
+/// 🔹 Endpoint GET `/professionals/:id/calendar-sources`
+pub async fn list_calendar_sources(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(professional_id): Path<Uuid>,
+) -> Result<Json<Vec<CalendarSource>>, (StatusCode, String)> {
+    let mut conn = pool.get().map_err(internal_error)?;
+    authorize(&mut conn, &claims, professional_id)?;
+
+    let sources = calendar_sources::table
+        .filter(calendar_sources::professional_id.eq(professional_id))
+        .order(calendar_sources::created_at.asc())
+        .load::<CalendarSource>(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok(Json(sources))
+}
+
+/// 🔹 Endpoint POST `/professionals/:id/calendar-sources/:source_id/sync`
+pub async fn sync_calendar_source(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(claims): Extension<Claims>,
+    Path((professional_id, source_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ImportSummary>, (StatusCode, String)> {
+    let mut conn = pool.get().map_err(internal_error)?;
+    authorize(&mut conn, &claims, professional_id)?;
+    let source = find_source(&mut conn, professional_id, source_id)?;
+    if source.kind == busy_block_service::SOURCE_UPLOAD {
+        return Err((StatusCode::BAD_REQUEST, "Uploads manuais não são relidos; envie o arquivo novamente".to_string()));
+    }
+    drop(conn);
+
+    let summary = busy_block_service::sync_source(pool.clone(), source, config.calendar_utc_offset)
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e))?;
+
+    Ok(Json(summary))
+}
+
+/// 🔹 Endpoint DELETE `/professionals/:id/calendar-sources/:source_id` (remove também os blocos)
+pub async fn delete_calendar_source(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path((professional_id, source_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let mut conn = pool.get().map_err(internal_error)?;
+    authorize(&mut conn, &claims, professional_id)?;
+    find_source(&mut conn, professional_id, source_id)?;
+
+    diesel::delete(calendar_sources::table.find(source_id))
+        .execute(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 🔹 Apenas o próprio profissional ou um admin gerencia a agenda externa
+fn authorize(conn: &mut PgConnection, claims: &Claims, professional_id: Uuid) -> Result<(), (StatusCode, String)> {
+    let owner = professionals::table
+        .find(professional_id)
+        .filter(professionals::deleted_at.is_null())
+        .select(professionals::user_id)
+        .first::<Uuid>(conn)
+        .optional()
+        .map_err(internal_error)?
+        .ok_or((StatusCode::NOT_FOUND, "Profissional não encontrado".to_string()))?;
+
+    if claims.sub != owner.to_string() && claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    Ok(())
+}
+
+fn find_source(conn: &mut PgConnection, professional_id: Uuid, source_id: Uuid) -> Result<CalendarSource, (StatusCode, String)> {
+    calendar_sources::table
+        .find(source_id)
+        .filter(calendar_sources::professional_id.eq(professional_id))
+        .first::<CalendarSource>(conn)
+        .optional()
+        .map_err(internal_error)?
+        .ok_or((StatusCode::NOT_FOUND, "Calendário não encontrado".to_string()))
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
@@ -0,0 +1,600 @@
+use axum::{
+    extract::{Extension, Path},
+    http::{header, HeaderMap, HeaderName, Method, StatusCode},
+    response::{IntoResponse, Response},
+    routing::any,
+    Router,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::Pool,
+    models::appointment::Appointment,
+    models::app_password::AppPassword,
+    models::caldav_object::{CaldavObject, NewCaldavObject},
+    models::service::Service,
+    models::user::User,
+    schema::{app_passwords, appointments, caldav_objects, professionals, services, users},
+    services::auth_service::verify_password,
+    services::busy_block_service::{import_window, rebuild_caldav_blocks},
+    services::caldav_service::{
+        collection_tag, encode_resource_name, multistatus, requested_hrefs, requested_time_range,
+        resource_name_from_href, xml_escape, DavResponse,
+    },
+    services::ical_service::{first_event_uid, is_generated_uid, parse_busy_intervals, render_calendar, IcsEvent, FEED_HISTORY_DAYS},
+};
+
+const ROOT_HREF: &str = "/caldav/";
+const HOME_HREF: &str = "/caldav/calendars/";
+
+/// 🔹 Recursos de agendamento são somente leitura (gerenciados pela API)
+const APPOINTMENT_PREFIX: &str = "appointment-";
+
+const DAV_CAPABILITIES: &str = "1, 3, calendar-access";
+const ALLOWED_METHODS: &str = "OPTIONS, GET, HEAD, PUT, DELETE, PROPFIND, REPORT";
+
+type DavResult = Result<Response, (StatusCode, String)>;
+
+/// 🔹 Um recurso da coleção: agendamento (somente leitura) ou evento gravado via CalDAV
+enum CalendarResource {
+    Appointment(Box<(Appointment, Service)>),
+    Object(CaldavObject),
+}
+
+impl CalendarResource {
+    fn name(&self) -> String {
+        match self {
+            CalendarResource::Appointment(row) => format!("{}{}.ics", APPOINTMENT_PREFIX, row.0.id),
+            CalendarResource::Object(object) => object.name.clone(),
+        }
+    }
+
+    /// 🔹 Status e SEQUENCE cobrem todas as mudanças relevantes de um agendamento
+    fn etag(&self) -> String {
+        match self {
+            CalendarResource::Appointment(row) => format!("\"{}-{}\"", row.0.sequence, row.0.status),
+            CalendarResource::Object(object) => format!("\"{}\"", object.etag),
+        }
+    }
+
+    fn calendar_data(&self) -> String {
+        match self {
+            CalendarResource::Appointment(row) => {
+                render_calendar("Agenda", &[IcsEvent::from_appointment(&row.0, &row.1)], Utc::now().naive_utc())
+            }
+            CalendarResource::Object(object) => object.ics_data.clone(),
+        }
+    }
+
+    fn overlaps(&self, start: NaiveDateTime, end: NaiveDateTime, config: &Config) -> bool {
+        match self {
+            CalendarResource::Appointment(row) => {
+                let (appointment, service) = (&row.0, &row.1);
+                appointment.appointment_time < end
+                    && appointment.appointment_time + Duration::minutes(service.duracao_min.into()) > start
+            }
+            CalendarResource::Object(object) => parse_busy_intervals(&object.ics_data, start, end, config.calendar_utc_offset)
+                .is_ok_and(|intervals| !intervals.is_empty()),
+        }
+    }
+
+    fn props(&self, with_data: bool) -> Vec<String> {
+        let mut props = vec![
+            "<d:resourcetype/>".to_string(),
+            format!("<d:getetag>{}</d:getetag>", xml_escape(&self.etag())),
+            "<d:getcontenttype>text/calendar; charset=utf-8; component=vevent</d:getcontenttype>".to_string(),
+        ];
+        if with_data {
+            props.push(format!("<c:calendar-data>{}</c:calendar-data>", xml_escape(&self.calendar_data())));
+        }
+        props
+    }
+}
+
+/// 🔹 `/.well-known/caldav` (RFC 6764) → raiz do serviço
+pub async fn well_known() -> Response {
+    (StatusCode::MOVED_PERMANENTLY, [(header::LOCATION, ROOT_HREF)]).into_response()
+}
+
+/// 🔹 Raiz `/caldav/`: descoberta do principal do usuário autenticado
+pub async fn service_root(Extension(pool): Extension<Arc<Pool>>, method: Method, headers: HeaderMap) -> Response {
+    dav(|| {
+        if method == Method::OPTIONS {
+            return Ok(options_response());
+        }
+        let mut conn = pool.get().map_err(internal_error)?;
+        let user = authenticate(&mut conn, &headers)?;
+
+        match method.as_str() {
+            "PROPFIND" => Ok(multistatus_response(&[DavResponse::found(ROOT_HREF, vec![
+                "<d:resourcetype><d:collection/></d:resourcetype>".to_string(),
+                current_user_principal(&user),
+            ])])),
+            _ => Err(method_not_allowed()),
+        }
+    })
+}
+
+/// 🔹 `/caldav/principals/:user_id/`: aponta para a coleção de calendários
+pub async fn principal(
+    Extension(pool): Extension<Arc<Pool>>,
+    Path(user_id): Path<Uuid>,
+    method: Method,
+    headers: HeaderMap,
+) -> Response {
+    dav(|| {
+        if method == Method::OPTIONS {
+            return Ok(options_response());
+        }
+        let mut conn = pool.get().map_err(internal_error)?;
+        let user = authenticate(&mut conn, &headers)?;
+        if user.id != user_id {
+            return Err(dav_error(StatusCode::FORBIDDEN, "Access denied"));
+        }
+
+        match method.as_str() {
+            "PROPFIND" => Ok(multistatus_response(&[DavResponse::found(principal_href(user.id), vec![
+                "<d:resourcetype><d:principal/></d:resourcetype>".to_string(),
+                format!("<d:displayname>{}</d:displayname>", xml_escape(&user.name)),
+                current_user_principal(&user),
+                format!("<c:calendar-home-set><d:href>{}</d:href></c:calendar-home-set>", HOME_HREF),
+            ])])),
+            _ => Err(method_not_allowed()),
+        }
+    })
+}
+
+/// 🔹 `/caldav/calendars/`: lista as agendas acessíveis (próprias; todas para admins)
+pub async fn calendar_home(
+    Extension(pool): Extension<Arc<Pool>>,
+    method: Method,
+    headers: HeaderMap,
+) -> Response {
+    dav(|| {
+        if method == Method::OPTIONS {
+            return Ok(options_response());
+        }
+        let mut conn = pool.get().map_err(internal_error)?;
+        let user = authenticate(&mut conn, &headers)?;
+        if method.as_str() != "PROPFIND" {
+            return Err(method_not_allowed());
+        }
+
+        let mut responses = vec![DavResponse::found(HOME_HREF, vec![
+            "<d:resourcetype><d:collection/></d:resourcetype>".to_string(),
+            current_user_principal(&user),
+        ])];
+
+        if depth(&headers) > 0 {
+            let mut calendars = professionals::table
+                .inner_join(users::table)
+                .filter(professionals::deleted_at.is_null())
+                .select((professionals::id, users::name))
+                .into_boxed();
+            if !is_admin(&user) {
+                calendars = calendars.filter(professionals::user_id.eq(user.id));
+            }
+
+            for (professional_id, name) in calendars.load::<(Uuid, String)>(&mut conn).map_err(internal_error)? {
+                let resources = load_resources(&mut conn, professional_id).map_err(internal_error)?;
+                responses.push(DavResponse::found(
+                    calendar_href(professional_id),
+                    collection_props(&user, &name, &resources),
+                ));
+            }
+        }
+
+        Ok(multistatus_response(&responses))
+    })
+}
+
+/// 🔹 `/caldav/calendars/:professional_id/`: PROPFIND e REPORT (calendar-query / calendar-multiget)
+pub async fn calendar_collection(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Path(professional_id): Path<Uuid>,
+    method: Method,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    dav(|| {
+        if method == Method::OPTIONS {
+            return Ok(options_response());
+        }
+        let mut conn = pool.get().map_err(internal_error)?;
+        let user = authenticate(&mut conn, &headers)?;
+        let name = authorize_calendar(&mut conn, &user, professional_id)?;
+
+        match method.as_str() {
+            "PROPFIND" => {
+                let resources = load_resources(&mut conn, professional_id).map_err(internal_error)?;
+                let mut responses = vec![DavResponse::found(
+                    calendar_href(professional_id),
+                    collection_props(&user, &name, &resources),
+                )];
+                if depth(&headers) > 0 {
+                    responses.extend(resources.iter().map(|resource| {
+                        DavResponse::found(resource_href(professional_id, &resource.name()), resource.props(false))
+                    }));
+                }
+                Ok(multistatus_response(&responses))
+            }
+            "REPORT" => {
+                let with_data = body.contains("calendar-data");
+                let responses = if body.contains("calendar-multiget") {
+                    let mut responses = Vec::new();
+                    for href in requested_hrefs(&body) {
+                        let resource = match resource_name_from_href(&href) {
+                            Some(name) => find_resource(&mut conn, professional_id, &name).map_err(internal_error)?,
+                            None => None,
+                        };
+                        responses.push(match resource {
+                            Some(resource) => DavResponse::found(href, resource.props(with_data)),
+                            None => DavResponse::missing(href),
+                        });
+                    }
+                    responses
+                } else {
+                    let range = requested_time_range(&body, config.calendar_utc_offset);
+                    load_resources(&mut conn, professional_id)
+                        .map_err(internal_error)?
+                        .iter()
+                        .filter(|resource| range.is_none_or(|(start, end)| resource.overlaps(start, end, &config)))
+                        .map(|resource| DavResponse::found(resource_href(professional_id, &resource.name()), resource.props(with_data)))
+                        .collect()
+                };
+                Ok(multistatus_response(&responses))
+            }
+            _ => Err(method_not_allowed()),
+        }
+    })
+}
+
+/// 🔹 `/caldav/calendars/:professional_id/:resource`: GET/PUT/DELETE de eventos.
+/// PUT de eventos que não são agendamentos vira bloco ocupado na agenda do profissional.
+pub async fn calendar_resource(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Path((professional_id, name)): Path<(Uuid, String)>,
+    method: Method,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    dav(|| {
+        if method == Method::OPTIONS {
+            return Ok(options_response());
+        }
+        let mut conn = pool.get().map_err(internal_error)?;
+        let user = authenticate(&mut conn, &headers)?;
+        authorize_calendar(&mut conn, &user, professional_id)?;
+
+        match method.as_str() {
+            "GET" | "HEAD" => {
+                let resource = find_resource(&mut conn, professional_id, &name)
+                    .map_err(internal_error)?
+                    .ok_or_else(|| dav_error(StatusCode::NOT_FOUND, "Evento não encontrado"))?;
+                Ok((
+                    [(header::CONTENT_TYPE, "text/calendar; charset=utf-8".to_string()), (header::ETAG, resource.etag())],
+                    resource.calendar_data(),
+                )
+                    .into_response())
+            }
+            "PROPFIND" => {
+                let resource = find_resource(&mut conn, professional_id, &name)
+                    .map_err(internal_error)?
+                    .ok_or_else(|| dav_error(StatusCode::NOT_FOUND, "Evento não encontrado"))?;
+                Ok(multistatus_response(&[DavResponse::found(resource_href(professional_id, &name), resource.props(false))]))
+            }
+            "PUT" => put_object(&mut conn, &config, professional_id, &name, &headers, &body),
+            "DELETE" => delete_object(&mut conn, &config, professional_id, &name, &headers),
+            _ => Err(method_not_allowed()),
+        }
+    })
+}
+
+fn put_object(
+    conn: &mut PgConnection,
+    config: &Config,
+    professional_id: Uuid,
+    name: &str,
+    headers: &HeaderMap,
+    body: &str,
+) -> DavResult {
+    if name.starts_with(APPOINTMENT_PREFIX) {
+        return Err(dav_error(StatusCode::FORBIDDEN, "Agendamentos são gerenciados pela API"));
+    }
+
+    let uid = first_event_uid(body).ok_or_else(|| dav_error(StatusCode::BAD_REQUEST, "VEVENT com UID é obrigatório"))?;
+    if is_generated_uid(&uid) {
+        return Err(dav_error(StatusCode::FORBIDDEN, "Agendamentos são gerenciados pela API"));
+    }
+
+    let now = Utc::now().naive_utc();
+    let (window_start, window_end) = import_window(now);
+    parse_busy_intervals(body, window_start, window_end, config.calendar_utc_offset)
+        .map_err(|e| dav_error(StatusCode::BAD_REQUEST, &e))?;
+
+    let existing = find_object(conn, professional_id, name).map_err(internal_error)?;
+    check_preconditions(headers, existing.as_ref())?;
+
+    let object = NewCaldavObject {
+        professional_id,
+        name: name.to_string(),
+        uid,
+        ics_data: body.to_string(),
+        etag: Uuid::new_v4().simple().to_string(),
+        updated_at: now,
+    };
+
+    conn.transaction(|conn| {
+        diesel::insert_into(caldav_objects::table)
+            .values(&object)
+            .on_conflict((caldav_objects::professional_id, caldav_objects::name))
+            .do_update()
+            .set((
+                caldav_objects::uid.eq(&object.uid),
+                caldav_objects::ics_data.eq(&object.ics_data),
+                caldav_objects::etag.eq(&object.etag),
+                caldav_objects::updated_at.eq(object.updated_at),
+            ))
+            .execute(conn)?;
+        rebuild_caldav_blocks(conn, professional_id, config.calendar_utc_offset, now)
+    })
+    .map_err(internal_error)?;
+
+    let status = if existing.is_some() { StatusCode::NO_CONTENT } else { StatusCode::CREATED };
+    Ok((status, [(header::ETAG, format!("\"{}\"", object.etag))]).into_response())
+}
+
+fn delete_object(conn: &mut PgConnection, config: &Config, professional_id: Uuid, name: &str, headers: &HeaderMap) -> DavResult {
+    if name.starts_with(APPOINTMENT_PREFIX) {
+        return Err(dav_error(StatusCode::FORBIDDEN, "Agendamentos são gerenciados pela API"));
+    }
+
+    let existing = find_object(conn, professional_id, name)
+        .map_err(internal_error)?
+        .ok_or_else(|| dav_error(StatusCode::NOT_FOUND, "Evento não encontrado"))?;
+    check_preconditions(headers, Some(&existing))?;
+
+    conn.transaction(|conn| {
+        diesel::delete(caldav_objects::table.find(existing.id)).execute(conn)?;
+        rebuild_caldav_blocks(conn, professional_id, config.calendar_utc_offset, Utc::now().naive_utc())
+    })
+    .map_err(internal_error)?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// 🔹 `If-Match` / `If-None-Match: *` evitam sobrescrever alterações de outro dispositivo
+fn check_preconditions(headers: &HeaderMap, current: Option<&CaldavObject>) -> Result<(), (StatusCode, String)> {
+    let current_etag = current.map(|object| format!("\"{}\"", object.etag));
+
+    if let Some(expected) = headers.get(header::IF_MATCH).and_then(|value| value.to_str().ok()) {
+        let matches = current_etag.as_deref().is_some_and(|etag| {
+            expected.split(',').map(str::trim).any(|candidate| candidate == "*" || candidate == etag)
+        });
+        if !matches {
+            return Err(dav_error(StatusCode::PRECONDITION_FAILED, "ETag não confere"));
+        }
+    }
+
+    let if_none_match = headers.get(header::IF_NONE_MATCH).and_then(|value| value.to_str().ok());
+    if if_none_match.is_some_and(|value| value.trim() == "*") && current.is_some() {
+        return Err(dav_error(StatusCode::PRECONDITION_FAILED, "Evento já existe"));
+    }
+
+    Ok(())
+}
+
+/// 🔹 HTTP Basic com telefone (ou ID) do usuário e uma senha de aplicativo
+fn authenticate(conn: &mut PgConnection, headers: &HeaderMap) -> Result<User, (StatusCode, String)> {
+    let credentials = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Basic "))
+        .and_then(|value| STANDARD.decode(value.trim()).ok())
+        .and_then(|value| String::from_utf8(value).ok())
+        .ok_or_else(unauthorized)?;
+    let (username, password) = credentials.split_once(':').ok_or_else(unauthorized)?;
+
+    let user = match Uuid::parse_str(username) {
+        Ok(user_id) => users::table.find(user_id).filter(users::deleted_at.is_null()).first::<User>(conn).optional(),
+        Err(_) => users::table.filter(users::phone.eq(username)).filter(users::deleted_at.is_null()).first::<User>(conn).optional(),
+    }
+    .map_err(internal_error)?
+    .ok_or_else(unauthorized)?;
+
+    let passwords = app_passwords::table
+        .filter(app_passwords::user_id.eq(user.id))
+        .load::<AppPassword>(conn)
+        .map_err(internal_error)?;
+    let matched = passwords
+        .iter()
+        .find(|app_password| verify_password(&app_password.password_hash, password))
+        .ok_or_else(unauthorized)?;
+
+    diesel::update(app_passwords::table.find(matched.id))
+        .set(app_passwords::last_used_at.eq(Utc::now().naive_utc()))
+        .execute(conn)
+        .map_err(internal_error)?;
+
+    Ok(user)
+}
+
+/// 🔹 Apenas o próprio profissional ou um admin; devolve o nome exibido da agenda
+fn authorize_calendar(conn: &mut PgConnection, user: &User, professional_id: Uuid) -> Result<String, (StatusCode, String)> {
+    let (owner, name) = professionals::table
+        .inner_join(users::table)
+        .filter(professionals::id.eq(professional_id))
+        .filter(professionals::deleted_at.is_null())
+        .select((professionals::user_id, users::name))
+        .first::<(Uuid, String)>(conn)
+        .optional()
+        .map_err(internal_error)?
+        .ok_or_else(|| dav_error(StatusCode::NOT_FOUND, "Agenda não encontrada"))?;
+
+    if owner != user.id && !is_admin(user) {
+        return Err(dav_error(StatusCode::FORBIDDEN, "Access denied"));
+    }
+
+    Ok(name)
+}
+
+/// 🔹 Agendamentos recentes/futuros do profissional + eventos gravados via CalDAV
+fn load_resources(conn: &mut PgConnection, professional_id: Uuid) -> QueryResult<Vec<CalendarResource>> {
+    let since = Utc::now().naive_utc() - Duration::days(FEED_HISTORY_DAYS);
+    let appointment_rows = appointments::table
+        .inner_join(services::table)
+        .filter(appointments::professional_id.eq(professional_id))
+        .filter(appointments::appointment_time.ge(since))
+        .filter(appointments::deleted_at.is_null())
+        .order(appointments::appointment_time.asc())
+        .select((appointments::all_columns, services::all_columns))
+        .load::<(Appointment, Service)>(conn)?;
+
+    let objects = caldav_objects::table
+        .filter(caldav_objects::professional_id.eq(professional_id))
+        .order(caldav_objects::name.asc())
+        .load::<CaldavObject>(conn)?;
+
+    Ok(appointment_rows
+        .into_iter()
+        .map(|row| CalendarResource::Appointment(Box::new(row)))
+        .chain(objects.into_iter().map(CalendarResource::Object))
+        .collect())
+}
+
+fn find_resource(conn: &mut PgConnection, professional_id: Uuid, name: &str) -> QueryResult<Option<CalendarResource>> {
+    let appointment_id = name
+        .strip_prefix(APPOINTMENT_PREFIX)
+        .and_then(|rest| rest.strip_suffix(".ics"))
+        .and_then(|id| Uuid::parse_str(id).ok());
+
+    match appointment_id {
+        Some(appointment_id) => Ok(appointments::table
+            .inner_join(services::table)
+            .filter(appointments::id.eq(appointment_id))
+            .filter(appointments::professional_id.eq(professional_id))
+            .filter(appointments::deleted_at.is_null())
+            .select((appointments::all_columns, services::all_columns))
+            .first::<(Appointment, Service)>(conn)
+            .optional()?
+            .map(|row| CalendarResource::Appointment(Box::new(row)))),
+        None => Ok(find_object(conn, professional_id, name)?.map(CalendarResource::Object)),
+    }
+}
+
+fn find_object(conn: &mut PgConnection, professional_id: Uuid, name: &str) -> QueryResult<Option<CaldavObject>> {
+    caldav_objects::table
+        .filter(caldav_objects::professional_id.eq(professional_id))
+        .filter(caldav_objects::name.eq(name))
+        .first::<CaldavObject>(conn)
+        .optional()
+}
+
+fn collection_props(user: &User, name: &str, resources: &[CalendarResource]) -> Vec<String> {
+    let entries: Vec<(String, String)> = resources.iter().map(|resource| (resource.name(), resource.etag())).collect();
+    let ctag = collection_tag(entries.iter().map(|(name, etag)| (name.as_str(), etag.as_str())));
+
+    vec![
+        "<d:resourcetype><d:collection/><c:calendar/></d:resourcetype>".to_string(),
+        format!("<d:displayname>{}</d:displayname>", xml_escape(&format!("Agenda - {}", name))),
+        "<c:supported-calendar-component-set><c:comp name=\"VEVENT\"/></c:supported-calendar-component-set>".to_string(),
+        format!("<cs:getctag>{}</cs:getctag>", ctag),
+        "<d:current-user-privilege-set><d:privilege><d:read/></d:privilege><d:privilege><d:write/></d:privilege></d:current-user-privilege-set>".to_string(),
+        current_user_principal(user),
+    ]
+}
+
+fn current_user_principal(user: &User) -> String {
+    format!("<d:current-user-principal><d:href>{}</d:href></d:current-user-principal>", principal_href(user.id))
+}
+
+fn principal_href(user_id: Uuid) -> String {
+    format!("/caldav/principals/{}/", user_id)
+}
+
+fn calendar_href(professional_id: Uuid) -> String {
+    format!("{}{}/", HOME_HREF, professional_id)
+}
+
+fn resource_href(professional_id: Uuid, name: &str) -> String {
+    format!("{}{}", calendar_href(professional_id), encode_resource_name(name))
+}
+
+/// 🔹 Cabeçalho `Depth` (ausente = infinito, tratado como 1)
+fn depth(headers: &HeaderMap) -> u8 {
+    match headers.get("depth").and_then(|value| value.to_str().ok()) {
+        Some("0") => 0,
+        _ => 1,
+    }
+}
+
+fn is_admin(user: &User) -> bool {
+    user.role == "admin" || user.role == "admin_master"
+}
+
+/// 🔹 Executa o handler e completa os erros com os cabeçalhos exigidos pelo protocolo
+fn dav<F: FnOnce() -> DavResult>(handler: F) -> Response {
+    handler().unwrap_or_else(|(status, message)| match status {
+        StatusCode::UNAUTHORIZED => (status, [(header::WWW_AUTHENTICATE, "Basic realm=\"scheduling\"")], message).into_response(),
+        StatusCode::METHOD_NOT_ALLOWED => (status, [(header::ALLOW, ALLOWED_METHODS)], message).into_response(),
+        _ => (status, message).into_response(),
+    })
+}
+
+fn multistatus_response(responses: &[DavResponse]) -> Response {
+    (
+        StatusCode::MULTI_STATUS,
+        [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+        multistatus(responses),
+    )
+        .into_response()
+}
+
+fn options_response() -> Response {
+    (
+        StatusCode::OK,
+        [(HeaderName::from_static("dav"), DAV_CAPABILITIES), (header::ALLOW, ALLOWED_METHODS)],
+    )
+        .into_response()
+}
+
+fn unauthorized() -> (StatusCode, String) {
+    dav_error(StatusCode::UNAUTHORIZED, "Credenciais inválidas")
+}
+
+fn method_not_allowed() -> (StatusCode, String) {
+    dav_error(StatusCode::METHOD_NOT_ALLOWED, "Método não suportado")
+}
+
+fn dav_error(status: StatusCode, message: &str) -> (StatusCode, String) {
+    (status, message.to_string())
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
+
+/// ✅ Rotas CalDAV (autenticadas por senha de aplicativo, fora do `AuthMiddleware`)
+pub fn caldav_router(pool: Arc<Pool>, config: Arc<Config>) -> Router {
+    Router::new()
+        .route("/.well-known/caldav", any(well_known))
+        .route("/caldav", any(service_root))
+        .route("/caldav/", any(service_root))
+        .route("/caldav/principals/:user_id", any(principal))
+        .route("/caldav/principals/:user_id/", any(principal))
+        .route("/caldav/calendars", any(calendar_home))
+        .route("/caldav/calendars/", any(calendar_home))
+        .route("/caldav/calendars/:professional_id", any(calendar_collection))
+        .route("/caldav/calendars/:professional_id/", any(calendar_collection))
+        .route("/caldav/calendars/:professional_id/:resource", any(calendar_resource))
+        .layer(Extension(pool))
+        .layer(Extension(config))
+}
@@ -0,0 +1,246 @@
+use axum::{
+    extract::{Extension, Path, Query},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    db::Pool,
+    middleware::auth_middleware::Claims,
+    models::appointment::Appointment,
+    models::calendar_feed_token::{CalendarFeedToken, NewCalendarFeedToken},
+    models::professional::Professional,
+    models::reservation::Reservation,
+    models::service::Service,
+    routes::v1,
+    schema::{appointments, calendar_feed_tokens, professionals, reservations, services},
+    services::ical_service::{render_calendar, IcsEvent, FEED_HISTORY_DAYS},
+};
+
+/// 🔹 Token do feed via query string (`?token=...`), já que apps de calendário não enviam Bearer
+#[derive(Deserialize)]
+pub struct FeedQuery {
+    pub token: String,
+}
+
+/// 🔹 Resposta da geração/rotação do token
+#[derive(Serialize)]
+pub struct FeedTokenResponse {
+    pub token: String,
+    pub user_feed_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub professional_feed_url: Option<String>,
+}
+
+/// 🔹 Endpoint GET `/professionals/:id/calendar.ics?token=...`
+/// Feed com os agendamentos do profissional; o token deve pertencer ao usuário do profissional.
+pub async fn professional_feed(
+    Extension(pool): Extension<Arc<Pool>>,
+    Path(professional_id): Path<Uuid>,
+    Query(query): Query<FeedQuery>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let owner = token_owner(&mut conn, &query.token)?;
+    let professional = professionals::table
+        .filter(professionals::id.eq(professional_id))
+        .filter(professionals::deleted_at.is_null())
+        .first::<Professional>(&mut conn)
+        .optional()
+        .map_err(internal_error)?
+        .filter(|professional| professional.user_id == owner)
+        .ok_or_else(invalid_token)?;
+
+    let since = Utc::now().naive_utc() - Duration::days(FEED_HISTORY_DAYS);
+    let rows = appointments::table
+        .inner_join(services::table)
+        .filter(appointments::professional_id.eq(professional.id))
+        .filter(appointments::appointment_time.ge(since))
+        .filter(appointments::deleted_at.is_null())
+        .order(appointments::appointment_time.asc())
+        .select((appointments::all_columns, services::all_columns))
+        .load::<(Appointment, Service)>(&mut conn)
+        .map_err(internal_error)?;
+
+    let events: Vec<IcsEvent> = rows
+        .iter()
+        .map(|(appointment, service)| IcsEvent::from_appointment(appointment, service))
+        .collect();
+
+    Ok(ics_response("Agenda do profissional", &events))
+}
+
+/// 🔹 Endpoint GET `/users/:id/calendar.ics?token=...`
+/// Feed com os agendamentos e reservas do cliente; o token deve pertencer ao próprio usuário.
+pub async fn user_feed(
+    Extension(pool): Extension<Arc<Pool>>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<FeedQuery>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    if token_owner(&mut conn, &query.token)? != user_id {
+        return Err(invalid_token());
+    }
+
+    let since = Utc::now().naive_utc() - Duration::days(FEED_HISTORY_DAYS);
+    let appointment_rows = appointments::table
+        .inner_join(services::table)
+        .filter(appointments::client_id.eq(user_id))
+        .filter(appointments::appointment_time.ge(since))
+        .filter(appointments::deleted_at.is_null())
+        .select((appointments::all_columns, services::all_columns))
+        .load::<(Appointment, Service)>(&mut conn)
+        .map_err(internal_error)?;
+
+    let reservation_rows = reservations::table
+        .filter(reservations::user_id.eq(user_id))
+        .filter(reservations::appointment_time.ge(since))
+        .load::<Reservation>(&mut conn)
+        .map_err(internal_error)?;
+
+    let mut events: Vec<IcsEvent> = appointment_rows
+        .iter()
+        .map(|(appointment, service)| IcsEvent::from_appointment(appointment, service))
+        .chain(reservation_rows.iter().map(IcsEvent::from_reservation))
+        .collect();
+    events.sort_by_key(|event| event.start);
+
+    Ok(ics_response("Meus agendamentos", &events))
+}
+
+/// 🔹 Endpoint POST `/users/:id/calendar-token` (self ou admin)
+/// Gera um novo token secreto, invalidando o anterior. O token só aparece nesta resposta; o banco guarda o hash.
+pub async fn rotate_feed_token(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(target_id): Path<Uuid>,
+) -> Result<Json<FeedTokenResponse>, (StatusCode, String)> {
+    if claims.sub != target_id.to_string() && claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let token = generate_token();
+    let new_token = NewCalendarFeedToken {
+        user_id: target_id,
+        token_hash: hash_token(&token),
+    };
+    let saved = diesel::insert_into(calendar_feed_tokens::table)
+        .values(&new_token)
+        .on_conflict(calendar_feed_tokens::user_id)
+        .do_update()
+        .set((
+            calendar_feed_tokens::token_hash.eq(&new_token.token_hash),
+            calendar_feed_tokens::created_at.eq(Utc::now().naive_utc()),
+        ))
+        .returning(CalendarFeedToken::as_returning())
+        .get_result(&mut conn)
+        .map_err(internal_error)?;
+
+    let professional_id = professionals::table
+        .filter(professionals::user_id.eq(saved.user_id))
+        .filter(professionals::deleted_at.is_null())
+        .select(professionals::id)
+        .first::<Uuid>(&mut conn)
+        .optional()
+        .map_err(internal_error)?;
+
+    Ok(Json(FeedTokenResponse {
+        user_feed_url: format!("{}/users/{}/calendar.ics?token={}", v1::PREFIX, saved.user_id, token),
+        professional_feed_url: professional_id
+            .map(|professional_id| format!("{}/professionals/{}/calendar.ics?token={}", v1::PREFIX, professional_id, token)),
+        token,
+    }))
+}
+
+/// ✅ Rotas públicas dos feeds (autenticadas pelo token, fora do `AuthMiddleware`)
+pub fn calendar_feed_router(pool: Arc<Pool>) -> Router {
+    Router::new()
+        .route("/professionals/:id/calendar.ics", get(professional_feed))
+        .route("/users/:id/calendar.ics", get(user_feed))
+        .layer(Extension(pool))
+}
+
+/// 🔹 Usuário dono do token (busca pelo hash), ou 401 se o token não existir
+fn token_owner(conn: &mut PgConnection, token: &str) -> Result<Uuid, (StatusCode, String)> {
+    calendar_feed_tokens::table
+        .filter(calendar_feed_tokens::token_hash.eq(hash_token(token)))
+        .select(calendar_feed_tokens::user_id)
+        .first::<Uuid>(conn)
+        .optional()
+        .map_err(internal_error)?
+        .ok_or_else(invalid_token)
+}
+
+/// 🔹 32 bytes aleatórios em hexadecimal
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// 🔹 SHA-256 em hexadecimal: o token tem 256 bits aleatórios, então um hash rápido basta
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+fn ics_response(name: &str, events: &[IcsEvent]) -> impl IntoResponse {
+    (
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "inline; filename=\"calendar.ics\""),
+        ],
+        render_calendar(name, events, Utc::now().naive_utc()),
+    )
+}
+
+fn invalid_token() -> (StatusCode, String) {
+    (StatusCode::UNAUTHORIZED, "Token de calendário inválido".to_string())
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db::{self, fixtures};
+
+    #[tokio::test]
+    async fn test_feed_token_is_stored_hashed() {
+        let Some(db) = test_db::setup() else { return };
+        let mut conn = db.pool.get().unwrap();
+        let user_id = fixtures::user(&mut conn, "client", "5511900000070");
+        drop(conn);
+        let claims = Claims { sub: user_id.to_string(), exp: 0, role: "client".to_string() };
+
+        let Json(created) = rotate_feed_token(Extension(db.pool.clone()), Extension(claims), Path(user_id)).await.unwrap();
+        let mut conn = db.pool.get().unwrap();
+        let stored: String = calendar_feed_tokens::table
+            .find(user_id)
+            .select(calendar_feed_tokens::token_hash)
+            .first(&mut conn)
+            .unwrap();
+        drop(conn);
+        assert_ne!(stored, created.token);
+        assert_eq!(stored, hash_token(&created.token));
+
+        let feed = |token: String| user_feed(Extension(db.pool.clone()), Path(user_id), Query(FeedQuery { token }));
+        assert!(feed(created.token.clone()).await.is_ok());
+        // Quem lê o banco não consegue usar o valor armazenado como token
+        assert_eq!(feed(stored).await.err().unwrap().0, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,344 @@
+use axum::{
+    extract::{Extension, Json, Path},
+    http::StatusCode,
+};
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::Pool,
+    middleware::{audit::Audit, auth_middleware::Claims},
+    models::{
+        client_note::{ClientNote, CreateClientNote, NewClientNote, VISIBILITIES},
+        client_profile::{ClientProfile, UpdateClientProfile},
+    },
+    pagination::{keyset, ListParams, Page, SortOrder},
+    schema::{appointments, client_notes, client_profiles, professionals, services, users},
+};
+
+/// 🔹 Endpoint GET `/users/:id/profile` (o próprio cliente ou a equipe)
+/// Quem ainda não tem ficha recebe uma vazia.
+pub async fn get_client_profile(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(target_id): Path<Uuid>,
+) -> Result<Json<ClientProfile>, (StatusCode, String)> {
+    authorize(&claims, target_id)?;
+    let mut conn = pool.get().map_err(internal_error)?;
+    ensure_client(&mut conn, target_id)?;
+
+    Ok(Json(load(&mut conn, target_id)?))
+}
+
+/// 🔹 Endpoint PUT `/users/:id/profile` (o próprio cliente ou a equipe)
+/// Ex: `{"allergies": "amônia", "hair_formula": "7.1 + 20 vol", "preferred_professional_ids": ["..."]}`
+pub async fn update_client_profile(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Extension(author_id): Extension<Uuid>,
+    Path(target_id): Path<Uuid>,
+    Json(payload): Json<UpdateClientProfile>,
+) -> Result<(Audit, Json<ClientProfile>), (StatusCode, String)> {
+    authorize(&claims, target_id)?;
+    let mut conn = pool.get().map_err(internal_error)?;
+    ensure_client(&mut conn, target_id)?;
+
+    if let Some(preferred) = &payload.preferred_professional_ids {
+        let found = professionals::table
+            .filter(professionals::id.eq_any(preferred))
+            .filter(professionals::deleted_at.is_null())
+            .count()
+            .get_result::<i64>(&mut conn)
+            .map_err(internal_error)?;
+        let mut unique = preferred.clone();
+        unique.sort();
+        unique.dedup();
+        if found != unique.len() as i64 {
+            return Err((StatusCode::BAD_REQUEST, "preferred_professional_ids contém profissional inexistente".to_string()));
+        }
+    }
+
+    let current = load(&mut conn, target_id)?;
+    let updated = payload.apply(current.clone(), author_id);
+    let saved = diesel::insert_into(client_profiles::table)
+        .values(&updated)
+        .on_conflict(client_profiles::user_id)
+        .do_update()
+        .set(&updated)
+        .get_result::<ClientProfile>(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok((Audit::changed("update", "client_profiles", target_id, &current, &saved), Json(saved)))
+}
+
+/// 🔹 Endpoint GET `/users/:id/notes`, paginado, mais recentes primeiro
+/// A equipe vê todas; o próprio cliente só as `shared`. Filtros: `from`/`to`. Ordenação: `created_at`.
+pub async fn list_client_notes(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(target_id): Path<Uuid>,
+    params: ListParams,
+) -> Result<Json<Page<ClientNote>>, (StatusCode, String)> {
+    authorize(&claims, target_id)?;
+    let sort = params.accept(&["from", "to"], &["created_at"], SortOrder::Desc)?;
+    let staff = is_staff(&claims);
+
+    let filtered = || {
+        let mut query = client_notes::table.filter(client_notes::client_id.eq(target_id)).into_boxed();
+        if !staff {
+            query = query.filter(client_notes::visibility.eq("shared"));
+        }
+        if let Some(start) = params.filters.starts_at() {
+            query = query.filter(client_notes::created_at.ge(start));
+        }
+        if let Some(end) = params.filters.ends_before() {
+            query = query.filter(client_notes::created_at.lt(end));
+        }
+        query
+    };
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    let total = match params.cursor {
+        None => Some(filtered().count().get_result::<i64>(&mut conn).map_err(internal_error)?),
+        Some(_) => None,
+    };
+    let results = keyset!(filtered(), client_notes::created_at, NaiveDateTime, client_notes::id, params, sort)
+        .load::<ClientNote>(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok(Json(Page::new(results, &params, sort, total, |note| (serde_json::json!(note.created_at), note.id))))
+}
+
+/// 🔹 Endpoint POST `/users/:id/notes` (apenas a equipe; o autor é quem está autenticado)
+pub async fn create_client_note(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Extension(author_id): Extension<Uuid>,
+    Path(target_id): Path<Uuid>,
+    Json(payload): Json<CreateClientNote>,
+) -> Result<(Audit, Json<ClientNote>), (StatusCode, String)> {
+    if !is_staff(&claims) {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    let body = payload.body.trim().to_string();
+    if body.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "`body` é obrigatório".to_string()));
+    }
+    let visibility = payload.visibility.unwrap_or_else(|| "staff".to_string());
+    if !VISIBILITIES.contains(&visibility.as_str()) {
+        return Err((StatusCode::BAD_REQUEST, format!("visibility deve ser um de: {}", VISIBILITIES.join(", "))));
+    }
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    ensure_client(&mut conn, target_id)?;
+
+    let note = diesel::insert_into(client_notes::table)
+        .values(&NewClientNote { client_id: target_id, author_id: Some(author_id), body, visibility })
+        .get_result::<ClientNote>(&mut conn)
+        .map_err(internal_error)?;
+
+    // O texto da anotação fica fora da trilha de auditoria (pode conter dados de saúde)
+    Ok((Audit::new("create", "client_notes", note.id), Json(note)))
+}
+
+/// 🔹 Endpoint DELETE `/users/:id/notes/:note_id` (autor da anotação ou admin)
+pub async fn delete_client_note(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Extension(author_id): Extension<Uuid>,
+    Path((target_id, note_id)): Path<(Uuid, Uuid)>,
+) -> Result<(Audit, StatusCode), (StatusCode, String)> {
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let note = client_notes::table
+        .filter(client_notes::id.eq(note_id))
+        .filter(client_notes::client_id.eq(target_id))
+        .first::<ClientNote>(&mut conn)
+        .optional()
+        .map_err(internal_error)?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Anotação não encontrada".to_string()))?;
+    if note.author_id != Some(author_id) && claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    diesel::delete(client_notes::table.find(note_id)).execute(&mut conn).map_err(internal_error)?;
+
+    Ok((Audit::new("delete", "client_notes", note_id), StatusCode::NO_CONTENT))
+}
+
+/// 🔹 Atendimento passado, com o serviço e o profissional
+#[derive(Debug, Queryable, Serialize, Deserialize)]
+pub struct ServiceHistoryEntry {
+    pub appointment_id: Uuid,
+    pub appointment_time: NaiveDateTime,
+    pub status: String,
+    pub service_id: Uuid,
+    pub service_name: String,
+    pub price: f64,
+    pub duration_min: i32,
+    pub professional_id: Uuid,
+    pub professional_name: String,
+}
+
+/// 🔹 Endpoint GET `/users/:id/service-history` (o próprio cliente ou a equipe), paginado, mais recentes primeiro
+/// Agendamentos já passados (no fuso do salão). Filtros: `from`/`to`, `status`, `professional_id`, `service_id`.
+pub async fn get_service_history(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(claims): Extension<Claims>,
+    Path(target_id): Path<Uuid>,
+    params: ListParams,
+) -> Result<Json<Page<ServiceHistoryEntry>>, (StatusCode, String)> {
+    authorize(&claims, target_id)?;
+    let sort = params.accept(&["from", "to", "status", "professional_id", "service_id"], &["appointment_time"], SortOrder::Desc)?;
+    let local_now = Utc::now().naive_utc() + Duration::seconds(config.calendar_utc_offset.local_minus_utc().into());
+
+    let filtered = || {
+        let mut query = appointments::table
+            .inner_join(services::table)
+            .inner_join(professionals::table.inner_join(users::table))
+            .filter(appointments::client_id.eq(target_id))
+            .filter(appointments::appointment_time.lt(local_now))
+            .filter(appointments::deleted_at.is_null())
+            .select((
+                appointments::id,
+                appointments::appointment_time,
+                appointments::status,
+                services::id,
+                services::nome,
+                services::preco,
+                services::duracao_min,
+                professionals::id,
+                users::name,
+            ))
+            .into_boxed();
+        if let Some(start) = params.filters.starts_at() {
+            query = query.filter(appointments::appointment_time.ge(start));
+        }
+        if let Some(end) = params.filters.ends_before() {
+            query = query.filter(appointments::appointment_time.lt(end));
+        }
+        if let Some(wanted) = params.filters.status.clone() {
+            query = query.filter(appointments::status.eq(wanted));
+        }
+        if let Some(wanted) = params.filters.professional_id {
+            query = query.filter(appointments::professional_id.eq(wanted));
+        }
+        if let Some(wanted) = params.filters.service_id {
+            query = query.filter(appointments::service_id.eq(wanted));
+        }
+        query
+    };
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    let total = match params.cursor {
+        None => Some(
+            filtered()
+                .select(diesel::dsl::count_star())
+                .get_result::<i64>(&mut conn)
+                .map_err(internal_error)?,
+        ),
+        Some(_) => None,
+    };
+    let results = keyset!(filtered(), appointments::appointment_time, NaiveDateTime, appointments::id, params, sort)
+        .load::<ServiceHistoryEntry>(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok(Json(Page::new(results, &params, sort, total, |entry| {
+        (serde_json::json!(entry.appointment_time), entry.appointment_id)
+    })))
+}
+
+fn load(conn: &mut PgConnection, user_id: Uuid) -> Result<ClientProfile, (StatusCode, String)> {
+    let profile = client_profiles::table
+        .find(user_id)
+        .first::<ClientProfile>(conn)
+        .optional()
+        .map_err(internal_error)?;
+
+    Ok(profile.unwrap_or_else(|| ClientProfile::empty(user_id)))
+}
+
+/// 🔹 A ficha só existe para usuários ativos
+fn ensure_client(conn: &mut PgConnection, user_id: Uuid) -> Result<(), (StatusCode, String)> {
+    let exists = diesel::select(diesel::dsl::exists(users::table.find(user_id).filter(users::deleted_at.is_null())))
+        .get_result::<bool>(conn)
+        .map_err(internal_error)?;
+    if !exists {
+        return Err((StatusCode::NOT_FOUND, "User not found".to_string()));
+    }
+    Ok(())
+}
+
+/// 🔹 Equipe do salão: profissionais e administradores
+fn is_staff(claims: &Claims) -> bool {
+    matches!(claims.role.as_str(), "professional" | "admin" | "admin_master")
+}
+
+fn authorize(claims: &Claims, target_id: Uuid) -> Result<(), (StatusCode, String)> {
+    if claims.sub != target_id.to_string() && !is_staff(claims) {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    Ok(())
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pagination::ListFilters;
+    use crate::test_db::{self, fixtures};
+
+    fn claims(sub: Uuid, role: &str) -> Claims {
+        Claims { sub: sub.to_string(), exp: 0, role: role.to_string() }
+    }
+
+    fn first_page() -> ListParams {
+        ListParams { limit: 20, sort: None, order: None, cursor: None, filters: ListFilters::default() }
+    }
+
+    #[test]
+    fn test_authorize_owner_or_staff() {
+        let client = Uuid::new_v4();
+        let other = Uuid::new_v4();
+
+        assert!(authorize(&claims(client, "client"), client).is_ok());
+        assert_eq!(authorize(&claims(other, "client"), client).unwrap_err().0, StatusCode::FORBIDDEN);
+        for role in ["professional", "admin", "admin_master"] {
+            assert!(is_staff(&claims(other, role)));
+            assert!(authorize(&claims(other, role), client).is_ok());
+        }
+        assert!(!is_staff(&claims(client, "client")));
+    }
+
+    #[tokio::test]
+    async fn test_client_sees_only_shared_notes() {
+        let Some(db) = test_db::setup() else { return };
+        let mut conn = db.pool.get().unwrap();
+        let client = fixtures::user(&mut conn, "client", "5511900000020");
+        for (body, visibility) in [("Prefere café", "shared"), ("Reclamou do preço", "staff")] {
+            diesel::insert_into(client_notes::table)
+                .values(&NewClientNote { client_id: client, author_id: None, body: body.to_string(), visibility: visibility.to_string() })
+                .execute(&mut conn)
+                .unwrap();
+        }
+
+        let own = list_client_notes(Extension(db.pool.clone()), Extension(claims(client, "client")), Path(client), first_page())
+            .await
+            .unwrap();
+        assert_eq!(own.items.iter().map(|note| note.visibility.as_str()).collect::<Vec<_>>(), ["shared"]);
+
+        let staff = list_client_notes(Extension(db.pool.clone()), Extension(claims(Uuid::new_v4(), "professional")), Path(client), first_page())
+            .await
+            .unwrap();
+        assert_eq!(staff.items.len(), 2);
+    }
+}
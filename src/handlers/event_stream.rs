@@ -0,0 +1,170 @@
+use axum::{
+    extract::{Extension, Query},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use futures::stream::{self, Stream};
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use uuid::Uuid;
+
+use crate::{
+    db::Pool,
+    events::{CalendarChange, EventBus},
+    middleware::auth_middleware::Claims,
+    schema::professionals,
+    shutdown::Shutdown,
+};
+
+/// 🔹 Filtros do stream (`?professional_id=...&date=YYYY-MM-DD`)
+#[derive(Debug, Deserialize)]
+pub struct StreamQuery {
+    pub professional_id: Option<Uuid>,
+    pub date: Option<NaiveDate>,
+}
+
+/// 🔹 Quem está assistindo: define o que é visível em cada alteração
+#[derive(Debug)]
+pub struct Viewer {
+    pub user_id: Option<Uuid>,
+    pub is_admin: bool,
+    pub professional_ids: Vec<Uuid>, // Agendas do próprio usuário (se ele for profissional)
+}
+
+impl Viewer {
+    /// ✅ Admins, o cliente dono e o profissional da agenda veem os detalhes; os demais só que o horário mudou
+    pub fn present(&self, change: &CalendarChange) -> CalendarChange {
+        let is_owner = self.user_id.is_some() && change.user_id == self.user_id;
+        let is_professional = change.professional_id.is_some_and(|professional| self.professional_ids.contains(&professional));
+        if self.is_admin || is_owner || is_professional {
+            change.clone()
+        } else {
+            change.redacted()
+        }
+    }
+}
+
+impl StreamQuery {
+    /// ✅ Reservas não têm profissional e aparecem para qualquer filtro de profissional
+    pub fn matches(&self, change: &CalendarChange) -> bool {
+        let professional_matches = match (self.professional_id, change.professional_id) {
+            (Some(wanted), Some(professional)) => wanted == professional,
+            _ => true,
+        };
+        professional_matches && self.date.is_none_or(|date| change.touches_date(date))
+    }
+}
+
+/// 🔹 Endpoint GET `/events/stream` (autenticado): alterações da agenda via Server-Sent Events
+/// Cada evento SSE tem o nome da alteração (ex: `appointment.updated`) e o JSON da `CalendarChange`.
+/// `resync` indica que alterações foram perdidas (conexão lenta): o cliente deve recarregar a agenda.
+pub async fn stream_events(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(events): Extension<EventBus>,
+    Extension(shutdown): Extension<Shutdown>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<StreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
+    // Assina antes de qualquer consulta para não perder alterações feitas nesse intervalo
+    let receiver = events.subscribe();
+
+    let user_id = claims.sub.parse::<Uuid>().ok();
+    let professional_ids = match user_id {
+        Some(user_id) => {
+            let mut conn = pool.get().map_err(internal_error)?;
+            professionals::table
+                .filter(professionals::user_id.eq(user_id))
+                .select(professionals::id)
+                .load::<Uuid>(&mut conn)
+                .map_err(internal_error)?
+        }
+        None => Vec::new(),
+    };
+    let viewer = Viewer {
+        user_id,
+        is_admin: claims.role == "admin" || claims.role == "admin_master",
+        professional_ids,
+    };
+
+    let stream = stream::unfold((receiver, shutdown, viewer, query), |(mut receiver, shutdown, viewer, query)| async move {
+        let event = next_event(&mut receiver, &shutdown, &viewer, &query).await?;
+        Some((Ok(event), (receiver, shutdown, viewer, query)))
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// 🔹 Próxima alteração visível para o assinante; `None` encerra o stream (shutdown ou barramento fechado)
+async fn next_event(
+    receiver: &mut Receiver<CalendarChange>,
+    shutdown: &Shutdown,
+    viewer: &Viewer,
+    query: &StreamQuery,
+) -> Option<Event> {
+    loop {
+        let received = tokio::select! {
+            received = receiver.recv() => received,
+            _ = shutdown.wait() => return None,
+        };
+
+        match received {
+            Ok(change) if query.matches(&change) => {
+                let change = viewer.present(&change);
+                return Event::default().event(change.name()).json_data(&change).ok();
+            }
+            Ok(_) => continue,
+            Err(RecvError::Lagged(_)) => return Some(Event::default().event("resync").data("{}")),
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{ChangeAction, Resource};
+
+    fn change(professional_id: Option<Uuid>, user_id: Uuid, day: u32) -> CalendarChange {
+        let starts_at = NaiveDate::from_ymd_opt(2025, 5, day).unwrap().and_hms_opt(14, 0, 0).unwrap();
+        CalendarChange {
+            resource: Resource::Appointment,
+            action: ChangeAction::Updated,
+            id: Some(Uuid::new_v4()),
+            professional_id,
+            user_id: Some(user_id),
+            starts_at,
+            ends_at: None,
+            previous_starts_at: Some(starts_at - chrono::Duration::days(1)),
+            occurred_at: starts_at,
+        }
+    }
+
+    #[test]
+    fn test_stream_filters_and_visibility() {
+        let (professional, client) = (Uuid::new_v4(), Uuid::new_v4());
+        let moved = change(Some(professional), client, 10);
+
+        let query = StreamQuery { professional_id: Some(professional), date: NaiveDate::from_ymd_opt(2025, 5, 9) };
+        assert!(query.matches(&moved)); // Data anterior da remarcação também conta
+        assert!(!StreamQuery { professional_id: Some(Uuid::new_v4()), date: None }.matches(&moved));
+        assert!(StreamQuery { professional_id: Some(Uuid::new_v4()), date: None }.matches(&change(None, client, 10)));
+
+        let stranger = Viewer { user_id: Some(Uuid::new_v4()), is_admin: false, professional_ids: vec![] };
+        assert_eq!(stranger.present(&moved).user_id, None);
+        assert_eq!(stranger.present(&moved).id, None);
+
+        let owner = Viewer { user_id: Some(client), is_admin: false, professional_ids: vec![] };
+        let agenda = Viewer { user_id: Some(Uuid::new_v4()), is_admin: false, professional_ids: vec![professional] };
+        assert_eq!(owner.present(&moved).user_id, Some(client));
+        assert_eq!(agenda.present(&moved).id, moved.id);
+    }
+}
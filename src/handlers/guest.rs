@@ -0,0 +1,450 @@
+use axum::{
+    extract::{ConnectInfo, Extension, Json, Path},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Router,
+};
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::Pool,
+    events::{CalendarChange, ChangeAction, EventBus, EventKind},
+    handlers::appointment::{check_slot, insert_booking, record_event},
+    middleware::audit::{client_ip, Audit},
+    models::appointment::{Appointment, NewAppointment},
+    routes::v1,
+    schema::{appointments, professionals, services, users},
+    services::{
+        guest_service::{self, Check, Guest, Started},
+        reminder_service,
+    },
+    telemetry,
+};
+
+/// 🔹 Status em que o convidado ainda pode remarcar ou cancelar
+const ACTIVE_STATUSES: [&str; 2] = ["pending", "confirmed"];
+
+/// 🔹 Pedido do código de verificação
+#[derive(Debug, Deserialize)]
+pub struct VerificationRequest {
+    pub phone: String,
+}
+
+/// 🔹 Código enviado: o ID acompanha o código no agendamento
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerificationStarted {
+    pub verification_id: Uuid,
+    pub expires_at: NaiveDateTime,
+}
+
+/// 🔹 Agendamento sem cadastro: dados do convidado, código recebido por SMS e o horário
+#[derive(Debug, Deserialize)]
+pub struct GuestBookingRequest {
+    pub verification_id: Uuid,
+    pub code: String,
+    pub name: String,
+    pub phone: String,
+    pub professional_id: Uuid,
+    pub service_id: Uuid,
+    pub appointment_time: NaiveDateTime,
+}
+
+/// 🔹 Remarcação pelo link
+#[derive(Debug, Deserialize)]
+pub struct GuestReschedule {
+    pub appointment_time: NaiveDateTime,
+}
+
+/// 🔹 O que o convidado vê do agendamento
+#[derive(Debug, Queryable, Serialize, Deserialize)]
+pub struct GuestAppointment {
+    pub id: Uuid,
+    pub appointment_time: NaiveDateTime,
+    pub status: String,
+    pub service_id: Uuid,
+    pub service_name: String,
+    pub duration_min: i32,
+    pub professional_id: Uuid,
+    pub professional_name: String,
+}
+
+/// 🔹 Agendamento com o link de gerenciamento (token assinado, com validade)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GuestBooking {
+    pub appointment: GuestAppointment,
+    pub management_token: String,
+    pub management_url: String,
+    pub expires_at: NaiveDateTime,
+}
+
+/// 🔹 Endpoint POST `/guest/verifications` (público): envia um código por SMS para o telefone
+/// Limites: 3 códigos por telefone e 10 por IP a cada hora (429).
+pub async fn start_guest_verification(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(config): Extension<Arc<Config>>,
+    connection: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Json(payload): Json<VerificationRequest>,
+) -> Result<(StatusCode, Audit, Json<VerificationStarted>), (StatusCode, String)> {
+    if config.sms_gateway_url.is_none() && config.whatsapp_webhook_url.is_none() {
+        return Err((StatusCode::SERVICE_UNAVAILABLE, "Agendamento sem cadastro não está habilitado".to_string()));
+    }
+    let phone = guest_service::normalize_phone(&payload.phone).ok_or_else(invalid_phone)?;
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    let ip = client_ip(&headers, connection.as_ref(), &config.trusted_proxies);
+    let started = guest_service::start_verification(&mut conn, &payload.phone, &phone, ip, Utc::now().naive_utc())
+        .map_err(internal_error)?;
+
+    let verification = match started {
+        Started::Sent(verification) => verification,
+        Started::PhoneLimited | Started::IpLimited => {
+            telemetry::rate_limit_rejected("guest_verification");
+            return Err((StatusCode::TOO_MANY_REQUESTS, "Muitos códigos pedidos; tente novamente mais tarde".to_string()));
+        }
+    };
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Audit::new("guest_verification", "guest_verifications", verification.id),
+        Json(VerificationStarted { verification_id: verification.id, expires_at: verification.expires_at }),
+    ))
+}
+
+/// 🔹 Endpoint POST `/guest/appointments` (público): agenda com o código recebido por SMS.
+/// Usa o cadastro de cliente com o mesmo telefone ou cria um cliente sem senha; telefones da equipe ou de contas
+/// excluídas/anonimizadas são recusados (409).
+pub async fn book_as_guest(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(events): Extension<EventBus>,
+    Json(payload): Json<GuestBookingRequest>,
+) -> Result<(Audit, Json<GuestBooking>), (StatusCode, String)> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "`name` é obrigatório".to_string()));
+    }
+    let phone = guest_service::normalize_phone(&payload.phone).ok_or_else(invalid_phone)?;
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    let now = Utc::now().naive_utc();
+    match guest_service::check_code(&mut conn, payload.verification_id, &phone, &payload.code, now).map_err(internal_error)? {
+        Check::Valid => {}
+        Check::Invalid => return Err((StatusCode::BAD_REQUEST, "Código de verificação inválido".to_string())),
+        Check::Expired => return Err((StatusCode::BAD_REQUEST, "Código vencido ou já usado; peça um novo".to_string())),
+    }
+
+    if payload.appointment_time <= local_now(&config) {
+        return Err((StatusCode::BAD_REQUEST, "Horário já passou".to_string()));
+    }
+    let service_name = check_slot(&mut conn, payload.service_id, payload.professional_id, payload.appointment_time)?;
+
+    let booked = conn
+        .transaction(|conn| {
+            let user = match guest_service::find_or_create_guest(conn, name, &payload.phone, &phone)? {
+                Guest::Client(user) => user,
+                Guest::Refused => {
+                    return Ok(Err((StatusCode::CONFLICT, "Telefone vinculado a outra conta; fale com o salão".to_string())));
+                }
+            };
+            if !guest_service::consume(conn, payload.verification_id, now)? {
+                return Err(diesel::result::Error::RollbackTransaction);
+            }
+
+            let new_appointment = NewAppointment {
+                client_id: user.id,
+                professional_id: payload.professional_id,
+                service_id: payload.service_id,
+                appointment_time: payload.appointment_time,
+                status: "pending".to_string(),
+            };
+            insert_booking(conn, &config, &new_appointment, &service_name).map(Ok)
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::RollbackTransaction => {
+                (StatusCode::BAD_REQUEST, "Código vencido ou já usado; peça um novo".to_string())
+            }
+            e => internal_error(e),
+        })??;
+
+    telemetry::appointment_created();
+    events.publish(CalendarChange::appointment(ChangeAction::Created, &booked, None));
+
+    let audit = Audit::created("appointments", booked.id, &booked);
+    Ok((audit, Json(with_link(&mut conn, &config, &booked)?)))
+}
+
+/// 🔹 Endpoint GET `/guest/appointments/:token` (link de gerenciamento)
+pub async fn get_guest_appointment(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Path(token): Path<String>,
+) -> Result<Json<GuestAppointment>, (StatusCode, String)> {
+    let (appointment_id, sequence) = verify(&config, &token)?;
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    // Link de uma versão anterior (remarcado ou cancelado depois)
+    let current = appointments::table
+        .find(appointment_id)
+        .select(appointments::sequence)
+        .first::<i32>(&mut conn)
+        .optional()
+        .map_err(internal_error)?;
+    if current != Some(sequence) {
+        return Err(invalid_link());
+    }
+
+    Ok(Json(describe(&mut conn, appointment_id)?))
+}
+
+/// 🔹 Endpoint PUT `/guest/appointments/:token` (link de gerenciamento): remarca para outro horário futuro.
+/// A resposta traz um link novo (a validade acompanha o novo horário).
+pub async fn reschedule_guest_appointment(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(events): Extension<EventBus>,
+    Path(token): Path<String>,
+    Json(payload): Json<GuestReschedule>,
+) -> Result<(Option<Audit>, Json<GuestBooking>), (StatusCode, String)> {
+    let (appointment_id, sequence) = verify(&config, &token)?;
+    let local_now = local_now(&config);
+    if payload.appointment_time <= local_now {
+        return Err((StatusCode::BAD_REQUEST, "Horário já passou".to_string()));
+    }
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    let (existing, updated) = conn
+        .transaction(|conn| {
+            let existing = match lock_active(conn, appointment_id, sequence, local_now)? {
+                Ok(existing) => existing,
+                Err(rejected) => return Ok(Err(rejected)),
+            };
+            if existing.appointment_time == payload.appointment_time {
+                return Ok(Ok((existing, None)));
+            }
+            if let Err(rejected) = check_slot(conn, existing.service_id, existing.professional_id, payload.appointment_time) {
+                return Ok(Err(rejected));
+            }
+
+            let updated = diesel::update(appointments::table.find(appointment_id))
+                .set((
+                    appointments::appointment_time.eq(payload.appointment_time),
+                    appointments::sequence.eq(existing.sequence + 1),
+                ))
+                .get_result::<Appointment>(conn)?;
+            reminder_service::reset_attendance(conn, updated.id)?;
+            reminder_service::schedule_reminders(conn, &updated, config.calendar_utc_offset, Utc::now().naive_utc())?;
+            record_event(conn, EventKind::Rescheduled, &updated)?;
+            Ok::<_, diesel::result::Error>(Ok((existing, Some(updated))))
+        })
+        .map_err(internal_error)??;
+
+    // Mesmo horário: nada muda, o link continua o mesmo
+    let Some(updated) = updated else {
+        return Ok((None, Json(with_link(&mut conn, &config, &existing)?)));
+    };
+
+    events.publish(CalendarChange::appointment(ChangeAction::Updated, &updated, Some(&existing)));
+
+    let audit = Audit::changed("reschedule", "appointments", appointment_id, &existing, &updated);
+    Ok((Some(audit), Json(with_link(&mut conn, &config, &updated)?)))
+}
+
+/// 🔹 Endpoint DELETE `/guest/appointments/:token` (link de gerenciamento): cancela o agendamento
+pub async fn cancel_guest_appointment(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(events): Extension<EventBus>,
+    Path(token): Path<String>,
+) -> Result<(Audit, Json<GuestAppointment>), (StatusCode, String)> {
+    let (appointment_id, sequence) = verify(&config, &token)?;
+    let local_now = local_now(&config);
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let (existing, canceled) = conn
+        .transaction(|conn| {
+            let existing = match lock_active(conn, appointment_id, sequence, local_now)? {
+                Ok(existing) => existing,
+                Err(rejected) => return Ok(Err(rejected)),
+            };
+            let canceled = diesel::update(appointments::table.find(appointment_id))
+                .set((appointments::status.eq("canceled"), appointments::sequence.eq(existing.sequence + 1)))
+                .get_result::<Appointment>(conn)?;
+            // Status cancelado: só cancela os lembretes pendentes
+            reminder_service::schedule_reminders(conn, &canceled, config.calendar_utc_offset, Utc::now().naive_utc())?;
+            record_event(conn, EventKind::Canceled, &canceled)?;
+            Ok::<_, diesel::result::Error>(Ok((existing, canceled)))
+        })
+        .map_err(internal_error)??;
+
+    telemetry::appointment_status_changed("canceled");
+    events.publish(CalendarChange::appointment(ChangeAction::Updated, &canceled, Some(&existing)));
+
+    let audit = Audit::changed("cancel", "appointments", appointment_id, &existing, &canceled);
+    Ok((audit, Json(describe(&mut conn, appointment_id)?)))
+}
+
+/// 🔹 Agendamento do link, travado até o fim da transação (chamar dentro dela). Só é gerenciável se não foi
+/// excluído, continua na versão do link (`sequence`), está ativo e no futuro.
+fn lock_active(
+    conn: &mut PgConnection,
+    appointment_id: Uuid,
+    sequence: i32,
+    local_now: NaiveDateTime,
+) -> QueryResult<Result<Appointment, (StatusCode, String)>> {
+    let Some(appointment) = appointments::table
+        .find(appointment_id)
+        .filter(appointments::deleted_at.is_null())
+        .for_update()
+        .first::<Appointment>(conn)
+        .optional()?
+    else {
+        return Ok(Err(invalid_link()));
+    };
+    if appointment.sequence != sequence {
+        return Ok(Err(invalid_link()));
+    }
+    if !ACTIVE_STATUSES.contains(&appointment.status.as_str()) {
+        return Ok(Err((StatusCode::CONFLICT, "Agendamento cancelado".to_string())));
+    }
+    if appointment.appointment_time <= local_now {
+        return Ok(Err((StatusCode::CONFLICT, "Agendamento já passou".to_string())));
+    }
+    Ok(Ok(appointment))
+}
+
+/// 🔹 Agendamento com o nome do serviço e do profissional
+fn describe(conn: &mut PgConnection, appointment_id: Uuid) -> Result<GuestAppointment, (StatusCode, String)> {
+    appointments::table
+        .inner_join(services::table)
+        .inner_join(professionals::table.inner_join(users::table))
+        .filter(appointments::id.eq(appointment_id))
+        .filter(appointments::deleted_at.is_null())
+        .select((
+            appointments::id,
+            appointments::appointment_time,
+            appointments::status,
+            services::id,
+            services::nome,
+            services::duracao_min,
+            professionals::id,
+            users::name,
+        ))
+        .first::<GuestAppointment>(conn)
+        .optional()
+        .map_err(internal_error)?
+        .ok_or_else(invalid_link)
+}
+
+/// 🔹 Resposta com o link de gerenciamento assinado
+fn with_link(conn: &mut PgConnection, config: &Config, appointment: &Appointment) -> Result<GuestBooking, (StatusCode, String)> {
+    let expires_at = guest_service::link_expiry(appointment.appointment_time, config.calendar_utc_offset);
+    let token = guest_service::sign_link(&config.secret_key, appointment.id, appointment.sequence, expires_at);
+
+    Ok(GuestBooking {
+        appointment: describe(conn, appointment.id)?,
+        management_url: format!("{}{}/guest/appointments/{}", config.public_base_url.trim_end_matches('/'), v1::PREFIX, token),
+        management_token: token,
+        expires_at,
+    })
+}
+
+fn verify(config: &Config, token: &str) -> Result<(Uuid, i32), (StatusCode, String)> {
+    guest_service::verify_link(&config.secret_key, token, Utc::now().naive_utc()).ok_or_else(invalid_link)
+}
+
+/// 🔹 Horário atual no fuso do salão (os horários dos agendamentos estão nele)
+fn local_now(config: &Config) -> NaiveDateTime {
+    Utc::now().naive_utc() + Duration::seconds(config.calendar_utc_offset.local_minus_utc().into())
+}
+
+/// ✅ Rotas públicas do agendamento sem cadastro (código por SMS / token assinado no link)
+pub fn guest_router(pool: Arc<Pool>, config: Arc<Config>) -> Router {
+    Router::new()
+        .route("/guest/verifications", post(start_guest_verification))
+        .route("/guest/appointments", post(book_as_guest))
+        .route("/guest/appointments/:token", get(get_guest_appointment).put(reschedule_guest_appointment).delete(cancel_guest_appointment))
+        .layer(Extension(pool))
+        .layer(Extension(config))
+}
+
+fn invalid_phone() -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, "Telefone inválido".to_string())
+}
+
+fn invalid_link() -> (StatusCode, String) {
+    (StatusCode::NOT_FOUND, "Link de agendamento inválido ou expirado".to_string())
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db::{self, fixtures};
+
+    #[tokio::test]
+    async fn test_link_is_bound_to_appointment_version() {
+        let Some(db) = test_db::setup() else { return };
+        let config = Arc::new(Config::for_tests());
+        let events = EventBus::new();
+        let mut conn = db.pool.get().unwrap();
+        let professional_id = fixtures::professional(&mut conn, "5511900000040");
+        let service_id = fixtures::service(&mut conn, 30);
+        let starts_at = local_now(&config) + Duration::days(2);
+
+        let Started::Sent(verification) = guest_service::start_verification(&mut conn, "5511900000041", "5511900000041", None, Utc::now().naive_utc()).unwrap() else {
+            panic!("primeiro pedido não deveria ser limitado");
+        };
+        let code = guest_service::issue_code(&mut conn, verification.id, Utc::now().naive_utc()).unwrap().unwrap();
+        let request = GuestBookingRequest {
+            verification_id: verification.id,
+            code,
+            name: "Ana".to_string(),
+            phone: "5511900000041".to_string(),
+            professional_id,
+            service_id,
+            appointment_time: starts_at,
+        };
+        let (_, Json(booked)) = book_as_guest(Extension(db.pool.clone()), Extension(config.clone()), Extension(events.clone()), Json(request))
+            .await
+            .unwrap();
+
+        let reschedule = GuestReschedule { appointment_time: starts_at + Duration::hours(1) };
+        let (_, Json(rescheduled)) = reschedule_guest_appointment(
+            Extension(db.pool.clone()),
+            Extension(config.clone()),
+            Extension(events.clone()),
+            Path(booked.management_token.clone()),
+            Json(reschedule),
+        )
+        .await
+        .unwrap();
+        assert_ne!(rescheduled.management_token, booked.management_token);
+
+        // O link anterior à remarcação não serve mais, nem para ver nem para cancelar
+        let stale = get_guest_appointment(Extension(db.pool.clone()), Extension(config.clone()), Path(booked.management_token.clone())).await;
+        assert_eq!(stale.unwrap_err().0, StatusCode::NOT_FOUND);
+        let stale = cancel_guest_appointment(Extension(db.pool.clone()), Extension(config.clone()), Extension(events.clone()), Path(booked.management_token)).await;
+        assert_eq!(stale.unwrap_err().0, StatusCode::NOT_FOUND);
+
+        let (_, Json(canceled)) = cancel_guest_appointment(
+            Extension(db.pool.clone()),
+            Extension(config.clone()),
+            Extension(events.clone()),
+            Path(rescheduled.management_token.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(canceled.status, "canceled");
+    }
+}
@@ -0,0 +1,46 @@
+use axum::{
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Extension, Json, Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::db::Pool;
+use crate::jobs::JobRegistry;
+use crate::services::health_service;
+use crate::shutdown::Shutdown;
+
+/// 🔹 Endpoint GET `/health/live` → o processo está respondendo
+pub async fn liveness() -> impl IntoResponse {
+    Json(json!({ "status": "ok" }))
+}
+
+/// 🔹 Endpoint GET `/health/ready` → dependências prontas (503 se alguma verificação crítica falhar)
+pub async fn readiness(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(jobs): Extension<JobRegistry>,
+    Extension(shutdown): Extension<Shutdown>,
+) -> impl IntoResponse {
+    let report = health_service::readiness(pool, &jobs, &shutdown).await;
+
+    let status = if report.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(report))
+}
+
+/// Rotas de health check
+pub fn health_router(pool: Arc<Pool>, jobs: JobRegistry, shutdown: Shutdown) -> Router {
+    Router::new()
+        .route("/health", get(liveness)) // Mantido por compatibilidade
+        .route("/health/live", get(liveness))
+        .route("/health/ready", get(readiness))
+        .layer(Extension(pool))
+        .layer(Extension(jobs))
+        .layer(Extension(shutdown))
+}
@@ -6,4 +6,21 @@ pub mod user;
 pub mod service;
 pub mod availability;
 pub mod appointment;
-pub mod salon_settings;
\ No newline at end of file
+pub mod salon_settings;
+pub mod metrics;
+pub mod health;
+pub mod calendar_feed;
+pub mod busy_block;
+pub mod caldav;
+pub mod app_password;
+pub mod notification_preference;
+pub mod attendance;
+pub mod webhook;
+pub mod event_stream;
+pub mod report;
+pub mod bulk;
+pub mod openapi;
+pub mod audit;
+pub mod privacy;
+pub mod client_profile;
+pub mod guest;
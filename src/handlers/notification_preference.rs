@@ -0,0 +1,83 @@
+use axum::{
+    extract::{Extension, Json, Path},
+    http::StatusCode,
+};
+use diesel::prelude::*;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    db::Pool,
+    middleware::auth_middleware::Claims,
+    models::notification_preference::{NotificationPreference, UpdateNotificationPreference},
+    notifications::templates::SUPPORTED_LOCALES,
+    schema::notification_preferences,
+};
+
+/// 🔹 Endpoint GET `/users/:id/notification-preferences` (self ou admin)
+/// Quem nunca configurou recebe os padrões (nenhum canal ativo).
+pub async fn get_notification_preferences(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(target_id): Path<Uuid>,
+) -> Result<Json<NotificationPreference>, (StatusCode, String)> {
+    authorize(&claims, target_id)?;
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    Ok(Json(load(&mut conn, target_id)?))
+}
+
+/// 🔹 Endpoint PUT `/users/:id/notification-preferences` (self ou admin)
+/// Ex: `{"email": true, "email_address": "ana@example.com", "locale": "en"}`
+pub async fn update_notification_preferences(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(target_id): Path<Uuid>,
+    Json(payload): Json<UpdateNotificationPreference>,
+) -> Result<Json<NotificationPreference>, (StatusCode, String)> {
+    authorize(&claims, target_id)?;
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let updated = payload.apply(load(&mut conn, target_id)?);
+    if !SUPPORTED_LOCALES.contains(&updated.locale.as_str()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("locale deve ser um de: {}", SUPPORTED_LOCALES.join(", ")),
+        ));
+    }
+    if updated.email && updated.email_address.as_deref().is_none_or(|address| !address.contains('@')) {
+        return Err((StatusCode::BAD_REQUEST, "email_address válido é obrigatório para notificações por e-mail".to_string()));
+    }
+
+    let saved = diesel::insert_into(notification_preferences::table)
+        .values(&updated)
+        .on_conflict(notification_preferences::user_id)
+        .do_update()
+        .set(&updated)
+        .get_result::<NotificationPreference>(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok(Json(saved))
+}
+
+fn load(conn: &mut PgConnection, user_id: Uuid) -> Result<NotificationPreference, (StatusCode, String)> {
+    let preferences = notification_preferences::table
+        .find(user_id)
+        .first::<NotificationPreference>(conn)
+        .optional()
+        .map_err(internal_error)?;
+
+    Ok(preferences.unwrap_or_else(|| NotificationPreference::defaults(user_id)))
+}
+
+fn authorize(claims: &Claims, target_id: Uuid) -> Result<(), (StatusCode, String)> {
+    if claims.sub != target_id.to_string() && claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    Ok(())
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
@@ -0,0 +1,53 @@
+use axum::{
+    http::{header, StatusCode},
+    response::{Html, IntoResponse},
+    routing::get,
+    Json, Router,
+};
+use serde_json::Value;
+
+use crate::openapi::DOCUMENT;
+
+/// 🔹 Endpoint GET `/openapi.json` → documento OpenAPI 3.1 gerado a partir das rotas e dos modelos
+pub async fn openapi_json() -> Result<Json<Value>, (StatusCode, String)> {
+    match &*DOCUMENT {
+        Ok(document) => Ok(Json(document.clone())),
+        Err(e) => {
+            tracing::error!("❌ Falha ao gerar o documento OpenAPI: {}", e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to build OpenAPI document".to_string()))
+        }
+    }
+}
+
+/// 🔹 Endpoint GET `/docs` → Swagger UI embutido no binário (sem CDN), lendo `/openapi.json`
+pub async fn docs() -> Html<&'static str> {
+    Html(include_str!("../openapi/docs.html"))
+}
+
+/// ✅ Arquivos do Swagger UI (mesma versão do binário, podem ficar em cache)
+const ASSET_CACHE: &str = "public, max-age=86400";
+
+/// 🔹 Endpoint GET `/docs/swagger-ui.css`
+pub async fn swagger_ui_css() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/css; charset=utf-8"), (header::CACHE_CONTROL, ASSET_CACHE)],
+        include_str!("../openapi/swagger-ui/swagger-ui.css"),
+    )
+}
+
+/// 🔹 Endpoint GET `/docs/swagger-ui-bundle.js`
+pub async fn swagger_ui_bundle() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/javascript; charset=utf-8"), (header::CACHE_CONTROL, ASSET_CACHE)],
+        include_str!("../openapi/swagger-ui/swagger-ui-bundle.js"),
+    )
+}
+
+/// Rotas da documentação da API
+pub fn openapi_router() -> Router {
+    Router::new()
+        .route("/openapi.json", get(openapi_json))
+        .route("/docs", get(docs))
+        .route("/docs/swagger-ui.css", get(swagger_ui_css))
+        .route("/docs/swagger-ui-bundle.js", get(swagger_ui_bundle))
+}
@@ -0,0 +1,76 @@
+use axum::{
+    extract::{Extension, Json, Path},
+    http::{header, StatusCode},
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::Pool,
+    events::{CalendarChange, ChangeAction, EventBus},
+    middleware::{audit::Audit, auth_middleware::Claims},
+    services::privacy_service::{self, DataExport, Erasure},
+};
+
+/// 🔹 Pedidos do titular (LGPD) só podem vir dele mesmo ou do `admin_master`
+fn authorize(claims: &Claims, user_id: Uuid) -> Result<(), (StatusCode, String)> {
+    if claims.sub != user_id.to_string() && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    Ok(())
+}
+
+/// 🔹 Endpoint GET `/users/:id/data-export`: arquivo JSON com todos os dados do titular (portabilidade)
+pub async fn export_user_data(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<Uuid>,
+) -> Result<(Audit, [(header::HeaderName, String); 1], Json<DataExport>), (StatusCode, String)> {
+    authorize(&claims, user_id)?;
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    let export = privacy_service::export(&mut conn, user_id, Utc::now().naive_utc()).map_err(|e| match e {
+        diesel::result::Error::NotFound => (StatusCode::NOT_FOUND, "User not found".to_string()),
+        e => internal_error(e),
+    })?;
+
+    let disposition = format!("attachment; filename=\"data-export-{}.json\"", user_id);
+    Ok((Audit::new("data_export", "users", user_id), [(header::CONTENT_DISPOSITION, disposition)], Json(export)))
+}
+
+/// 🔹 Endpoint POST `/users/:id/erasure`: anonimiza os dados pessoais do titular (irreversível)
+pub async fn erase_user_data(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(events): Extension<EventBus>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<Uuid>,
+) -> Result<(Audit, Json<Erasure>), (StatusCode, String)> {
+    authorize(&claims, user_id)?;
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    let now = Utc::now().naive_utc();
+    // Horários dos agendamentos estão no fuso do salão
+    let local_now = now + Duration::seconds(config.calendar_utc_offset.local_minus_utc().into());
+    let erasure = privacy_service::erase(&mut conn, user_id, now, local_now)
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => (StatusCode::NOT_FOUND, "User not found".to_string()),
+            e => internal_error(e),
+        })?
+        .ok_or_else(|| (StatusCode::CONFLICT, "Os dados pessoais deste usuário já foram eliminados".to_string()))?;
+    for appointment in &erasure.canceled {
+        events.publish(CalendarChange::appointment(ChangeAction::Updated, appointment, None));
+    }
+
+    // Pedido do próprio titular: o IP dele também não fica na trilha
+    let audit = Audit::new("erasure", "users", user_id);
+    let audit = if claims.sub == user_id.to_string() { audit.without_ip() } else { audit };
+    Ok((audit, Json(erasure)))
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
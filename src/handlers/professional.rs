@@ -4,21 +4,26 @@ use axum::{
     routing::{get, post, put, delete},
     Router,
 };
+use chrono::{NaiveDateTime, Utc};
 use diesel::prelude::*;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
+    concurrency::{Conditional, IfMatch, Tagged},
     db::Pool,
+    middleware::{audit::Audit, auth_middleware::Claims},
     models::professional::{Professional, NewProfessional, UpdateProfessional},
+    pagination::{keyset, ListParams, Page, SortOrder},
     schema::professionals::dsl::*,
+    schema::users,
 };
 
 /// 🔹 Cria um novo profissional
 pub async fn create_professional(
     Extension(pool): Extension<Arc<Pool>>,
     Json(payload): Json<NewProfessional>,
-) -> Result<Json<Professional>, (StatusCode, String)> {
+) -> Result<(Audit, Json<Professional>), (StatusCode, String)> {
     let mut conn = pool.get().map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão: {}", e))
     })?;
@@ -31,79 +36,145 @@ pub async fn create_professional(
             (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao criar profissional: {}", e))
         })?;
 
-    Ok(Json(inserted))
+    Ok((Audit::created("professionals", inserted.id, &inserted), Json(inserted)))
 }
 
-/// 🔹 Lista todos os profissionais
+/// 🔹 Lista os profissionais, paginado
+/// Filtros: `q` (nome do usuário), `from`/`to` (data de cadastro), `include_deleted` (admin). Ordenação: `created_at` (padrão, desc).
 pub async fn list_professionals(
     Extension(pool): Extension<Arc<Pool>>,
-) -> Result<Json<Vec<Professional>>, (StatusCode, String)> {
+    Extension(claims): Extension<Claims>,
+    params: ListParams,
+) -> Result<Json<Page<Professional>>, (StatusCode, String)> {
+    let sort = params.accept(&["q", "from", "to", "include_deleted"], &["created_at"], SortOrder::Desc)?;
+    let include_deleted = params.filters.include_deleted.unwrap_or(false);
+    if include_deleted && claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "`include_deleted` é restrito a administradores".to_string()));
+    }
     let mut conn = pool.get().map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão: {}", e))
     })?;
 
-    let results = professionals
-        .select(Professional::as_select())
-        .order(created_at.desc())
+    let filtered = || {
+        let mut query = professionals.inner_join(users::table).select(Professional::as_select()).into_boxed();
+        if let Some(pattern) = params.filters.search_pattern() {
+            query = query.filter(users::name.ilike(pattern));
+        }
+        if let Some(start) = params.filters.starts_at() {
+            query = query.filter(created_at.ge(start));
+        }
+        if let Some(end) = params.filters.ends_before() {
+            query = query.filter(created_at.lt(end));
+        }
+        if !include_deleted {
+            query = query.filter(deleted_at.is_null());
+        }
+        query
+    };
+
+    let total = match params.cursor {
+        None => Some(filtered().count().get_result::<i64>(&mut conn).map_err(|e| {
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao contar profissionais: {}", e))
+        })?),
+        Some(_) => None,
+    };
+    let results = keyset!(filtered(), created_at, NaiveDateTime, id, params, sort)
         .load(&mut conn)
         .map_err(|e| {
             (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao listar profissionais: {}", e))
         })?;
 
-    Ok(Json(results))
+    Ok(Json(Page::new(results, &params, sort, total, |professional: &Professional| {
+        (serde_json::json!(professional.created_at), professional.id)
+    })))
 }
 
 /// 🔹 Busca um profissional específico pelo ID
 pub async fn get_professional_by_id(
     Extension(pool): Extension<Arc<Pool>>,
     Path(prof_id): Path<Uuid>,
-) -> Result<Json<Professional>, (StatusCode, String)> {
+) -> Result<Tagged<Professional>, (StatusCode, String)> {
     let mut conn = pool.get().map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão: {}", e))
     })?;
 
     let result = professionals
         .filter(id.eq(prof_id))
+        .filter(deleted_at.is_null())
         .first::<Professional>(&mut conn)
         .map_err(|_| (StatusCode::NOT_FOUND, "Profissional não encontrado".to_string()))?;
 
-    Ok(Json(result))
+    Ok(Tagged(result))
 }
 
-/// 🔹 Atualiza um profissional
+/// 🔹 Atualiza um profissional (exige `If-Match`; versão desatualizada → 412 com o profissional atual)
 pub async fn update_professional(
     Extension(pool): Extension<Arc<Pool>>,
     Path(prof_id): Path<Uuid>,
+    if_match: IfMatch,
     Json(update): Json<UpdateProfessional>,
-) -> Result<Json<Professional>, (StatusCode, String)> {
+) -> Result<(Option<Audit>, Conditional<Professional>), (StatusCode, String)> {
     let mut conn = pool.get().map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão: {}", e))
     })?;
 
-    let updated = diesel::update(professionals.filter(id.eq(prof_id)))
-        .set(&update)
-        .get_result::<Professional>(&mut conn)
-        .map_err(|e| {
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao atualizar profissional: {}", e))
+    let outcome = conn
+        .transaction(|conn| {
+            let current = professionals.filter(id.eq(prof_id)).filter(deleted_at.is_null()).for_update().first::<Professional>(conn)?;
+            if !if_match.matches(current.version) {
+                return Ok((None, Conditional::Stale(current)));
+            }
+            let updated = diesel::update(professionals.filter(id.eq(prof_id))).set(&update).get_result::<Professional>(conn)?;
+            Ok((Some(Audit::changed("update", "professionals", prof_id, &current, &updated)), Conditional::Applied(updated)))
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => (StatusCode::NOT_FOUND, "Profissional não encontrado".to_string()),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao atualizar profissional: {}", e)),
         })?;
 
-    Ok(Json(updated))
+    Ok(outcome)
 }
 
-/// 🔹 Deleta um profissional
+/// 🔹 Exclui um profissional (exclusão lógica: o histórico de agendamentos é mantido)
 pub async fn delete_professional(
     Extension(pool): Extension<Arc<Pool>>,
     Path(prof_id): Path<Uuid>,
-) -> Result<StatusCode, (StatusCode, String)> {
+) -> Result<(Audit, StatusCode), (StatusCode, String)> {
     let mut conn = pool.get().map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão: {}", e))
     })?;
 
-    diesel::delete(professionals.filter(id.eq(prof_id)))
+    diesel::update(professionals.filter(id.eq(prof_id)).filter(deleted_at.is_null()))
+        .set(deleted_at.eq(Utc::now().naive_utc()))
         .execute(&mut conn)
         .map_err(|e| {
             (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao deletar profissional: {}", e))
         })?;
 
-    Ok(StatusCode::NO_CONTENT)
+    Ok((Audit::new("delete", "professionals", prof_id), StatusCode::NO_CONTENT))
+}
+
+/// 🔹 Restaura um profissional excluído (apenas admin)
+pub async fn restore_professional(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(prof_id): Path<Uuid>,
+) -> Result<(Audit, Tagged<Professional>), (StatusCode, String)> {
+    if claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    let mut conn = pool.get().map_err(|e| {
+        (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão: {}", e))
+    })?;
+
+    let restored = diesel::update(professionals.filter(id.eq(prof_id)))
+        .set(deleted_at.eq(None::<NaiveDateTime>))
+        .get_result::<Professional>(&mut conn)
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => (StatusCode::NOT_FOUND, "Profissional não encontrado".to_string()),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao restaurar profissional: {}", e)),
+        })?;
+
+    Ok((Audit::new("restore", "professionals", prof_id), Tagged(restored)))
 }
@@ -0,0 +1,92 @@
+use axum::{
+    extract::{Extension, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    db::Pool,
+    middleware::auth_middleware::Claims,
+    services::report_service::{self, GroupBy, ReportFilter},
+};
+
+/// 🔹 Intervalo máximo de um relatório (os dados são agregados em memória)
+const MAX_RANGE_DAYS: i64 = 366;
+
+/// 🔹 Formato da resposta
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// 🔹 Parâmetros (`?from=YYYY-MM-DD&to=YYYY-MM-DD&professional_id=...&service_id=...&group_by=week&format=csv`)
+#[derive(Debug, Deserialize)]
+pub struct ReportQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub professional_id: Option<Uuid>,
+    pub service_id: Option<Uuid>,
+    #[serde(default)]
+    pub group_by: GroupBy,
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+/// 🔹 Endpoint GET `/reports` (somente admin): receita, cancelamentos, faltas e ocupação por período
+pub async fn get_report(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<ReportQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    if claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    if query.to < query.from {
+        return Err((StatusCode::BAD_REQUEST, "`to` deve ser igual ou posterior a `from`".to_string()));
+    }
+    if (query.to - query.from).num_days() >= MAX_RANGE_DAYS {
+        return Err((StatusCode::BAD_REQUEST, format!("Intervalo máximo de {} dias", MAX_RANGE_DAYS)));
+    }
+
+    let filter = ReportFilter {
+        from: query.from,
+        to: query.to,
+        professional_id: query.professional_id,
+        service_id: query.service_id,
+    };
+    let group_by = query.group_by;
+    let report = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get().map_err(internal_error)?;
+        report_service::generate(&mut conn, &filter, group_by).map_err(internal_error)
+    })
+    .await
+    .map_err(internal_error)??;
+
+    Ok(match query.format {
+        ReportFormat::Json => Json(report).into_response(),
+        ReportFormat::Csv => {
+            let filename = format!("attachment; filename=\"report-{}-{}.csv\"", report.from, report.to);
+            (
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                    (header::CONTENT_DISPOSITION, filename),
+                ],
+                report_service::to_csv(&report),
+            )
+                .into_response()
+        }
+    })
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
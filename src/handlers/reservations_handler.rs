@@ -5,15 +5,17 @@ use axum::{
 use diesel::prelude::*;
 use uuid::Uuid;
 use crate::db::Pool;
+use crate::events::{CalendarChange, ChangeAction, DomainEvent, EventBus, EventKind};
 use crate::models::reservation::{Reservation, NewReservation};
 use crate::schema::reservations;
-use crate::services::reservation_service;
+use crate::services::{outbox, reservation_service};
 use tracing::error;
 use std::sync::Arc;  // Certifique-se de importar `Arc`
 
 #[axum::debug_handler]
 pub async fn create_reservation(
     Extension(pool): Extension<Arc<Pool>>,  // Recebendo Arc<Pool>
+    Extension(events): Extension<EventBus>, // Alterações da agenda ao vivo (SSE)
     Extension(user_id): Extension<Uuid>,   // Obtém `user_id` autenticado via middleware
     Json(payload): Json<NewReservation>,
 ) -> Result<Json<Reservation>, (StatusCode, String)> {
@@ -31,15 +33,21 @@ pub async fn create_reservation(
         status: "pending".to_string(),
     };
 
-    // Insere a reserva no banco de dados
-    let reservation = diesel::insert_into(reservations::table)
-        .values(&new_reservation)
-        .get_result::<Reservation>(&mut conn)
+    // Insere a reserva no banco de dados junto com o evento (outbox)
+    let reservation = conn
+        .transaction(|conn| {
+            let reservation = diesel::insert_into(reservations::table)
+                .values(&new_reservation)
+                .get_result::<Reservation>(conn)?;
+            outbox::record_event(conn, &DomainEvent::from_reservation(EventKind::Created, &reservation))?;
+            Ok::<_, diesel::result::Error>(reservation)
+        })
         .map_err(|e| {
             error!("❌ Erro ao criar a reserva: {:?}", e);
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
         })?;
 
+    events.publish(CalendarChange::reservation(ChangeAction::Created, &reservation, None));
     Ok(Json(reservation))  // Retorna a reserva criada
 }
 
@@ -3,8 +3,10 @@ use diesel::prelude::*;
 use std::sync::Arc;
 use uuid::Uuid;
 use crate::{
+    concurrency::{Conditional, IfMatch, Tagged},
     db::Pool,
-    models::salon_settings::{SalonSetting, NewSalonSetting, UpdateSalonSetting},
+    middleware::audit::Audit,
+    models::salon_settings::{validate_reminder_offsets, SalonSetting, NewSalonSetting, UpdateSalonSetting},
     schema::salon_settings::dsl::*,
 };
 
@@ -12,7 +14,11 @@ use crate::{
 pub async fn create_salon_setting(
     Extension(pool): Extension<Arc<Pool>>,  // Agora recebendo Arc<Pool>
     Json(payload): Json<NewSalonSetting>,
-) -> Result<Json<SalonSetting>, (StatusCode, String)> {
+) -> Result<(Audit, Json<SalonSetting>), (StatusCode, String)> {
+    if let Some(offsets) = &payload.reminder_offsets_hours {
+        validate_reminder_offsets(offsets).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
+
     let mut conn = pool.get().map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão: {}", e))
     })?;
@@ -25,13 +31,13 @@ pub async fn create_salon_setting(
             (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao criar configuração: {}", e))
         })?;
 
-    Ok(Json(new_setting))
+    Ok((Audit::created("salon_settings", new_setting.id, &new_setting), Json(new_setting)))
 }
 
 // 🔹 Lista a configuração atual do salão
 pub async fn get_salon_setting(
     Extension(pool): Extension<Arc<Pool>>,  // Agora recebendo Arc<Pool>
-) -> Result<Json<SalonSetting>, (StatusCode, String)> {
+) -> Result<Tagged<SalonSetting>, (StatusCode, String)> {
     let mut conn = pool.get().map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão: {}", e))
     })?;
@@ -41,26 +47,40 @@ pub async fn get_salon_setting(
         .first::<SalonSetting>(&mut conn)
         .map_err(|_| (StatusCode::NOT_FOUND, "Configuração do salão não encontrada".to_string()))?;
 
-    Ok(Json(setting))
+    Ok(Tagged(setting))
 }
 
-// 🔹 Atualiza a configuração do salão
+// 🔹 Atualiza a configuração do salão (exige `If-Match`; versão desatualizada → 412 com a configuração atual)
 pub async fn update_salon_setting(
     Extension(pool): Extension<Arc<Pool>>,  // Agora recebendo Arc<Pool>
     Path(salon_id): Path<Uuid>,  // Mudança: alterado `id` para `salon_id` para evitar conflito com o nome da coluna
+    if_match: IfMatch,
     Json(update): Json<UpdateSalonSetting>,
-) -> Result<Json<SalonSetting>, (StatusCode, String)> {
+) -> Result<(Option<Audit>, Conditional<SalonSetting>), (StatusCode, String)> {
+    if let Some(offsets) = &update.reminder_offsets_hours {
+        validate_reminder_offsets(offsets).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
+
     let mut conn = pool.get().map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão: {}", e))
     })?;
 
-    // Atualiza a configuração do salão usando o salon_id
-    let updated_setting = diesel::update(salon_settings.filter(id.eq(salon_id)))  // Usando `salon_id` ao invés de `id`
-        .set(update)
-        .get_result::<SalonSetting>(&mut conn)
-        .map_err(|e| {
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao atualizar configuração: {}", e))
+    // Atualiza a configuração do salão usando o salon_id, se o cliente editou a versão atual
+    let outcome = conn
+        .transaction(|conn| {
+            let current = salon_settings.filter(id.eq(salon_id)).for_update().first::<SalonSetting>(conn)?;
+            if !if_match.matches(current.version) {
+                return Ok((None, Conditional::Stale(current)));
+            }
+            let updated = diesel::update(salon_settings.filter(id.eq(salon_id)))  // Usando `salon_id` ao invés de `id`
+                .set(update)
+                .get_result::<SalonSetting>(conn)?;
+            Ok((Some(Audit::changed("update", "salon_settings", salon_id, &current, &updated)), Conditional::Applied(updated)))
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => (StatusCode::NOT_FOUND, "Configuração do salão não encontrada".to_string()),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao atualizar configuração: {}", e)),
         })?;
 
-    Ok(Json(updated_setting))
+    Ok(outcome)
 }
@@ -4,13 +4,17 @@ use axum::{
     routing::{get, post, put, delete},
     Router,
 };
+use chrono::{NaiveDateTime, Utc};
 use diesel::prelude::*;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
+    concurrency::{Conditional, IfMatch, Tagged},
     db::Pool,
+    middleware::{audit::Audit, auth_middleware::Claims},
     models::service::{Service, NewService, UpdateService},
+    pagination::{keyset, ListParams, Page, SortOrder},
     schema::services::dsl::*,
 };
 
@@ -18,7 +22,7 @@ use crate::{
 pub async fn create_service(
     Extension(pool): Extension<Arc<Pool>>,  // Agora utilizando Arc<Pool>
     Json(payload): Json<NewService>,
-) -> Result<Json<Service>, (StatusCode, String)> {
+) -> Result<(Audit, Json<Service>), (StatusCode, String)> {
     let mut conn = pool.get().map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão com o banco: {}", e))
     })?;
@@ -30,78 +34,144 @@ pub async fn create_service(
             (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao criar serviço: {}", e))
         })?;
 
-    Ok(Json(new_service))  // Retorna o serviço criado
+    Ok((Audit::created("services", new_service.id, &new_service), Json(new_service)))  // Retorna o serviço criado
 }
 
-/// 🔹 Lista todos os serviços
+/// 🔹 Lista os serviços, paginado
+/// Filtros: `active` (padrão `true`: apenas serviços ativos), `q` (nome), `include_deleted` (admin). Ordenação: `nome` (padrão), `preco`, `duracao_min`.
 pub async fn list_services(
     Extension(pool): Extension<Arc<Pool>>,  // Agora utilizando Arc<Pool>
-) -> Result<Json<Vec<Service>>, (StatusCode, String)> {
+    Extension(claims): Extension<Claims>,
+    params: ListParams,
+) -> Result<Json<Page<Service>>, (StatusCode, String)> {
+    let sort = params.accept(&["active", "q", "include_deleted"], &["nome", "preco", "duracao_min"], SortOrder::Asc)?;
+    let include_deleted = params.filters.include_deleted.unwrap_or(false);
+    if include_deleted && claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "`include_deleted` é restrito a administradores".to_string()));
+    }
     let mut conn = pool.get().map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão com o banco: {}", e))
     })?;
 
-    let results = services
-        .filter(ativo.eq(true)) // Apenas serviços ativos
-        .load::<Service>(&mut conn)
-        .map_err(|e| {
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao buscar serviços: {}", e))
-        })?;
+    let filtered = || {
+        let mut query = services.filter(ativo.eq(params.filters.active.unwrap_or(true))).into_boxed();
+        if let Some(pattern) = params.filters.search_pattern() {
+            query = query.filter(nome.ilike(pattern));
+        }
+        if !include_deleted {
+            query = query.filter(deleted_at.is_null());
+        }
+        query
+    };
+
+    let total = match params.cursor {
+        None => Some(filtered().count().get_result::<i64>(&mut conn).map_err(|e| {
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao contar serviços: {}", e))
+        })?),
+        Some(_) => None,
+    };
+    let results = match sort.field {
+        "preco" => keyset!(filtered(), preco, f64, id, params, sort).load::<Service>(&mut conn),
+        "duracao_min" => keyset!(filtered(), duracao_min, i32, id, params, sort).load::<Service>(&mut conn),
+        _ => keyset!(filtered(), nome, String, id, params, sort).load::<Service>(&mut conn),
+    }
+    .map_err(|e| {
+        (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao buscar serviços: {}", e))
+    })?;
 
-    Ok(Json(results))  // Retorna a lista de serviços
+    Ok(Json(Page::new(results, &params, sort, total, |service| match sort.field {
+        "preco" => (serde_json::json!(service.preco), service.id),
+        "duracao_min" => (serde_json::json!(service.duracao_min), service.id),
+        _ => (serde_json::json!(service.nome), service.id),
+    })))
 }
 
 /// 🔹 Busca serviço por ID
 pub async fn get_service_by_id(
     Extension(pool): Extension<Arc<Pool>>,  // Agora utilizando Arc<Pool>
     Path(service_id): Path<Uuid>,
-) -> Result<Json<Service>, (StatusCode, String)> {
+) -> Result<Tagged<Service>, (StatusCode, String)> {
     let mut conn = pool.get().map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão com o banco: {}", e))
     })?;
 
     let service = services
         .filter(id.eq(service_id))
+        .filter(deleted_at.is_null())
         .first::<Service>(&mut conn)
         .map_err(|_| (StatusCode::NOT_FOUND, "Serviço não encontrado".to_string()))?;
 
-    Ok(Json(service))  // Retorna o serviço encontrado
+    Ok(Tagged(service))  // Retorna o serviço encontrado (com `ETag`)
 }
 
-/// 🔹 Atualiza serviço
+/// 🔹 Atualiza serviço (exige `If-Match` com a versão lida; versão desatualizada → 412 com o serviço atual)
 pub async fn update_service(
     Extension(pool): Extension<Arc<Pool>>,  // Agora utilizando Arc<Pool>
     Path(service_id): Path<Uuid>,
+    if_match: IfMatch,
     Json(update): Json<UpdateService>,
-) -> Result<Json<Service>, (StatusCode, String)> {
+) -> Result<(Option<Audit>, Conditional<Service>), (StatusCode, String)> {
     let mut conn = pool.get().map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão com o banco: {}", e))
     })?;
 
-    let updated_service = diesel::update(services.filter(id.eq(service_id)))
-        .set(update)
-        .get_result::<Service>(&mut conn)
-        .map_err(|e| {
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao atualizar serviço: {}", e))
+    let outcome = conn
+        .transaction(|conn| {
+            let current = services.filter(id.eq(service_id)).filter(deleted_at.is_null()).for_update().first::<Service>(conn)?;
+            if !if_match.matches(current.version) {
+                return Ok((None, Conditional::Stale(current)));
+            }
+            let updated = diesel::update(services.filter(id.eq(service_id))).set(update).get_result::<Service>(conn)?;
+            Ok((Some(Audit::changed("update", "services", service_id, &current, &updated)), Conditional::Applied(updated)))
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => (StatusCode::NOT_FOUND, "Serviço não encontrado".to_string()),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao atualizar serviço: {}", e)),
         })?;
 
-    Ok(Json(updated_service))  // Retorna o serviço atualizado
+    Ok(outcome)  // Retorna o serviço atualizado (ou o atual, se o cliente estava desatualizado)
 }
 
-/// 🔹 Deleta serviço
+/// 🔹 Exclui serviço (exclusão lógica: os agendamentos e relatórios continuam apontando para ele)
 pub async fn delete_service(
     Extension(pool): Extension<Arc<Pool>>,  // Agora utilizando Arc<Pool>
     Path(service_id): Path<Uuid>,
-) -> Result<StatusCode, (StatusCode, String)> {
+) -> Result<(Audit, StatusCode), (StatusCode, String)> {
     let mut conn = pool.get().map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão com o banco: {}", e))
     })?;
 
-    diesel::delete(services.filter(id.eq(service_id)))
+    diesel::update(services.filter(id.eq(service_id)).filter(deleted_at.is_null()))
+        .set(deleted_at.eq(Utc::now().naive_utc()))
         .execute(&mut conn)
         .map_err(|e| {
             (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao deletar serviço: {}", e))
         })?;
 
-    Ok(StatusCode::NO_CONTENT)  // Retorna 204 (sem conteúdo)
+    Ok((Audit::new("delete", "services", service_id), StatusCode::NO_CONTENT))  // Retorna 204 (sem conteúdo)
+}
+
+/// 🔹 Restaura serviço excluído (apenas admin; antes da remoção definitiva pelo job de retenção)
+pub async fn restore_service(
+    Extension(pool): Extension<Arc<Pool>>,  // Agora utilizando Arc<Pool>
+    Extension(claims): Extension<Claims>,
+    Path(service_id): Path<Uuid>,
+) -> Result<(Audit, Tagged<Service>), (StatusCode, String)> {
+    if claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    let mut conn = pool.get().map_err(|e| {
+        (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao obter conexão com o banco: {}", e))
+    })?;
+
+    let restored = diesel::update(services.filter(id.eq(service_id)))
+        .set(deleted_at.eq(None::<NaiveDateTime>))
+        .get_result::<Service>(&mut conn)
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => (StatusCode::NOT_FOUND, "Serviço não encontrado".to_string()),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao restaurar serviço: {}", e)),
+        })?;
+
+    Ok((Audit::new("restore", "services", service_id), Tagged(restored)))
 }
@@ -4,30 +4,66 @@ use axum::{
     routing::{get, put, delete, patch},
     Router,
 };
+use chrono::{NaiveDateTime, Utc};
 use diesel::prelude::*;
 use uuid::Uuid;
 use std::sync::Arc;
 
 use crate::{
+    concurrency::{Conditional, IfMatch, Tagged},
     db::Pool,
     models::user::{User, UpdateUser},
     schema::users::dsl::*,
-    middleware::auth_middleware::Claims,
+    schema::professionals,
+    middleware::{audit::Audit, auth_middleware::Claims},
+    pagination::{keyset, ListParams, Page, SortOrder},
 };
 
-/// 🔹 Lista todos os usuários (apenas admin)
+/// 🔹 Lista os usuários (apenas admin), paginado
+/// Filtros: `q` (nome ou telefone), `from`/`to` (data de cadastro), `include_deleted`. Ordenação: `created_at` (padrão, desc), `name`.
 pub async fn list_users(
     Extension(pool): Extension<Arc<Pool>>,  // Corrigido para usar Arc<Pool>
     Extension(claims): Extension<Claims>,
-) -> Result<Json<Vec<User>>, (StatusCode, String)> {
+    params: ListParams,
+) -> Result<Json<Page<User>>, (StatusCode, String)> {
     if claims.role != "admin" && claims.role != "admin_master" {
         return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
     }
+    let sort = params.accept(&["q", "from", "to", "include_deleted"], &["created_at", "name"], SortOrder::Desc)?;
+    let include_deleted = params.filters.include_deleted.unwrap_or(false);
+
+    let filtered = || {
+        let mut query = users.into_boxed();
+        if let Some(pattern) = params.filters.search_pattern() {
+            query = query.filter(name.ilike(pattern.clone()).or(phone.ilike(pattern)));
+        }
+        if let Some(start) = params.filters.starts_at() {
+            query = query.filter(created_at.ge(start));
+        }
+        if let Some(end) = params.filters.ends_before() {
+            query = query.filter(created_at.lt(end));
+        }
+        if !include_deleted {
+            query = query.filter(deleted_at.is_null());
+        }
+        query
+    };
 
     let mut conn = pool.get().map_err(internal_error)?;
-    let results = users.load::<User>(&mut conn).map_err(internal_error)?;
+    let total = match params.cursor {
+        None => Some(filtered().count().get_result::<i64>(&mut conn).map_err(internal_error)?),
+        Some(_) => None,
+    };
+    let results = match sort.field {
+        "name" => keyset!(filtered(), name, String, id, params, sort).load::<User>(&mut conn),
+        _ => keyset!(filtered(), created_at, NaiveDateTime, id, params, sort).load::<User>(&mut conn),
+    }
+    .map_err(internal_error)?;
 
-    Ok(Json(results))
+    Ok(Json(Page::new(results, &params, sort, total, |user| match sort.field {
+        "name" => (serde_json::json!(user.name), user.id),
+        _ => (serde_json::json!(user.created_at), user.id),
+    })))
 }
 
 /// 🔹 Busca usuário por ID (autorizado ou admin)
@@ -35,7 +71,7 @@ pub async fn get_user_by_id(
     Extension(pool): Extension<Arc<Pool>>,  // Corrigido para usar Arc<Pool>
     Extension(claims): Extension<Claims>,
     Path(target_id): Path<Uuid>,
-) -> Result<Json<User>, (StatusCode, String)> {
+) -> Result<Tagged<User>, (StatusCode, String)> {
     if claims.sub != target_id.to_string() && claims.role != "admin" && claims.role != "admin_master" {
         return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
     }
@@ -43,50 +79,106 @@ pub async fn get_user_by_id(
     let mut conn = pool.get().map_err(internal_error)?;
     let user_data = users
         .filter(id.eq(target_id))
+        .filter(deleted_at.is_null())
         .first::<User>(&mut conn)
         .map_err(|_| (StatusCode::NOT_FOUND, "User not found".to_string()))?;
 
-    Ok(Json(user_data))
+    Ok(Tagged(user_data))
 }
 
-/// 🔹 Atualiza dados do usuário (self ou admin)
+/// 🔹 Atualiza dados do usuário (self ou admin; exige `If-Match`, versão desatualizada → 412 com o usuário atual)
 pub async fn update_user(
     Extension(pool): Extension<Arc<Pool>>,  // Corrigido para usar Arc<Pool>
     Extension(claims): Extension<Claims>,
     Path(target_id): Path<Uuid>,
+    if_match: IfMatch,
     Json(update): Json<UpdateUser>,
-) -> Result<Json<User>, (StatusCode, String)> {
+) -> Result<(Option<Audit>, Conditional<User>), (StatusCode, String)> {
     if claims.sub != target_id.to_string() && claims.role != "admin" && claims.role != "admin_master" {
         return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
     }
 
     let mut conn = pool.get().map_err(internal_error)?;
 
-    let updated = diesel::update(users.filter(id.eq(target_id)))
-        .set(update)
-        .get_result::<User>(&mut conn)
-        .map_err(internal_error)?;
-
-    Ok(Json(updated))
+    let outcome = conn
+        .transaction(|conn| {
+            let current = users.filter(id.eq(target_id)).filter(deleted_at.is_null()).for_update().first::<User>(conn)?;
+            if !if_match.matches(current.version) {
+                return Ok((None, Conditional::Stale(current)));
+            }
+            let updated = diesel::update(users.filter(id.eq(target_id))).set(update).get_result::<User>(conn)?;
+            Ok((Some(Audit::changed("update", "users", target_id, &current, &updated)), Conditional::Applied(updated)))
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => (StatusCode::NOT_FOUND, "User not found".to_string()),
+            e => internal_error(e),
+        })?;
+
+    Ok(outcome)
 }
 
-/// 🔹 Deleta um usuário (self ou admin)
+/// 🔹 Exclui um usuário (self ou admin): exclusão lógica do usuário e do seu perfil de profissional
 pub async fn delete_user(
     Extension(pool): Extension<Arc<Pool>>,  // Corrigido para usar Arc<Pool>
     Extension(claims): Extension<Claims>,
     Path(target_id): Path<Uuid>,
-) -> Result<StatusCode, (StatusCode, String)> {
+) -> Result<(Audit, StatusCode), (StatusCode, String)> {
     if claims.sub != target_id.to_string() && claims.role != "admin" && claims.role != "admin_master" {
         return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
     }
 
     let mut conn = pool.get().map_err(internal_error)?;
 
-    diesel::delete(users.filter(id.eq(target_id)))
-        .execute(&mut conn)
-        .map_err(internal_error)?;
+    let now = Utc::now().naive_utc();
+    conn.transaction(|conn| {
+        let deleted = diesel::update(users.filter(id.eq(target_id)).filter(deleted_at.is_null()))
+            .set(deleted_at.eq(now))
+            .execute(conn)?;
+        if deleted > 0 {
+            // Mesmo instante nos dois registros: a restauração do usuário devolve o perfil junto
+            diesel::update(professionals::table.filter(professionals::user_id.eq(target_id)).filter(professionals::deleted_at.is_null()))
+                .set(professionals::deleted_at.eq(now))
+                .execute(conn)?;
+        }
+        Ok::<_, diesel::result::Error>(())
+    })
+    .map_err(internal_error)?;
+
+    Ok((Audit::new("delete", "users", target_id), StatusCode::NO_CONTENT))
+}
 
-    Ok(StatusCode::NO_CONTENT)
+/// 🔹 Restaura um usuário excluído (apenas admin), junto com o perfil de profissional excluído com ele
+/// (usuários com os dados pessoais eliminados não são restaurados: 404)
+pub async fn restore_user(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(target_id): Path<Uuid>,
+) -> Result<(Audit, Tagged<User>), (StatusCode, String)> {
+    if claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let restored = conn
+        .transaction(|conn| {
+            // Contas anonimizadas (LGPD) não voltam
+            let current = users.filter(id.eq(target_id)).filter(anonymized_at.is_null()).for_update().first::<User>(conn)?;
+            if let Some(deleted) = current.deleted_at {
+                diesel::update(professionals::table.filter(professionals::user_id.eq(target_id)).filter(professionals::deleted_at.eq(deleted)))
+                    .set(professionals::deleted_at.eq(None::<NaiveDateTime>))
+                    .execute(conn)?;
+            }
+            diesel::update(users.filter(id.eq(target_id)))
+                .set(deleted_at.eq(None::<NaiveDateTime>))
+                .get_result::<User>(conn)
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => (StatusCode::NOT_FOUND, "User not found".to_string()),
+            e => internal_error(e),
+        })?;
+
+    Ok((Audit::new("restore", "users", target_id), Tagged(restored)))
 }
 
 /// 🔹 Admin atualiza o `role` de um usuário
@@ -100,22 +192,59 @@ pub async fn update_user_role(
     Extension(claims): Extension<Claims>,
     Path(target_id): Path<Uuid>,
     Json(body): Json<RoleUpdate>,
-) -> Result<Json<User>, (StatusCode, String)> {
+) -> Result<(Audit, Json<User>), (StatusCode, String)> {
     if claims.role != "admin" && claims.role != "admin_master" {
         return Err((StatusCode::FORBIDDEN, "Only admin can update roles".to_string()));
     }
 
     let mut conn = pool.get().map_err(internal_error)?;
 
-    let updated = diesel::update(users.filter(id.eq(target_id)))
-        .set(role.eq(body.role))
-        .get_result::<User>(&mut conn)
-        .map_err(internal_error)?;
-
-    Ok(Json(updated))
+    let (previous, updated) = conn
+        .transaction(|conn| {
+            let current = users.filter(id.eq(target_id)).filter(deleted_at.is_null()).for_update().first::<User>(conn)?;
+            let updated = diesel::update(users.filter(id.eq(target_id))).set(role.eq(body.role)).get_result::<User>(conn)?;
+            Ok::<_, diesel::result::Error>((current, updated))
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => (StatusCode::NOT_FOUND, "User not found".to_string()),
+            e => internal_error(e),
+        })?;
+
+    Ok((Audit::changed("role_change", "users", target_id, &previous, &updated), Json(updated)))
 }
 
 // 🔧 Utilitário para converter erros internos
 fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
     (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db::{self, fixtures};
+
+    #[tokio::test]
+    async fn test_restore_user_brings_back_professional_but_not_anonymized() {
+        let Some(db) = test_db::setup() else { return };
+        let mut conn = db.pool.get().unwrap();
+        let admin = Claims { sub: Uuid::new_v4().to_string(), exp: 0, role: "admin".to_string() };
+        let deleted = Utc::now().naive_utc();
+
+        let professional = fixtures::professional(&mut conn, "5511900000010");
+        let target = professionals::table.find(professional).select(professionals::user_id).first::<Uuid>(&mut conn).unwrap();
+        diesel::update(users.find(target)).set(deleted_at.eq(deleted)).execute(&mut conn).unwrap();
+        diesel::update(professionals::table.find(professional)).set(professionals::deleted_at.eq(deleted)).execute(&mut conn).unwrap();
+
+        let (_, Tagged(restored)) = restore_user(Extension(db.pool.clone()), Extension(admin.clone()), Path(target)).await.unwrap();
+        assert_eq!(restored.deleted_at, None);
+        let professional_deleted = professionals::table.find(professional).select(professionals::deleted_at).first::<Option<NaiveDateTime>>(&mut conn).unwrap();
+        assert_eq!(professional_deleted, None);
+
+        let erased = fixtures::user(&mut conn, "client", "5511900000011");
+        diesel::update(users.find(erased)).set((deleted_at.eq(deleted), anonymized_at.eq(deleted))).execute(&mut conn).unwrap();
+        let Err((status, _)) = restore_user(Extension(db.pool.clone()), Extension(admin), Path(erased)).await else {
+            panic!("usuário anonimizado não deve ser restaurado");
+        };
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,249 @@
+use axum::{
+    extract::{Extension, Json, Path},
+    http::StatusCode,
+};
+use chrono::Utc;
+use diesel::prelude::*;
+use serde::Serialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    db::Pool,
+    middleware::auth_middleware::Claims,
+    models::webhook::{
+        CreateWebhookSubscription, NewWebhookSubscription, UpdateWebhookSubscription, WebhookDelivery,
+        WebhookSubscription,
+    },
+    schema::{webhook_deliveries, webhook_subscriptions},
+    services::webhook_service::{self, EVENT_TYPES},
+};
+
+/// 🔹 Entregas devolvidas no log (mais recentes primeiro)
+const DELIVERY_LOG_LIMIT: i64 = 100;
+
+/// 🔹 Resposta da criação: o segredo só é exibido aqui
+#[derive(Serialize)]
+pub struct CreatedWebhookSubscription {
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+    pub secret: String,
+}
+
+/// 🔹 Endpoint POST `/webhooks` (somente admin)
+pub async fn create_webhook(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateWebhookSubscription>,
+) -> Result<(StatusCode, Json<CreatedWebhookSubscription>), (StatusCode, String)> {
+    authorize(&claims)?;
+    validate_url(&payload.url)?;
+    let event_types = validate_event_types(payload.event_types)?;
+    let secret = match payload.secret {
+        Some(secret) if !secret.trim().is_empty() => secret,
+        _ => webhook_service::generate_secret(),
+    };
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    let subscription = diesel::insert_into(webhook_subscriptions::table)
+        .values(&NewWebhookSubscription {
+            url: payload.url.trim().to_string(),
+            event_types,
+            secret: secret.clone(),
+        })
+        .get_result::<WebhookSubscription>(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok((StatusCode::CREATED, Json(CreatedWebhookSubscription { subscription, secret })))
+}
+
+/// 🔹 Endpoint GET `/webhooks` (somente admin)
+pub async fn list_webhooks(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<WebhookSubscription>>, (StatusCode, String)> {
+    authorize(&claims)?;
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let subscriptions = webhook_subscriptions::table
+        .order(webhook_subscriptions::created_at.asc())
+        .load::<WebhookSubscription>(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok(Json(subscriptions))
+}
+
+/// 🔹 Endpoint GET `/webhooks/:id` (somente admin)
+pub async fn get_webhook(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<Json<WebhookSubscription>, (StatusCode, String)> {
+    authorize(&claims)?;
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let subscription = webhook_subscriptions::table
+        .find(webhook_id)
+        .first::<WebhookSubscription>(&mut conn)
+        .optional()
+        .map_err(internal_error)?
+        .ok_or_else(not_found)?;
+
+    Ok(Json(subscription))
+}
+
+/// 🔹 Endpoint PUT `/webhooks/:id` (somente admin)
+pub async fn update_webhook(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(webhook_id): Path<Uuid>,
+    Json(mut payload): Json<UpdateWebhookSubscription>,
+) -> Result<Json<WebhookSubscription>, (StatusCode, String)> {
+    authorize(&claims)?;
+    if let Some(url) = &payload.url {
+        validate_url(url)?;
+        payload.url = Some(url.trim().to_string());
+    }
+    if let Some(event_types) = payload.event_types.take() {
+        payload.event_types = Some(validate_event_types(event_types)?);
+    }
+    if payload.secret.as_deref().is_some_and(|secret| secret.trim().is_empty()) {
+        return Err((StatusCode::BAD_REQUEST, "secret não pode ser vazio".to_string()));
+    }
+
+    let mut conn = pool.get().map_err(internal_error)?;
+    let subscription = diesel::update(webhook_subscriptions::table.find(webhook_id))
+        .set((&payload, webhook_subscriptions::updated_at.eq(Utc::now().naive_utc())))
+        .get_result::<WebhookSubscription>(&mut conn)
+        .optional()
+        .map_err(internal_error)?
+        .ok_or_else(not_found)?;
+
+    Ok(Json(subscription))
+}
+
+/// 🔹 Endpoint DELETE `/webhooks/:id` (somente admin; o log de entregas é apagado junto)
+pub async fn delete_webhook(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    authorize(&claims)?;
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let deleted = diesel::delete(webhook_subscriptions::table.find(webhook_id))
+        .execute(&mut conn)
+        .map_err(internal_error)?;
+
+    if deleted == 0 {
+        return Err(not_found());
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 🔹 Endpoint POST `/webhooks/:id/ping` (somente admin): envia um evento `webhook.ping` de teste
+pub async fn ping_webhook(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<WebhookDelivery>), (StatusCode, String)> {
+    authorize(&claims)?;
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let exists = webhook_subscriptions::table
+        .find(webhook_id)
+        .select(webhook_subscriptions::id)
+        .first::<Uuid>(&mut conn)
+        .optional()
+        .map_err(internal_error)?;
+    if exists.is_none() {
+        return Err(not_found());
+    }
+
+    let delivery = webhook_service::ping(&mut conn, webhook_id).map_err(internal_error)?;
+    Ok((StatusCode::ACCEPTED, Json(delivery)))
+}
+
+/// 🔹 Endpoint GET `/webhooks/:id/deliveries` (somente admin): log das últimas entregas
+pub async fn list_deliveries(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<Json<Vec<WebhookDelivery>>, (StatusCode, String)> {
+    authorize(&claims)?;
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let deliveries = webhook_deliveries::table
+        .filter(webhook_deliveries::subscription_id.eq(webhook_id))
+        .order(webhook_deliveries::created_at.desc())
+        .limit(DELIVERY_LOG_LIMIT)
+        .load::<WebhookDelivery>(&mut conn)
+        .map_err(internal_error)?;
+
+    Ok(Json(deliveries))
+}
+
+/// 🔹 Endpoint POST `/webhooks/:id/deliveries/:delivery_id/redeliver` (somente admin)
+pub async fn redeliver(
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(claims): Extension<Claims>,
+    Path((webhook_id, delivery_id)): Path<(Uuid, Uuid)>,
+) -> Result<(StatusCode, Json<WebhookDelivery>), (StatusCode, String)> {
+    authorize(&claims)?;
+    let mut conn = pool.get().map_err(internal_error)?;
+
+    let exists = webhook_deliveries::table
+        .find(delivery_id)
+        .filter(webhook_deliveries::subscription_id.eq(webhook_id))
+        .select(webhook_deliveries::id)
+        .first::<Uuid>(&mut conn)
+        .optional()
+        .map_err(internal_error)?;
+    if exists.is_none() {
+        return Err((StatusCode::NOT_FOUND, "Entrega não encontrada".to_string()));
+    }
+
+    let delivery = webhook_service::redeliver(&mut conn, delivery_id).map_err(internal_error)?;
+    Ok((StatusCode::ACCEPTED, Json(delivery)))
+}
+
+fn validate_url(url: &str) -> Result<(), (StatusCode, String)> {
+    let url = url.trim();
+    let rest = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://")).unwrap_or_default();
+    if rest.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "url deve começar com http:// ou https://".to_string()));
+    }
+    Ok(())
+}
+
+/// 🔹 Tipos de evento conhecidos, sem repetição
+fn validate_event_types(mut event_types: Vec<String>) -> Result<Vec<String>, (StatusCode, String)> {
+    if event_types.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "event_types não pode ser vazio".to_string()));
+    }
+    if let Some(unknown) = event_types.iter().find(|event| !EVENT_TYPES.contains(&event.as_str())) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Evento desconhecido: {} (suportados: {})", unknown, EVENT_TYPES.join(", ")),
+        ));
+    }
+    event_types.sort();
+    event_types.dedup();
+    Ok(event_types)
+}
+
+fn authorize(claims: &Claims) -> Result<(), (StatusCode, String)> {
+    if claims.role != "admin" && claims.role != "admin_master" {
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    Ok(())
+}
+
+fn not_found() -> (StatusCode, String) {
+    (StatusCode::NOT_FOUND, "Webhook não encontrado".to_string())
+}
+
+// 🔧 Utilitário para converter erros internos
+fn internal_error<E: std::fmt::Debug>(err: E) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err))
+}
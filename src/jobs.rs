@@ -0,0 +1,92 @@
+use chrono::{NaiveDateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// 🔹 Estado de um worker em background
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Running,
+    Stopped,
+    Failed,
+}
+
+/// 🔹 Último status reportado por um worker
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub state: JobState,
+    pub last_heartbeat: NaiveDateTime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(skip)]
+    pub interval: Duration,
+}
+
+impl JobStatus {
+    /// ✅ Um worker em execução é considerado travado após 3 intervalos sem heartbeat
+    pub fn is_stale(&self, now: NaiveDateTime) -> bool {
+        let max_silence = chrono::Duration::from_std(self.interval * 3).unwrap_or(chrono::Duration::MAX);
+        self.state == JobState::Running && now - self.last_heartbeat > max_silence
+    }
+}
+
+/// 🔹 Registro compartilhado do status dos workers em background (usado no `/health/ready`)
+#[derive(Debug, Clone, Default)]
+pub struct JobRegistry {
+    jobs: Arc<RwLock<HashMap<String, JobStatus>>>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ✅ Registra um worker e o intervalo esperado entre heartbeats
+    pub fn register(&self, name: &str, interval: Duration) {
+        self.update(name, |status| status.interval = interval, interval);
+    }
+
+    /// ✅ Worker ainda vivo
+    pub fn heartbeat(&self, name: &str) {
+        self.update(name, |status| {
+            status.state = JobState::Running;
+            status.last_heartbeat = Utc::now().naive_utc();
+            status.last_error = None;
+        }, Duration::from_secs(60));
+    }
+
+    /// ✅ Ciclo do worker falhou (o próximo heartbeat limpa o erro)
+    pub fn failed(&self, name: &str, error: &str) {
+        self.update(name, |status| {
+            status.state = JobState::Failed;
+            status.last_error = Some(error.to_string());
+        }, Duration::from_secs(60));
+    }
+
+    /// ✅ Worker encerrado de forma limpa
+    pub fn stopped(&self, name: &str) {
+        self.update(name, |status| status.state = JobState::Stopped, Duration::from_secs(60));
+    }
+
+    /// 🔹 Cópia ordenada dos status atuais
+    pub fn snapshot(&self) -> BTreeMap<String, JobStatus> {
+        self.jobs
+            .read()
+            .map(|jobs| jobs.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default()
+    }
+
+    fn update(&self, name: &str, apply: impl FnOnce(&mut JobStatus), default_interval: Duration) {
+        if let Ok(mut jobs) = self.jobs.write() {
+            let status = jobs.entry(name.to_string()).or_insert_with(|| JobStatus {
+                state: JobState::Running,
+                last_heartbeat: Utc::now().naive_utc(),
+                last_error: None,
+                interval: default_interval,
+            });
+            apply(status);
+        }
+    }
+}
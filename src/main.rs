@@ -1,12 +1,11 @@
 use axum::{Router, Extension};
+use std::future::IntoFuture;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use std::net::SocketAddr;
-use tracing::Level;
-use tracing_subscriber::FmtSubscriber;
 use tower::ServiceBuilder;
-use diesel::r2d2::{Pool, ConnectionManager};
-use diesel::PgConnection;
+use metrics_exporter_prometheus::PrometheusHandle;
 
 mod db;
 mod models;
@@ -17,72 +16,202 @@ mod schema;
 mod config;
 mod utils;
 mod middleware;
+mod logging;
+mod errors;
+mod telemetry;
+mod jobs;
+mod shutdown;
+mod events;
+mod notifications;
+mod pagination;
+mod openapi;
+mod concurrency;
+#[cfg(test)]
+mod test_db;
 
-use crate::routes::{professionals, users, availabilities, appointments, salon_settings};
-use crate::routes::services as service_routes;
-use crate::middleware::auth_middleware::AuthMiddleware;
-use crate::middleware::rate_limit::{rate_limit_middleware, strict_rate_limit_middleware};
+use crate::routes::v1;
+use crate::middleware::rate_limit::rate_limit_middleware;
 use crate::middleware::cors::cors_middleware;
-use crate::handlers::auth::auth_router;
+use crate::middleware::deprecation::DeprecationLayer;
+use crate::handlers::metrics::metrics_router;
+use crate::handlers::health::health_router;
+use crate::handlers::caldav::caldav_router;
+use crate::handlers::openapi::openapi_router;
+use crate::config::Config;
+use crate::db::Pool;
+use crate::events::EventBus;
+use crate::jobs::JobRegistry;
+use crate::services::{busy_block_service, guest_service, idempotency_service, job_queue, outbox, reminder_service, retention_service, webhook_service};
+use crate::shutdown::Shutdown;
+use crate::middleware::request_id::RequestIdLayer;
 
 #[tokio::main]
 async fn main() {
     dotenvy::dotenv().ok();
 
-    // ✅ Inicializa logs com `tracing`
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::DEBUG)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("Falha ao configurar logs");
-
     // ✅ Carrega configurações
     let config = Arc::new(config::Config::from_env().expect("Failed to load config"));
+
+    // ✅ Inicializa o pipeline de logs (`tracing` → console + arquivo)
+    logging::init(&config).expect("Falha ao configurar logs");
+
+    // ✅ Inicializa o recorder de métricas (Prometheus)
+    let metrics_handle = telemetry::init().expect("Falha ao configurar métricas");
     
     // Inicializando o pool de conexões
     let pool = db::init_db(&config);
     
     tracing::info!("📡 Conectado ao banco de dados");
 
-    // ✅ Rotas abertas (sem autenticação) → RATE LIMIT + CORS
-    let auth_routes = auth_router(pool.clone(), config.clone())
-        .layer(
-            ServiceBuilder::new()
-                .layer(rate_limit_middleware())
-                .layer(cors_middleware())
-        );
+    // ✅ Status dos workers em background (reportado no readiness)
+    let jobs = JobRegistry::new();
+
+    // ✅ Sinal de encerramento compartilhado (servidor, readiness e workers)
+    let shutdown = Shutdown::new();
+
+    // ✅ Eventos de domínio (outbox) → notificações e webhooks; alterações da agenda ao vivo (EventBus) → SSE
+    let events = EventBus::new();
+    let notifiers = notifications::notifiers_from_config(&config);
+    let workers = vec![
+        telemetry::spawn_upkeep(metrics_handle.clone(), jobs.clone(), shutdown.clone()),
+        busy_block_service::spawn_calendar_sync(
+            pool.clone(),
+            config.calendar_utc_offset,
+            config.calendar_sync_interval,
+            jobs.clone(),
+            shutdown.clone(),
+        ),
+        outbox::spawn_relay(
+            pool.clone(),
+            vec![
+                Arc::new(notifications::NotificationConsumer::new(pool.clone(), notifiers.clone())),
+                Arc::new(webhook_service::WebhookConsumer::new(pool.clone())),
+            ],
+            config.outbox_poll_interval,
+            jobs.clone(),
+            shutdown.clone(),
+        ),
+        // ✅ Fila persistente (Postgres): lembretes de agendamento, códigos de verificação e entregas de webhooks
+        job_queue::spawn_runner(
+            pool.clone(),
+            vec![
+                Arc::new(reminder_service::ReminderJob::new(pool.clone(), notifiers.clone(), config.public_base_url.clone())),
+                Arc::new(guest_service::GuestCodeJob::new(pool.clone(), notifiers)),
+                Arc::new(webhook_service::WebhookJob::new(pool.clone())),
+            ],
+            config.job_poll_interval,
+            jobs.clone(),
+            shutdown.clone(),
+        ),
+        // ✅ Limpeza das respostas guardadas por `Idempotency-Key` (janela de 24h)
+        idempotency_service::spawn_cleanup(pool.clone(), Duration::from_secs(3600), jobs.clone(), shutdown.clone()),
+        // ✅ Remoção definitiva dos registros excluídos logicamente (retenção configurável)
+        retention_service::spawn_purge(
+            pool.clone(),
+            config.soft_delete_retention_days,
+            Duration::from_secs(3600),
+            jobs.clone(),
+            shutdown.clone(),
+        ),
+    ];
+
+    // ✅ Aplicação unificada
+    let app = app(pool, config.clone(), events, jobs, shutdown.clone(), metrics_handle);
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
+    tracing::info!("🚀 Servidor rodando em http://{}", addr);
+
+    let listener = TcpListener::bind(addr).await.unwrap();
 
-    // ✅ Rotas abertas (exemplo com `/health`)
-    let open_routes = Router::new()
-        .route("/health", axum::routing::get(|| async { "Service is running!" }))
+    // ✅ SIGINT/SIGTERM → readiness falha; após SHUTDOWN_GRACE_SECS novas conexões são recusadas e as requisições em andamento são concluídas
+    let mut server = tokio::spawn(
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+            .with_graceful_shutdown({
+                let shutdown = shutdown.clone();
+                async move { shutdown.wait().await }
+            })
+            .into_future(),
+    );
+
+    tokio::select! {
+        _ = shutdown::wait_for_signal() => {}
+        result = &mut server => {
+            // O servidor só termina sozinho em caso de erro
+            result.expect("Servidor abortado").expect("Erro no servidor");
+        }
+    }
+
+    // ✅ Primeiro só o readiness falha: o balanceador tem `shutdown_grace` para tirar a instância de rotação
+    tracing::info!("⏳ Encerrando: readiness desativado, aguardando {:?} antes de recusar conexões", config.shutdown_grace);
+    shutdown.start_draining();
+    tokio::time::sleep(config.shutdown_grace).await;
+
+    tracing::info!("⏳ Encerrando: aguardando requisições em andamento (até {:?})", config.shutdown_drain_timeout);
+    shutdown.trigger();
+
+    match tokio::time::timeout(config.shutdown_drain_timeout, &mut server).await {
+        Ok(Ok(Ok(()))) => tracing::info!("✅ Requisições em andamento concluídas"),
+        Ok(Ok(Err(e))) => tracing::error!("❌ Erro no servidor durante o encerramento: {}", e),
+        Ok(Err(e)) => tracing::error!("❌ Servidor abortado durante o encerramento: {}", e),
+        Err(_) => {
+            tracing::warn!("⚠️ Tempo de drenagem esgotado, encerrando conexões restantes");
+            server.abort();
+        }
+    }
+
+    // ✅ Aguarda os workers em background finalizarem
+    for worker in workers {
+        if let Err(e) = worker.await {
+            tracing::error!("❌ Worker finalizado com erro: {}", e);
+        }
+    }
+
+    tracing::info!("👋 Servidor encerrado");
+}
+
+/// ✅ Monta todas as rotas da API (também usado pelo teste que compara rotas e documento OpenAPI)
+fn app(
+    pool: Arc<Pool>,
+    config: Arc<Config>,
+    events: EventBus,
+    jobs: JobRegistry,
+    shutdown: Shutdown,
+    metrics_handle: PrometheusHandle,
+) -> Router {
+    // ✅ API versionada (`/v1/...`); os caminhos sem versão continuam respondendo como aliases obsoletos
+    let v1_routes = v1::router(pool.clone(), config.clone(), shutdown.clone());
+    let legacy_routes = v1_routes.clone().layer(DeprecationLayer::new(
+        config.legacy_api_deprecated_at,
+        config.legacy_api_sunset,
+        v1::PREFIX,
+    ));
+
+    // ✅ Rotas abertas (`/health`, `/health/live`, `/health/ready`)
+    let open_routes = health_router(pool.clone(), jobs.clone(), shutdown.clone())
         .layer(cors_middleware());
 
-    // ✅ Rotas protegidas (com autenticação) → RATE LIMIT + CORS + AUTH
-    let protected_routes = Router::new()
-        .nest("/professionals", professionals::router(pool.clone(), config.clone()))
-        .nest("/users", users::router(pool.clone(), config.clone()))
-        .nest("/services", service_routes::router(pool.clone(), config.clone()))
-        .nest("/availabilities", availabilities::router(pool.clone(), config.clone()))
-        .nest("/appointments", appointments::router(pool.clone(), config.clone()))
-        .nest("/salon-settings", salon_settings::router(pool.clone(), config.clone()))
-        .layer(AuthMiddleware)  // ✅ Middleware de autenticação como layer
+    // ✅ CalDAV (HTTP Basic com senha de aplicativo) → RATE LIMIT
+    let caldav_routes = caldav_router(pool.clone(), config.clone())
+        .layer(rate_limit_middleware());
+
+    // ✅ Documento OpenAPI (`/openapi.json`) e visualizador (`/docs`) → RATE LIMIT + CORS
+    let docs_routes = openapi_router()
         .layer(
             ServiceBuilder::new()
-                .layer(strict_rate_limit_middleware())
+                .layer(rate_limit_middleware())
                 .layer(cors_middleware())
         );
 
-    // ✅ Aplicação unificada
-    let app = Router::new()
-        .nest("/auth", auth_routes)
+    // ✅ Infraestrutura (health, métricas, CalDAV, documentação) fica fora do versionamento
+    Router::new()
+        .nest(v1::PREFIX, v1_routes)
+        .merge(legacy_routes)
         .merge(open_routes)
-        .merge(protected_routes)
+        .merge(caldav_routes)
+        .merge(docs_routes)
+        .merge(metrics_router(pool.clone(), config.clone(), metrics_handle))
         .layer(Extension(pool))
-        .layer(Extension(config));
-
-    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
-    tracing::info!("🚀 Servidor rodando em http://{}", addr);
-
-    let listener = TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app.into_make_service()).await.unwrap();
-}
\ No newline at end of file
+        .layer(Extension(config))
+        .layer(Extension(events))
+        .layer(RequestIdLayer)  // ✅ X-Request-Id + span + access log
+}
@@ -0,0 +1,249 @@
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, Request},
+    http::{HeaderMap, HeaderName, Method},
+    response::{IntoResponseParts, Response, ResponseParts},
+};
+use futures::future::Future;
+use serde::Serialize;
+use serde_json::Value;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::db::Pool;
+use crate::middleware::auth_middleware::Claims;
+use crate::middleware::request_id::{current_request_id, X_REQUEST_ID};
+use crate::models::audit_event::NewAuditEvent;
+use crate::services::audit_service;
+
+static X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+static X_REAL_IP: HeaderName = HeaderName::from_static("x-real-ip");
+
+/// ✅ Detalhes de auditoria que o handler anexa à resposta (ação, entidade e campos alterados).
+/// Sem ele, o `AuditLayer` registra a requisição de forma genérica (método + rota).
+#[derive(Debug, Clone)]
+pub struct Audit {
+    action: String,
+    entity_type: &'static str,
+    entity_id: Uuid,
+    before: Option<Value>,
+    after: Option<Value>,
+    without_ip: bool,
+}
+
+impl Audit {
+    /// ✅ Ação sem diff (ex: exclusão lógica, restauração)
+    pub fn new(action: &str, entity_type: &'static str, entity_id: Uuid) -> Self {
+        Audit { action: action.to_string(), entity_type, entity_id, before: None, after: None, without_ip: false }
+    }
+
+    /// ✅ Registro criado: o estado inicial completo (campos sensíveis mascarados)
+    pub fn created<T: Serialize>(entity_type: &'static str, entity_id: Uuid, after: &T) -> Self {
+        Audit { after: Some(audit_service::snapshot(after)), ..Audit::new("create", entity_type, entity_id) }
+    }
+
+    /// ✅ Registro alterado: apenas os campos que mudaram, com os valores de antes e depois
+    pub fn changed<B: Serialize, A: Serialize>(action: &str, entity_type: &'static str, entity_id: Uuid, before: &B, after: &A) -> Self {
+        let (before, after) = audit_service::diff(before, after);
+        Audit { before: Some(before), after: Some(after), ..Audit::new(action, entity_type, entity_id) }
+    }
+
+    /// ✅ Não guarda o IP da requisição (ex: eliminação de dados pessoais pedida pelo próprio titular)
+    pub fn without_ip(self) -> Self {
+        Audit { without_ip: true, ..self }
+    }
+}
+
+impl IntoResponseParts for Audit {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        res.extensions_mut().insert(self);
+        Ok(res)
+    }
+}
+
+/// Middleware da trilha de auditoria: toda requisição que altera dados (POST/PUT/PATCH/DELETE, ou que trouxe
+/// um `Audit` do handler) e termina com sucesso vira um registro em `audit_events`, com o usuário, IP e
+/// `X-Request-Id`. Deve ficar dentro do `AuthMiddleware` (para conhecer o usuário) e do `IdempotencyLayer`
+/// (respostas reenviadas não contam de novo).
+#[derive(Clone)]
+pub struct AuditLayer;
+
+impl<S> Layer<S> for AuditLayer {
+    type Service = AuditService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuditService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuditService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for AuditService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut inner = self.inner.clone();
+
+        Box::pin(async move {
+            let mutating = matches!(*req.method(), Method::POST | Method::PUT | Method::PATCH | Method::DELETE);
+            let Some(pool) = req.extensions().get::<Arc<Pool>>().cloned() else {
+                return inner.call(req).await;
+            };
+
+            let (route, entity_type, entity_id) = describe_path(req.uri().path());
+            let event = NewAuditEvent {
+                actor_id: req.extensions().get::<Uuid>().copied(),
+                actor_role: req.extensions().get::<Claims>().map(|claims| claims.role.clone()),
+                action: format!("{} {}", req.method(), route),
+                entity_type,
+                entity_id,
+                before: None,
+                after: None,
+                ip: client_ip(
+                    req.headers(),
+                    req.extensions().get::<ConnectInfo<SocketAddr>>(),
+                    req.extensions().get::<Arc<Config>>().map_or(&[], |config| config.trusted_proxies.as_slice()),
+                ),
+                request_id: req
+                    .headers()
+                    .get(&X_REQUEST_ID)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string)
+                    .or_else(current_request_id),
+            };
+
+            let mut response = inner.call(req).await?;
+            // GETs só entram quando o handler anexou um `Audit` (ex: confirmação pelo link do lembrete)
+            let audit = response.extensions_mut().remove::<Audit>();
+            if !response.status().is_success() || (!mutating && audit.is_none()) {
+                return Ok(response);
+            }
+
+            let event = match audit {
+                Some(audit) => NewAuditEvent {
+                    action: audit.action,
+                    entity_type: audit.entity_type.to_string(),
+                    entity_id: Some(audit.entity_id),
+                    before: audit.before,
+                    after: audit.after,
+                    ip: event.ip.filter(|_| !audit.without_ip),
+                    ..event
+                },
+                None => event,
+            };
+            // A alteração já foi confirmada: uma falha aqui é registrada no log, mas não muda a resposta
+            let recorded = tokio::task::spawn_blocking(move || {
+                let mut conn = pool.get().map_err(|e| e.to_string())?;
+                audit_service::record(&mut conn, &event).map_err(|e| e.to_string())
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|recorded| recorded);
+            if let Err(e) = recorded {
+                error!("❌ Falha ao gravar registro de auditoria: {}", e);
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+/// 🔹 Rota com os IDs trocados por `:id`, tipo da entidade (primeiro segmento) e o primeiro ID do caminho
+fn describe_path(path: &str) -> (String, String, Option<Uuid>) {
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    let entity_id = segments.iter().find_map(|segment| segment.parse::<Uuid>().ok());
+    let route = segments
+        .iter()
+        .map(|segment| if segment.parse::<Uuid>().is_ok() { ":id" } else { segment })
+        .fold(String::new(), |route, segment| format!("{}/{}", route, segment));
+    let entity_type = segments.first().map(|segment| segment.replace('-', "_")).unwrap_or_default();
+    (if route.is_empty() { "/".to_string() } else { route }, entity_type, entity_id)
+}
+
+/// 🔹 IP do cliente: o da conexão, a não ser que ela venha de um proxy confiável (`TRUSTED_PROXIES`).
+/// Nesse caso vale o endereço mais à direita do `X-Forwarded-For` que não seja de um proxy confiável
+/// (os da esquerda são informados pelo próprio cliente) ou, sem ele, o `X-Real-IP`.
+pub(crate) fn client_ip(headers: &HeaderMap, connection: Option<&ConnectInfo<SocketAddr>>, trusted_proxies: &[IpAddr]) -> Option<String> {
+    let peer = connection.map(|ConnectInfo(addr)| addr.ip())?;
+    if !trusted_proxies.contains(&peer) {
+        return Some(peer.to_string());
+    }
+
+    let header = |name: &HeaderName| headers.get(name).and_then(|value| value.to_str().ok());
+    let forwarded = match header(&X_FORWARDED_FOR) {
+        Some(chain) => chain
+            .rsplit(',')
+            .map(|hop| hop.trim().parse::<IpAddr>())
+            .find(|hop| !hop.as_ref().is_ok_and(|ip| trusted_proxies.contains(ip))),
+        None => header(&X_REAL_IP).map(|ip| ip.trim().parse::<IpAddr>()),
+    };
+    // Valor malformado: fica o endereço do proxy, que ao menos é real
+    Some(forwarded.and_then(Result::ok).unwrap_or(peer).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_path_normalizes_ids() {
+        let id = Uuid::new_v4();
+        let (route, entity_type, entity_id) = describe_path(&format!("/salon-settings/{}", id));
+        assert_eq!(route, "/salon-settings/:id");
+        assert_eq!(entity_type, "salon_settings");
+        assert_eq!(entity_id, Some(id));
+
+        let (route, _, entity_id) = describe_path("/webhooks");
+        assert_eq!(route, "/webhooks");
+        assert_eq!(entity_id, None);
+    }
+
+    #[test]
+    fn test_client_ip_trusts_forwarding_headers_only_from_proxies() {
+        let proxy: IpAddr = "10.0.0.2".parse().unwrap();
+        let connection = |ip: &str| ConnectInfo(SocketAddr::new(ip.parse().unwrap(), 40000));
+        let headers = |pairs: &[(&HeaderName, &str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.insert((*name).clone(), value.parse().unwrap());
+            }
+            headers
+        };
+        let spoofed = headers(&[(&X_FORWARDED_FOR, "1.2.3.4"), (&X_REAL_IP, "5.6.7.8")]);
+
+        // Conexão direta: headers ignorados
+        assert_eq!(client_ip(&spoofed, Some(&connection("203.0.113.9")), &[proxy]).as_deref(), Some("203.0.113.9"));
+        assert_eq!(client_ip(&spoofed, Some(&connection("203.0.113.9")), &[]).as_deref(), Some("203.0.113.9"));
+        assert_eq!(client_ip(&spoofed, None, &[proxy]), None);
+
+        // Via proxy: o último endereço não confiável da cadeia (o cliente só controla os da esquerda)
+        let chain = headers(&[(&X_FORWARDED_FOR, "1.2.3.4, 198.51.100.7, 10.0.0.2")]);
+        assert_eq!(client_ip(&chain, Some(&connection("10.0.0.2")), &[proxy]).as_deref(), Some("198.51.100.7"));
+        let real_ip = headers(&[(&X_REAL_IP, "198.51.100.8")]);
+        assert_eq!(client_ip(&real_ip, Some(&connection("10.0.0.2")), &[proxy]).as_deref(), Some("198.51.100.8"));
+        let garbage = headers(&[(&X_FORWARDED_FOR, "não-é-ip")]);
+        assert_eq!(client_ip(&garbage, Some(&connection("10.0.0.2")), &[proxy]).as_deref(), Some("10.0.0.2"));
+    }
+}
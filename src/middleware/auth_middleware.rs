@@ -90,8 +90,6 @@ where
                 }
             };
 
-            info!("Token recebido: {}", token);
-
             // Validação do token
             let key = DecodingKey::from_secret(config.secret_key.as_bytes());
             let decoded = decode::<Claims>(&token, &key, &Validation::default());
@@ -137,7 +135,10 @@ where
 
             info!("Acesso autorizado para usuário: {} (Role: {})", user_id, claims.role);
 
-            inner.call(req).await
+            // Expõe o usuário na resposta para o access log
+            let mut response = inner.call(req).await?;
+            response.extensions_mut().insert(user_id);
+            Ok(response)
         })
     }
 }
@@ -1,6 +1,8 @@
 use tower_http::cors::{CorsLayer, Any};
 use std::time::Duration;
 use axum::http::HeaderValue;
+use crate::middleware::idempotency::{IDEMPOTENCY_KEY, IDEMPOTENT_REPLAYED};
+use crate::middleware::request_id::X_REQUEST_ID;
 
 /// 🔐 Configuração de CORS para permitir apenas origens confiáveis
 pub fn cors_middleware() -> CorsLayer {
@@ -17,6 +19,9 @@ pub fn cors_middleware() -> CorsLayer {
         .allow_headers([
             axum::http::header::AUTHORIZATION,
             axum::http::header::CONTENT_TYPE,
+            X_REQUEST_ID.clone(),
+            IDEMPOTENCY_KEY.clone(),
         ])
+        .expose_headers([X_REQUEST_ID.clone(), IDEMPOTENT_REPLAYED.clone()])
         .max_age(Duration::from_secs(600))
 }
@@ -0,0 +1,99 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderName, HeaderValue},
+    response::Response,
+};
+use chrono::{NaiveDate, NaiveTime};
+use futures::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// 🔹 Cabeçalhos de obsolescência (RFC 9745 e RFC 8594)
+pub static DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+pub static SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// Middleware das rotas obsoletas: responde normalmente, mas anuncia desde quando a
+/// rota é obsoleta (`Deprecation`), quando deve ser removida (`Sunset`) e onde fica a
+/// versão atual (`Link: <...>; rel="successor-version"`)
+#[derive(Clone)]
+pub struct DeprecationLayer {
+    deprecation: HeaderValue,
+    sunset: HeaderValue,
+    successor_prefix: &'static str, // Prefixo da versão atual, ex: `/v1`
+}
+
+impl DeprecationLayer {
+    pub fn new(deprecated_at: NaiveDate, sunset: NaiveDate, successor_prefix: &'static str) -> Self {
+        let deprecated_at = deprecated_at.and_time(NaiveTime::MIN).and_utc().timestamp();
+        let sunset = sunset.and_time(NaiveTime::MIN).and_utc().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+
+        DeprecationLayer {
+            deprecation: HeaderValue::from_str(&format!("@{}", deprecated_at)).expect("valid header"),
+            sunset: HeaderValue::from_str(&sunset).expect("valid header"),
+            successor_prefix,
+        }
+    }
+}
+
+impl<S> Layer<S> for DeprecationLayer {
+    type Service = DeprecationService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DeprecationService { inner, layer: self.clone() }
+    }
+}
+
+#[derive(Clone)]
+pub struct DeprecationService<S> {
+    inner: S,
+    layer: DeprecationLayer,
+}
+
+impl<S> Service<Request<Body>> for DeprecationService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let successor = format!("<{}{}>; rel=\"successor-version\"", self.layer.successor_prefix, req.uri().path());
+        let layer = self.layer.clone();
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let mut response = future.await?;
+            let headers = response.headers_mut();
+            headers.insert(DEPRECATION.clone(), layer.deprecation);
+            headers.insert(SUNSET.clone(), layer.sunset);
+            if let Ok(link) = HeaderValue::from_str(&successor) {
+                headers.append(header::LINK, link);
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deprecation_header_values() {
+        let layer = DeprecationLayer::new(
+            NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+            NaiveDate::from_ymd_opt(2027, 4, 19).unwrap(),
+            "/v1",
+        );
+        assert_eq!(layer.deprecation, "@1792368000");
+        assert_eq!(layer.sunset, "Mon, 19 Apr 2027 00:00:00 GMT");
+    }
+}
@@ -0,0 +1,268 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderName, HeaderValue, Method, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use futures::future::Future;
+use sha2::{Digest, Sha256};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::routes::v1;
+use crate::services::idempotency_service::{self, Begin, StoredResponse};
+
+/// 🔹 Cabeçalho enviado pelo cliente e cabeçalho que marca uma resposta reenviada
+pub static IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+pub static IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// 🔹 Cabeçalhos da resposta original que são guardados e reenviados
+const REPLAYED_HEADERS: [HeaderName; 4] = [header::CONTENT_TYPE, header::LOCATION, header::ETAG, header::CONTENT_DISPOSITION];
+
+/// 🔹 Limites dos corpos guardados em memória/no banco (requisição para o hash, resposta para reenviar)
+const MAX_REQUEST_BODY: usize = 10 * 1024 * 1024;
+const MAX_STORED_RESPONSE: usize = 1024 * 1024;
+
+/// Middleware de idempotência dos endpoints que alteram dados (POST/PUT/PATCH/DELETE com
+/// `Idempotency-Key`): a primeira resposta fica guardada por usuário e chave, e repetições
+/// idênticas recebem a mesma resposta por 24h. Deve ficar dentro do `AuthMiddleware`.
+#[derive(Clone)]
+pub struct IdempotencyLayer;
+
+impl<S> Layer<S> for IdempotencyLayer {
+    type Service = IdempotencyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IdempotencyService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct IdempotencyService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for IdempotencyService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut inner = self.inner.clone();
+
+        Box::pin(async move {
+            let mutating = matches!(*req.method(), Method::POST | Method::PUT | Method::PATCH | Method::DELETE);
+            let key = req.headers().get(&IDEMPOTENCY_KEY).map(|value| value.to_str().unwrap_or_default().to_string());
+            let (key, user_id, pool) = match (key, req.extensions().get::<Uuid>(), req.extensions().get::<Arc<Pool>>()) {
+                (Some(key), Some(user_id), Some(pool)) if mutating => (key, *user_id, pool.clone()),
+                _ => return inner.call(req).await,
+            };
+            if !is_valid_key(&key) {
+                return Ok((StatusCode::BAD_REQUEST, "Idempotency-Key must have 1 to 255 visible ASCII characters").into_response());
+            }
+
+            let (parts, body) = req.into_parts();
+            let body = match to_bytes(body, MAX_REQUEST_BODY).await {
+                Ok(body) => body,
+                Err(_) => return Ok((StatusCode::PAYLOAD_TOO_LARGE, "Request body too large").into_response()),
+            };
+            let path = parts.uri.path_and_query().map(|path| path.as_str()).unwrap_or_else(|| parts.uri.path());
+            let hash = request_hash(&parts.method, unversioned(path), &body);
+
+            let begin = {
+                let (pool, key) = (pool.clone(), key.clone());
+                tokio::task::spawn_blocking(move || {
+                    let mut conn = pool.get().map_err(|e| e.to_string())?;
+                    idempotency_service::begin(&mut conn, user_id, &key, &hash, Utc::now().naive_utc()).map_err(|e| e.to_string())
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|begin| begin)
+            };
+            match begin {
+                Ok(Begin::Proceed) => {}
+                Ok(Begin::Replay(stored)) => return Ok(replay(stored)),
+                Ok(Begin::Unreplayable) => {
+                    return Ok((StatusCode::CONFLICT, "Request already processed; its response is too large to replay").into_response())
+                }
+                Ok(Begin::Mismatch) => {
+                    return Ok((StatusCode::UNPROCESSABLE_ENTITY, "Idempotency-Key already used with a different request").into_response())
+                }
+                Ok(Begin::InFlight) => {
+                    return Ok((StatusCode::CONFLICT, "A request with this Idempotency-Key is still in progress").into_response())
+                }
+                Err(e) => {
+                    error!("❌ Falha ao reservar Idempotency-Key: {}", e);
+                    return Ok((StatusCode::INTERNAL_SERVER_ERROR, "Failed to check Idempotency-Key").into_response());
+                }
+            }
+
+            let response = inner.call(Request::from_parts(parts, Body::from(body))).await?;
+
+            // Erros do servidor não são guardados: a próxima tentativa executa de novo
+            if response.status().is_server_error() {
+                finish(pool, user_id, key, None).await;
+                return Ok(response);
+            }
+
+            // Daqui em diante o handler já concluiu: a chave nunca é liberada, mesmo sem o corpo guardado
+            let (parts, body) = response.into_parts();
+            let status = parts.status.as_u16();
+            let headers = REPLAYED_HEADERS
+                .iter()
+                .filter_map(|name| Some((name.as_str().to_string(), parts.headers.get(name)?.to_str().ok()?.to_string())))
+                .collect();
+            let (response, body) = match to_bytes(body, usize::MAX).await {
+                Ok(body) if body.len() <= MAX_STORED_RESPONSE => (Response::from_parts(parts, Body::from(body.clone())), Some(body.to_vec())),
+                Ok(body) => (Response::from_parts(parts, Body::from(body)), None),
+                Err(e) => {
+                    error!("❌ Falha ao ler a resposta de uma requisição com Idempotency-Key: {}", e);
+                    ((StatusCode::INTERNAL_SERVER_ERROR, "Failed to read response body").into_response(), None)
+                }
+            };
+
+            let stored = StoredResponse { status, headers, body };
+            finish(pool, user_id, key, Some(stored)).await;
+            Ok(response)
+        })
+    }
+}
+
+/// 🔹 Guarda a resposta (ou libera a chave, sem resposta)
+async fn finish(pool: Arc<Pool>, user_id: Uuid, key: String, response: Option<StoredResponse>) {
+    let result = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get().map_err(|e| e.to_string())?;
+        match &response {
+            Some(response) => idempotency_service::complete(&mut conn, user_id, &key, response, Utc::now().naive_utc()),
+            None => idempotency_service::release(&mut conn, user_id, &key),
+        }
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+    if let Err(e) = result {
+        error!("❌ Falha ao registrar resposta da Idempotency-Key: {}", e);
+    }
+}
+
+/// 🔹 Resposta original, marcada com `Idempotent-Replayed: true`
+fn replay(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body.unwrap_or_default()));
+    *response.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    for (name, value) in stored.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::from_str(&value)) {
+            response.headers_mut().insert(name, value);
+        }
+    }
+    response.headers_mut().insert(IDEMPOTENT_REPLAYED.clone(), HeaderValue::from_static("true"));
+    response
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.len() <= 255 && key.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// 🔹 Caminho sem o prefixo de versão: `/v1/services` e o alias obsoleto `/services` são a mesma requisição
+fn unversioned(path: &str) -> &str {
+    match path.strip_prefix(v1::PREFIX) {
+        Some(rest) if rest.starts_with('/') || rest.starts_with('?') => rest,
+        _ => path,
+    }
+}
+
+/// 🔹 Identifica a requisição: método, caminho (com query, sem a versão) e corpo
+fn request_hash(method: &Method, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str().as_bytes());
+    hasher.update(b" ");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db::{self, fixtures};
+    use axum::{routing::post, Extension, Router};
+    use tower::ServiceExt;
+
+    async fn create_thing() -> impl IntoResponse {
+        (StatusCode::CREATED, [(header::LOCATION, "/v1/things/1"), (header::ETAG, "\"1\"")], "{}")
+    }
+
+    #[tokio::test]
+    async fn test_replay_across_version_alias_keeps_headers() {
+        assert_eq!(unversioned("/v1/services?limit=2"), "/services?limit=2");
+        assert_eq!(unversioned("/services"), "/services");
+        assert_eq!(unversioned("/v10/services"), "/v10/services");
+
+        let Some(db) = test_db::setup() else { return };
+        let user_id = fixtures::user(&mut db.pool.get().unwrap(), "client", "5511900000070");
+        let routes = Router::new()
+            .route("/things", post(create_thing))
+            .layer(IdempotencyLayer)
+            .layer(Extension(user_id))
+            .layer(Extension(db.pool.clone()));
+        let app = Router::new().nest(v1::PREFIX, routes.clone()).merge(routes);
+        let request = |path: &str| {
+            Request::post(path).header(&IDEMPOTENCY_KEY, "5f0c2f5e").body(Body::from(r#"{"name":"x"}"#)).unwrap()
+        };
+
+        let first = app.clone().oneshot(request("/v1/things")).await.unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert!(first.headers().get(&IDEMPOTENT_REPLAYED).is_none());
+
+        let replayed = app.oneshot(request("/things")).await.unwrap();
+        assert_eq!(replayed.status(), StatusCode::CREATED);
+        assert_eq!(replayed.headers()[&IDEMPOTENT_REPLAYED], "true");
+        assert_eq!(replayed.headers()[header::LOCATION], "/v1/things/1");
+        assert_eq!(replayed.headers()[header::ETAG], "\"1\"");
+    }
+
+    #[tokio::test]
+    async fn test_large_response_is_returned_and_never_executed_twice() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let Some(db) = test_db::setup() else { return };
+        let user_id = fixtures::user(&mut db.pool.get().unwrap(), "client", "5511900000071");
+        let calls = Arc::new(AtomicUsize::new(0));
+        let export = {
+            let calls = calls.clone();
+            move || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                (StatusCode::CREATED, "x".repeat(MAX_STORED_RESPONSE + 1))
+            }
+        };
+        let app = Router::new()
+            .route("/exports", post(export))
+            .layer(IdempotencyLayer)
+            .layer(Extension(user_id))
+            .layer(Extension(db.pool.clone()));
+        let request = || Request::post("/exports").header(&IDEMPOTENCY_KEY, "grande-1").body(Body::empty()).unwrap();
+
+        let first = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert_eq!(to_bytes(first.into_body(), usize::MAX).await.unwrap().len(), MAX_STORED_RESPONSE + 1);
+
+        let retry = app.oneshot(request()).await.unwrap();
+        assert_eq!(retry.status(), StatusCode::CONFLICT);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
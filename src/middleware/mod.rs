@@ -1,5 +1,10 @@
 pub mod auth_middleware;
 pub mod rate_limit;
 pub mod cors;
+pub mod request_id;
 
-pub use auth_middleware::{AuthMiddleware, require_role};
\ No newline at end of file
+pub mod deprecation;
+pub mod idempotency;
+pub mod audit;
+
+pub use auth_middleware::{AuthMiddleware, require_role};
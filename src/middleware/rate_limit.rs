@@ -6,12 +6,14 @@ use tokio::sync::Semaphore;
 use tower::{Layer, Service};
 use std::future::Future;
 use std::pin::Pin;
+use crate::telemetry;
 
 /// 🔹 Middleware de Rate Limiting
 #[derive(Clone)]
 pub struct RateLimitMiddleware<S> {
     inner: S,
     semaphore: Arc<Semaphore>,
+    name: &'static str,
 }
 
 impl<S> Service<Request<Body>> for RateLimitMiddleware<S>
@@ -29,6 +31,7 @@ where
 
     fn call(&mut self, req: Request<Body>) -> Self::Future {
         let semaphore = self.semaphore.clone();
+        let name = self.name;
         let permit = semaphore.try_acquire_owned();
 
         match permit {
@@ -36,7 +39,8 @@ where
                 let future = self.inner.call(req);
                 Box::pin(async move { future.await })
             }
-            Err(_) => Box::pin(async {
+            Err(_) => Box::pin(async move {
+                telemetry::rate_limit_rejected(name);
                 let response = Response::builder()
                     .status(StatusCode::TOO_MANY_REQUESTS) // HTTP 429: Too Many Requests
                     .body(Body::from("Too many requests"))
@@ -50,6 +54,7 @@ where
 #[derive(Clone)]
 pub struct RateLimitLayer {
     semaphore: Arc<Semaphore>,
+    name: &'static str, // Identifica o limitador nas métricas
 }
 
 impl<S> Layer<S> for RateLimitLayer
@@ -63,6 +68,7 @@ where
         RateLimitMiddleware {
             inner,
             semaphore: self.semaphore.clone(),
+            name: self.name,
         }
     }
 }
@@ -71,6 +77,7 @@ where
 pub fn rate_limit_middleware() -> RateLimitLayer {
     RateLimitLayer {
         semaphore: Arc::new(Semaphore::new(5)),
+        name: "default",
     }
 }
 
@@ -78,5 +85,6 @@ pub fn rate_limit_middleware() -> RateLimitLayer {
 pub fn strict_rate_limit_middleware() -> RateLimitLayer {
     RateLimitLayer {
         semaphore: Arc::new(Semaphore::new(2)),
+        name: "strict",
     }
 }
\ No newline at end of file
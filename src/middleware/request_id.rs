@@ -0,0 +1,225 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{MatchedPath, Request},
+    http::{header, HeaderName, HeaderValue},
+    response::{IntoResponse, Response},
+};
+use futures::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::{Layer, Service};
+use tracing::{info, info_span, Instrument};
+use uuid::Uuid;
+
+use crate::errors::ApiError;
+use crate::logging::ACCESS_LOG_TARGET;
+use crate::telemetry;
+
+/// 🔹 Cabeçalho usado para propagar o ID da requisição
+pub static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// 🔹 Tamanho máximo lido de um corpo de erro em texto para convertê-lo em `ApiError`
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// ✅ Retorna o ID da requisição em andamento (se houver)
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|request_id| request_id.clone()).ok()
+}
+
+/// 🔹 Aceita apenas IDs curtos e com caracteres visíveis, para não poluir logs e headers
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty() && value.len() <= 128 && value.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Middleware que aceita/gera o `X-Request-Id`, abre um span `tracing` para a
+/// requisição, devolve o ID na resposta e emite o access log em JSON e as
+/// métricas HTTP
+#[derive(Clone)]
+pub struct RequestIdLayer;
+
+impl<S> Layer<S> for RequestIdLayer {
+    type Service = RequestIdService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestIdService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct RequestIdService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for RequestIdService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        let request_id = req
+            .headers()
+            .get(&X_REQUEST_ID)
+            .and_then(|h| h.to_str().ok())
+            .filter(|h| is_valid_request_id(h))
+            .map(str::to_owned)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        let method = req.method().clone();
+        let route = req
+            .extensions()
+            .get::<MatchedPath>()
+            .map(|path| path.as_str().to_owned())
+            .unwrap_or_else(|| "unmatched".to_string());
+
+        if let Ok(value) = HeaderValue::from_str(&request_id) {
+            req.headers_mut().insert(X_REQUEST_ID.clone(), value);
+        }
+
+        let span = info_span!("request", request_id = %request_id, method = %method, route = %route);
+        let mut inner = self.inner.clone();
+        let started_at = Instant::now();
+        let header_value = HeaderValue::from_str(&request_id).ok();
+
+        let future = async move {
+            let response = inner.call(req).await?;
+
+            // Usuário autenticado (inserido pelo `AuthMiddleware`)
+            let user_id = response.extensions().get::<Uuid>().copied();
+            let status = response.status();
+
+            let mut response = with_api_error_body(response).await;
+            if let Some(value) = header_value {
+                response.headers_mut().insert(X_REQUEST_ID.clone(), value);
+            }
+
+            let latency = started_at.elapsed();
+            telemetry::record_http_request(method.as_str(), &route, status.as_u16(), latency);
+
+            info!(
+                target: ACCESS_LOG_TARGET,
+                method = %method,
+                route = %route,
+                status = status.as_u16(),
+                latency_ms = latency.as_secs_f64() * 1000.0,
+                user_id = user_id.map(|id| id.to_string()),
+                request_id = current_request_id(),
+                "request completed"
+            );
+
+            Ok(response)
+        };
+
+        Box::pin(REQUEST_ID.scope(request_id, future.instrument(span)))
+    }
+}
+
+/// 🔹 Converte respostas de erro em texto (ex: `(StatusCode, String)`) em `ApiError` JSON
+async fn with_api_error_body(response: Response) -> Response {
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return response;
+    }
+
+    let is_plain_text = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|ct| ct.to_str().map(|ct| ct.starts_with("text/plain")).unwrap_or(false))
+        .unwrap_or(true);
+    if !is_plain_text {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = to_bytes(body, MAX_ERROR_BODY).await.unwrap_or_default();
+    let message = String::from_utf8_lossy(&bytes).trim().to_string();
+    let message = if message.is_empty() {
+        status.canonical_reason().unwrap_or("Error").to_string()
+    } else {
+        message
+    };
+
+    parts.headers.remove(header::CONTENT_TYPE);
+    parts.headers.remove(header::CONTENT_LENGTH);
+
+    let mut converted = ApiError::new(status, message).into_response();
+    converted.headers_mut().extend(parts.headers);
+    converted.extensions_mut().extend(parts.extensions);
+    converted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get, Router};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    async fn missing() -> Result<String, (StatusCode, String)> {
+        Err((StatusCode::NOT_FOUND, "Agendamento não encontrado".to_string()))
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/missing", get(missing))
+            .route("/ok", get(|| async { "ok" }))
+            .layer(RequestIdLayer)
+    }
+
+    async fn call(uri: &str, request_id: Option<&str>) -> (Response, Option<String>) {
+        let mut request = Request::get(uri);
+        if let Some(request_id) = request_id {
+            request = request.header(&X_REQUEST_ID, request_id);
+        }
+        let response = app().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        let header = response.headers().get(&X_REQUEST_ID).map(|value| value.to_str().unwrap().to_string());
+        (response, header)
+    }
+
+    async fn json_body(response: Response) -> Value {
+        serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_text_error_becomes_api_error_with_generated_request_id() {
+        let (response, header) = call("/missing", None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        let header = header.expect("X-Request-Id ausente");
+        assert!(Uuid::parse_str(&header).is_ok());
+
+        let body = json_body(response).await;
+        assert_eq!(body["error"], "Agendamento não encontrado");
+        assert_eq!(body["request_id"], header.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_incoming_request_id_is_propagated() {
+        let (response, header) = call("/missing", Some("req-abc-123")).await;
+        assert_eq!(header.as_deref(), Some("req-abc-123"));
+        assert_eq!(json_body(response).await["request_id"], "req-abc-123");
+
+        // ID inválido (espaços) é substituído por um novo
+        let (response, header) = call("/missing", Some("com espaço")).await;
+        let header = header.unwrap();
+        assert_ne!(header, "com espaço");
+        assert_eq!(json_body(response).await["request_id"], header.as_str());
+
+        // Respostas de sucesso não são alteradas, mas também levam o ID
+        let (response, header) = call("/ok", Some("req-ok")).await;
+        assert_eq!(header.as_deref(), Some("req-ok"));
+        assert_eq!(to_bytes(response.into_body(), usize::MAX).await.unwrap(), "ok");
+    }
+}
@@ -0,0 +1,28 @@
+use diesel::{Queryable, Insertable, Identifiable, Selectable};
+use serde::Serialize;
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::schema::app_passwords;
+
+/// 🔹 Senha de aplicativo (clientes CalDAV autenticam com HTTP Basic)
+#[derive(Debug, Queryable, Selectable, Identifiable, Serialize)]
+#[diesel(table_name = app_passwords)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AppPassword {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,                      // Identificação dada pelo usuário (ex: "iPhone")
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+/// 🔹 Estrutura para criar uma senha de aplicativo
+#[derive(Debug, Insertable)]
+#[diesel(table_name = app_passwords)]
+pub struct NewAppPassword {
+    pub user_id: Uuid,
+    pub name: String,
+    pub password_hash: String,
+}
@@ -14,6 +14,9 @@ pub struct Appointment {
     pub service_id: Uuid,
     pub appointment_time: NaiveDateTime,
     pub status: String,  // Status do agendamento: "pending", "confirmed", "canceled"
+    pub sequence: i32,   // SEQUENCE do iCalendar (incrementado em remarcações/cancelamentos)
+    pub version: i32,  // Versão da linha (`ETag`), incrementada pelo banco a cada alteração
+    pub deleted_at: Option<NaiveDateTime>,  // Exclusão lógica (`None` = ativo)
 }
 
 /// 🔹 Estrutura para criar um novo agendamento (para inserção no banco)
@@ -33,4 +36,18 @@ pub struct NewAppointment {
 pub struct UpdateAppointment {
     pub appointment_time: Option<NaiveDateTime>, // Permite atualização da data/hora
     pub status: Option<String>,                  // Permite atualização do status (ex: "confirmed", "canceled")
+    #[serde(skip)]
+    pub sequence: Option<i32>,                   // Definido pelo servidor, nunca pelo cliente
+}
+
+impl UpdateAppointment {
+    /// ✅ Incrementa o SEQUENCE do iCalendar quando o agendamento é remarcado ou cancelado
+    pub fn bump_sequence(&mut self, existing: &Appointment) {
+        let rescheduled = self.appointment_time.is_some_and(|time| time != existing.appointment_time);
+        let canceled = self.status.as_deref() == Some("canceled") && existing.status != "canceled";
+
+        if rescheduled || canceled {
+            self.sequence = Some(existing.sequence + 1);
+        }
+    }
 }
@@ -0,0 +1,25 @@
+use diesel::{Queryable, Insertable, Selectable};
+use serde::Serialize;
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::schema::attendance_confirmations;
+
+/// 🔹 Token de confirmação de presença enviado nos lembretes
+#[derive(Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = attendance_confirmations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AttendanceConfirmation {
+    pub appointment_id: Uuid,
+    #[serde(skip_serializing)]
+    pub token: String,
+    pub confirmed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+/// 🔹 Estrutura para criar o token de um agendamento
+#[derive(Debug, Insertable)]
+#[diesel(table_name = attendance_confirmations)]
+pub struct NewAttendanceConfirmation {
+    pub appointment_id: Uuid,
+    pub token: String,
+}
@@ -0,0 +1,38 @@
+use diesel::{Queryable, Insertable, Selectable};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::schema::audit_events;
+
+/// 🔹 Registro imutável da trilha de auditoria
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = audit_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AuditEvent {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,     // `None` em ações sem usuário autenticado
+    pub actor_role: Option<String>,
+    pub action: String,             // Ex: "update", "role_change" ou "POST /webhooks/:id/ping"
+    pub entity_type: String,        // Ex: "services", "appointments"
+    pub entity_id: Option<Uuid>,
+    pub before: Option<serde_json::Value>, // Campos alterados, valores anteriores
+    pub after: Option<serde_json::Value>,  // Campos alterados, novos valores
+    pub ip: Option<String>,
+    pub request_id: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+/// 🔹 Estrutura para gravar um registro de auditoria
+#[derive(Debug, Insertable)]
+#[diesel(table_name = audit_events)]
+pub struct NewAuditEvent {
+    pub actor_id: Option<Uuid>,
+    pub actor_role: Option<String>,
+    pub action: String,
+    pub entity_type: String,
+    pub entity_id: Option<Uuid>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub ip: Option<String>,
+    pub request_id: Option<String>,
+}
@@ -0,0 +1,34 @@
+use diesel::{Queryable, Insertable, Identifiable, Selectable};
+use serde::Serialize;
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::schema::background_jobs;
+
+/// 🔹 Job persistido na fila (`background_jobs`)
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Serialize)]
+#[diesel(table_name = background_jobs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct BackgroundJob {
+    pub id: Uuid,
+    pub kind: String,                  // Tipo do job (ex: "appointment_reminder")
+    pub payload: serde_json::Value,
+    pub reference_id: Option<Uuid>,    // Registro relacionado (ex: agendamento), usado para cancelar jobs
+    pub status: String,                // "pending", "running", "done", "failed" ou "canceled"
+    pub run_at: NaiveDateTime,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub last_error: Option<String>,
+    pub locked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 🔹 Estrutura para enfileirar um job
+#[derive(Debug, Insertable)]
+#[diesel(table_name = background_jobs)]
+pub struct NewBackgroundJob {
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub reference_id: Option<Uuid>,
+    pub run_at: NaiveDateTime,
+}
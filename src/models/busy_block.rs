@@ -0,0 +1,56 @@
+use diesel::{Queryable, Insertable, Identifiable, Selectable};
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::schema::{busy_blocks, calendar_sources};
+
+/// 🔹 Calendário externo de um profissional (arquivo local/URL, uploads manuais ou CalDAV)
+#[derive(Debug, Queryable, Selectable, Identifiable, Serialize)]
+#[diesel(table_name = calendar_sources)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct CalendarSource {
+    pub id: Uuid,
+    pub professional_id: Uuid,
+    pub location: Option<String>,          // URL http(s) pública (`None` para uploads e CalDAV)
+    pub last_synced_at: Option<NaiveDateTime>,
+    pub last_error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub kind: String,                      // "url", "upload" ou "caldav"
+}
+
+/// 🔹 Estrutura para cadastrar um calendário externo
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = calendar_sources)]
+pub struct NewCalendarSource {
+    #[serde(skip)]
+    pub professional_id: Uuid,             // Vem do path, nunca do corpo
+    pub location: Option<String>,
+}
+
+/// 🔹 Bloco de horário ocupado (uma ocorrência de evento importado)
+#[derive(Debug, Queryable, Selectable, Identifiable, Serialize)]
+#[diesel(table_name = busy_blocks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct BusyBlock {
+    pub id: Uuid,
+    pub professional_id: Uuid,
+    pub source_id: Uuid,
+    pub uid: String,                       // UID do VEVENT de origem
+    pub summary: Option<String>,
+    pub start_time: NaiveDateTime,
+    pub end_time: NaiveDateTime,
+    pub synced_at: NaiveDateTime,          // Última importação que confirmou o bloco
+}
+
+/// 🔹 Estrutura para inserir/atualizar blocos durante a importação
+#[derive(Debug, Insertable)]
+#[diesel(table_name = busy_blocks)]
+pub struct NewBusyBlock {
+    pub professional_id: Uuid,
+    pub source_id: Uuid,
+    pub uid: String,
+    pub summary: Option<String>,
+    pub start_time: NaiveDateTime,
+    pub end_time: NaiveDateTime,
+    pub synced_at: NaiveDateTime,
+}
@@ -0,0 +1,30 @@
+use diesel::{Queryable, Insertable, Identifiable, Selectable};
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::schema::caldav_objects;
+
+/// 🔹 Evento (.ics) gravado por um cliente CalDAV na agenda do profissional
+#[derive(Debug, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = caldav_objects)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct CaldavObject {
+    pub id: Uuid,
+    pub professional_id: Uuid,
+    pub name: String,                      // Nome do recurso na URL (ex: "8F2C-41A0.ics")
+    pub uid: String,                       // UID do VEVENT
+    pub ics_data: String,                  // Conteúdo original, devolvido no GET
+    pub etag: String,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 🔹 Estrutura para criar/substituir um recurso no PUT
+#[derive(Debug, Insertable)]
+#[diesel(table_name = caldav_objects)]
+pub struct NewCaldavObject {
+    pub professional_id: Uuid,
+    pub name: String,
+    pub uid: String,
+    pub ics_data: String,
+    pub etag: String,
+    pub updated_at: NaiveDateTime,
+}
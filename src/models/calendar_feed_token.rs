@@ -0,0 +1,23 @@
+use diesel::{Queryable, Insertable, Selectable};
+use serde::Serialize;
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::schema::calendar_feed_tokens;
+
+/// 🔹 Token secreto que dá acesso aos feeds `.ics` de um usuário
+/// Só o SHA-256 (`token_hash`) é armazenado e ele nunca é carregado: o token em si aparece apenas na geração
+#[derive(Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = calendar_feed_tokens)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct CalendarFeedToken {
+    pub user_id: Uuid,
+    pub created_at: NaiveDateTime,
+}
+
+/// 🔹 Estrutura para criar (ou rotacionar) o token de um usuário
+#[derive(Debug, Insertable)]
+#[diesel(table_name = calendar_feed_tokens)]
+pub struct NewCalendarFeedToken {
+    pub user_id: Uuid,
+    pub token_hash: String,
+}
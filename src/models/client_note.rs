@@ -0,0 +1,38 @@
+use diesel::{Queryable, Insertable, Selectable};
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::schema::client_notes;
+
+/// 🔹 Visibilidade de uma anotação: só a equipe (`staff`) ou também o cliente (`shared`)
+pub const VISIBILITIES: [&str; 2] = ["staff", "shared"];
+
+/// 🔹 Anotação da equipe sobre o cliente
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = client_notes)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ClientNote {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub body: String,
+    pub visibility: String, // "staff" ou "shared"
+    pub created_at: NaiveDateTime,
+}
+
+/// 🔹 Estrutura para gravar uma anotação
+#[derive(Debug, Insertable)]
+#[diesel(table_name = client_notes)]
+pub struct NewClientNote {
+    pub client_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub body: String,
+    pub visibility: String,
+}
+
+/// 🔹 Corpo do POST `/users/:id/notes`
+#[derive(Debug, Deserialize)]
+pub struct CreateClientNote {
+    pub body: String,
+    pub visibility: Option<String>, // Padrão: "staff"
+}
@@ -0,0 +1,78 @@
+use diesel::{Queryable, Insertable, AsChangeset, Selectable};
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::schema::client_profiles;
+
+/// 🔹 Ficha do cliente (preferências e dados de atendimento)
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = client_profiles)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ClientProfile {
+    pub user_id: Uuid,
+    pub hair_formula: Option<String>,           // Fórmula de coloração/química em uso
+    pub allergies: Option<String>,
+    pub preferred_professional_ids: Vec<Uuid>,
+    pub preferred_contact_time: Option<String>, // Ex: "manhã", "após 18h"
+    pub updated_by: Option<Uuid>,
+    pub updated_at: NaiveDateTime,
+}
+
+impl ClientProfile {
+    /// ✅ Ficha de quem ainda não tem nada registrado
+    pub fn empty(user_id: Uuid) -> Self {
+        Self {
+            user_id,
+            hair_formula: None,
+            allergies: None,
+            preferred_professional_ids: Vec::new(),
+            preferred_contact_time: None,
+            updated_by: None,
+            updated_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+/// 🔹 Linha gravada no upsert da ficha
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = client_profiles)]
+#[diesel(treat_none_as_null = true)]
+pub struct NewClientProfile {
+    pub user_id: Uuid,
+    pub hair_formula: Option<String>,
+    pub allergies: Option<String>,
+    pub preferred_professional_ids: Vec<Uuid>,
+    pub preferred_contact_time: Option<String>,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 🔹 Corpo do PUT `/users/:id/profile` (campos ausentes ficam como estão; string vazia apaga o campo)
+#[derive(Debug, Deserialize)]
+pub struct UpdateClientProfile {
+    pub hair_formula: Option<String>,
+    pub allergies: Option<String>,
+    pub preferred_professional_ids: Option<Vec<Uuid>>,
+    pub preferred_contact_time: Option<String>,
+}
+
+impl UpdateClientProfile {
+    /// ✅ Aplica a atualização sobre a ficha atual
+    pub fn apply(self, current: ClientProfile, author_id: Uuid) -> NewClientProfile {
+        let text = |value: Option<String>, current: Option<String>| match value {
+            Some(value) if value.trim().is_empty() => None,
+            Some(value) => Some(value.trim().to_string()),
+            None => current,
+        };
+
+        NewClientProfile {
+            user_id: current.user_id,
+            hair_formula: text(self.hair_formula, current.hair_formula),
+            allergies: text(self.allergies, current.allergies),
+            preferred_professional_ids: self.preferred_professional_ids.unwrap_or(current.preferred_professional_ids),
+            preferred_contact_time: text(self.preferred_contact_time, current.preferred_contact_time),
+            updated_by: Some(author_id),
+            updated_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use diesel::{Queryable, Insertable, Selectable};
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::schema::guest_verifications;
+
+/// 🔹 Código de verificação enviado por SMS a um convidado (o código em si só existe como hash)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = guest_verifications)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct GuestVerification {
+    pub id: Uuid,
+    pub phone: String,  // Só os dígitos
+    pub code_hash: Option<String>,  // NULL até o job gerar e enviar o código
+    pub attempts: i32,  // Códigos errados informados
+    pub expires_at: NaiveDateTime,
+    pub consumed_at: Option<NaiveDateTime>,  // Já usado num agendamento
+}
+
+/// 🔹 Estrutura para registrar um pedido de código (o código é gerado no envio)
+#[derive(Debug, Insertable)]
+#[diesel(table_name = guest_verifications)]
+pub struct NewGuestVerification {
+    pub phone: String,
+    pub ip: Option<String>,
+    pub expires_at: NaiveDateTime,
+}
@@ -0,0 +1,29 @@
+use diesel::{Queryable, Insertable, Selectable};
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::schema::idempotency_keys;
+
+/// 🔹 Requisição registrada com `Idempotency-Key` (resposta preenchida ao concluir;
+/// a chave `(user_id, idempotency_key)` e `completed_at` ficam só no banco)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = idempotency_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct IdempotencyKey {
+    pub request_hash: String,
+    pub response_status: Option<i32>,
+    pub response_headers: Option<serde_json::Value>,  // {"location": "...", ...}
+    pub response_body: Option<Vec<u8>>,
+    pub locked_at: NaiveDateTime,  // Início da execução em andamento
+    pub created_at: NaiveDateTime,
+}
+
+/// 🔹 Estrutura para reservar uma chave antes de executar a requisição
+#[derive(Debug, Insertable)]
+#[diesel(table_name = idempotency_keys)]
+pub struct NewIdempotencyKey {
+    pub user_id: Uuid,
+    pub idempotency_key: String,
+    pub request_hash: String,
+    pub locked_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
@@ -4,4 +4,18 @@ pub mod professional;
 pub mod service;
 pub mod availability;
 pub mod appointment;
-pub mod salon_settings;
\ No newline at end of file
+pub mod salon_settings;
+pub mod calendar_feed_token;
+pub mod busy_block;
+pub mod app_password;
+pub mod caldav_object;
+pub mod notification_preference;
+pub mod background_job;
+pub mod attendance_confirmation;
+pub mod webhook;
+pub mod outbox_event;
+pub mod idempotency_key;
+pub mod audit_event;
+pub mod client_profile;
+pub mod client_note;
+pub mod guest_verification;
@@ -0,0 +1,79 @@
+use diesel::{Queryable, Insertable, AsChangeset, Selectable};
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::schema::notification_preferences;
+
+/// 🔹 Canais em que o usuário aceitou receber notificações (opt-in)
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = notification_preferences)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NotificationPreference {
+    pub user_id: Uuid,
+    pub sms: bool,
+    pub email: bool,
+    pub whatsapp: bool,
+    pub email_address: Option<String>, // Usuários não têm e-mail no cadastro; informado aqui
+    pub locale: String,                // Idioma dos templates (ex: "pt-BR", "en", "es")
+    pub updated_at: NaiveDateTime,
+}
+
+impl NotificationPreference {
+    /// ✅ Preferências de quem nunca configurou nada: nenhum canal ativo
+    pub fn defaults(user_id: Uuid) -> Self {
+        Self {
+            user_id,
+            sms: false,
+            email: false,
+            whatsapp: false,
+            email_address: None,
+            locale: "pt-BR".to_string(),
+            updated_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+/// 🔹 Linha gravada no upsert das preferências
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = notification_preferences)]
+#[diesel(treat_none_as_null = true)]
+pub struct NewNotificationPreference {
+    pub user_id: Uuid,
+    pub sms: bool,
+    pub email: bool,
+    pub whatsapp: bool,
+    pub email_address: Option<String>,
+    pub locale: String,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 🔹 Corpo do PUT `/users/:id/notification-preferences` (campos ausentes ficam como estão)
+#[derive(Debug, Deserialize)]
+pub struct UpdateNotificationPreference {
+    pub sms: Option<bool>,
+    pub email: Option<bool>,
+    pub whatsapp: Option<bool>,
+    pub email_address: Option<String>, // String vazia remove o e-mail
+    pub locale: Option<String>,
+}
+
+impl UpdateNotificationPreference {
+    /// ✅ Aplica a atualização sobre as preferências atuais
+    pub fn apply(self, current: NotificationPreference) -> NewNotificationPreference {
+        let email_address = match self.email_address {
+            Some(address) if address.trim().is_empty() => None,
+            Some(address) => Some(address.trim().to_string()),
+            None => current.email_address,
+        };
+
+        NewNotificationPreference {
+            user_id: current.user_id,
+            sms: self.sms.unwrap_or(current.sms),
+            email: self.email.unwrap_or(current.email),
+            whatsapp: self.whatsapp.unwrap_or(current.whatsapp),
+            email_address,
+            locale: self.locale.unwrap_or(current.locale),
+            updated_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use diesel::{Queryable, Insertable, Selectable};
+use uuid::Uuid;
+use crate::schema::outbox_events;
+
+/// 🔹 Evento de domínio reservado pelo relay do outbox
+/// (colunas de controle como `available_at`, `locked_at` e `published_at` ficam só no banco)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = outbox_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct OutboxEvent {
+    pub id: Uuid,           // Também é a chave de idempotência do evento
+    pub event_type: String, // Ex: "appointment.created", "user.registered"
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+}
+
+/// 🔹 Estrutura para gravar um evento no outbox
+#[derive(Debug, Insertable)]
+#[diesel(table_name = outbox_events)]
+pub struct NewOutboxEvent {
+    pub event_type: String,
+    pub payload: serde_json::Value,
+}
@@ -17,6 +17,8 @@ pub struct Professional {
     pub bio: Option<String>,
     pub specialties: Option<Vec<Option<String>>>,  // Alterado para Option<Vec<Option<String>>>
     pub created_at: NaiveDateTime,
+    pub version: i32,  // Versão da linha (`ETag`), incrementada pelo banco a cada alteração
+    pub deleted_at: Option<NaiveDateTime>,  // Exclusão lógica (`None` = ativo)
 }
 
 /// 🔹 Estrutura para criar um novo profissional
@@ -6,7 +6,7 @@ use diesel::pg::Pg;
 use crate::schema::reservations;
 
 /// 🔹 Estrutura para representar uma reserva no banco de dados
-#[derive(Debug, Queryable, Selectable, Serialize, Identifiable)]
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Identifiable)]
 #[diesel(table_name = reservations)]
 #[diesel(check_for_backend(Pg))]  // Garante que este código será validado apenas no backend PostgreSQL
 pub struct Reservation {
@@ -15,6 +15,7 @@ pub struct Reservation {
     pub service: String,           // Serviço que foi reservado
     pub appointment_time: NaiveDateTime, // Data e hora do agendamento
     pub status: String,            // Status da reserva (ex: "pending", "confirmed", "canceled")
+    pub sequence: i32,             // SEQUENCE do iCalendar (incrementado em remarcações/cancelamentos)
 }
 
 /// 🔹 Estrutura para criar uma nova reserva (para inserção no banco de dados)
@@ -27,6 +28,13 @@ pub struct NewReservation {
     pub status: String,            // Status inicial da reserva (geralmente "pending")
 }
 
+/// 🔹 Corpo de `POST /reservations` (usuário e status são definidos pelo servidor)
+#[derive(Debug, Deserialize)]
+pub struct CreateReservation {
+    pub service: String,           // Serviço que será reservado
+    pub appointment_time: NaiveDateTime, // Data e hora do agendamento
+}
+
 /// 🔹 Estrutura para atualizar uma reserva existente (para atualização no banco de dados)
 #[derive(Debug, AsChangeset, Deserialize)]
 #[diesel(table_name = reservations)]
@@ -34,4 +42,18 @@ pub struct UpdateReservation {
     pub service: Option<String>,          // Serviço que foi reservado (opcional, pode ser atualizado)
     pub appointment_time: Option<NaiveDateTime>, // Data e hora do agendamento (opcional)
     pub status: Option<String>,           // Status da reserva (opcional)
+    #[serde(skip)]
+    pub sequence: Option<i32>,            // Definido pelo servidor, nunca pelo cliente
+}
+
+impl UpdateReservation {
+    /// ✅ Incrementa o SEQUENCE do iCalendar quando a reserva é remarcada ou cancelada
+    pub fn bump_sequence(&mut self, existing: &Reservation) {
+        let rescheduled = self.appointment_time.is_some_and(|time| time != existing.appointment_time);
+        let canceled = self.status.as_deref() == Some("canceled") && existing.status != "canceled";
+
+        if rescheduled || canceled {
+            self.sequence = Some(existing.sequence + 1);
+        }
+    }
 }
@@ -14,6 +14,8 @@ pub struct SalonSetting {
     pub closing_hour: NaiveTime,
     pub working_days: String,  // Armazenado como JSON string no banco
     pub created_at: NaiveDateTime,
+    pub reminder_offsets_hours: Vec<i32>,  // Antecedência dos lembretes (ex: [24, 2])
+    pub version: i32,  // Versão da linha (`ETag`), incrementada pelo banco a cada alteração
 }
 
 /// Estrutura para inserção (usando tipos Diesel-compatíveis)
@@ -25,6 +27,8 @@ pub struct NewSalonSetting {
     pub closing_hour: NaiveTime,
     #[serde(deserialize_with = "deserialize_working_days")]
     pub working_days: String,  // String JSON serializada
+    #[serde(default)]
+    pub reminder_offsets_hours: Option<Vec<i32>>,  // Ausente = padrão do banco ([24, 2])
 }
 
 /// Estrutura para atualização
@@ -37,6 +41,16 @@ pub struct UpdateSalonSetting {
     pub closing_hour: Option<NaiveTime>,
     #[serde(default, deserialize_with = "deserialize_working_days_option")]
     pub working_days: Option<String>,  // Optional String JSON
+    #[serde(default)]
+    pub reminder_offsets_hours: Option<Vec<i32>>,
+}
+
+/// ✅ Lembretes entre 1 hora e 7 dias antes do horário
+pub fn validate_reminder_offsets(offsets: &[i32]) -> Result<(), String> {
+    if offsets.iter().any(|hours| !(1..=168).contains(hours)) {
+        return Err("reminder_offsets_hours deve conter horas entre 1 e 168".to_string());
+    }
+    Ok(())
 }
 
 // Modelo de domínio com tipos convenientes
@@ -48,6 +62,7 @@ pub struct SalonSettings {
     pub closing_hour: NaiveTime,
     pub working_days: Vec<String>,  // Tipo conveniente para a aplicação
     pub created_at: NaiveDateTime,
+    pub reminder_offsets_hours: Vec<i32>,
 }
 
 // Conversão entre modelos
@@ -63,6 +78,7 @@ impl From<SalonSetting> for SalonSettings {
             closing_hour: db_model.closing_hour,
             working_days: days,
             created_at: db_model.created_at,
+            reminder_offsets_hours: db_model.reminder_offsets_hours,
         }
     }
 }
@@ -77,6 +93,7 @@ impl From<SalonSettings> for NewSalonSetting {
             opening_hour: domain_model.opening_hour,
             closing_hour: domain_model.closing_hour,
             working_days: days_json,
+            reminder_offsets_hours: Some(domain_model.reminder_offsets_hours),
         }
     }
 }
@@ -1,6 +1,7 @@
 use diesel::{Queryable, Insertable, Identifiable, AsChangeset, Selectable};
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
+use chrono::NaiveDateTime;
 use crate::schema::services;
 
 /// 🔹 Estrutura de Serviço (Tabela `services`)
@@ -14,6 +15,8 @@ pub struct Service {
     pub preco: f64,                // Preço do serviço
     pub duracao_min: i32,          // Duração do serviço em minutos
     pub ativo: bool,               // Indica se o serviço está ativo ou não
+    pub version: i32,               // Versão da linha (`ETag`), incrementada pelo banco a cada alteração
+    pub deleted_at: Option<NaiveDateTime>, // Exclusão lógica (`None` = ativo)
 }
 
 /// 🔹 Estrutura para criar um novo serviço (para inserção no banco)
@@ -15,6 +15,9 @@ pub struct User {
     pub role: String,
     pub sms_verified: bool,
     pub created_at: NaiveDateTime,
+    pub version: i32,  // Versão da linha (`ETag`), incrementada pelo banco a cada alteração
+    pub deleted_at: Option<NaiveDateTime>,  // Exclusão lógica (`None` = ativo)
+    pub anonymized_at: Option<NaiveDateTime>,  // Dados pessoais eliminados a pedido do titular (LGPD)
 }
 
 
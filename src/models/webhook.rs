@@ -0,0 +1,75 @@
+use diesel::{Queryable, Insertable, AsChangeset, Selectable};
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::schema::{webhook_deliveries, webhook_subscriptions};
+
+/// 🔹 Assinatura de webhook: URL que recebe os eventos escolhidos
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = webhook_subscriptions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub url: String,
+    pub event_types: Vec<String>, // Ex: ["appointment.created", "user.registered"]
+    #[serde(skip_serializing)]
+    pub secret: String, // Chave do HMAC-SHA256 das entregas
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 🔹 Estrutura para criar uma assinatura
+#[derive(Debug, Insertable)]
+#[diesel(table_name = webhook_subscriptions)]
+pub struct NewWebhookSubscription {
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub secret: String,
+}
+
+/// 🔹 Corpo do POST `/webhooks` (sem `secret`, um segredo aleatório é gerado)
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookSubscription {
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub secret: Option<String>,
+}
+
+/// 🔹 Corpo do PUT `/webhooks/:id` (campos ausentes ficam como estão)
+#[derive(Debug, Deserialize, AsChangeset)]
+#[diesel(table_name = webhook_subscriptions)]
+pub struct UpdateWebhookSubscription {
+    pub url: Option<String>,
+    pub event_types: Option<Vec<String>>,
+    pub secret: Option<String>,
+    pub active: Option<bool>,
+}
+
+/// 🔹 Entrega de um evento para uma assinatura (log com o resultado da última tentativa)
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = webhook_deliveries)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value, // Corpo enviado (JSON)
+    pub status: String,             // "pending", "retrying", "delivered" ou "failed"
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 🔹 Estrutura para registrar uma entrega
+#[derive(Debug, Insertable)]
+#[diesel(table_name = webhook_deliveries)]
+pub struct NewWebhookDelivery {
+    pub subscription_id: Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+}
@@ -0,0 +1,199 @@
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use uuid::Uuid;
+
+use super::{Channel, Message, Notifier};
+
+/// 🔹 Tempo máximo de uma conversa SMTP completa
+const SMTP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 🔹 E-mail via SMTP sem TLS/autenticação: relay local ou servidor de captura (Mailpit, MailHog)
+pub struct SmtpNotifier {
+    server: String, // "host:porta"
+    from: String,
+}
+
+impl SmtpNotifier {
+    pub fn new(server: String, from: String) -> Self {
+        Self { server, from }
+    }
+
+    async fn deliver(&self, message: &Message) -> Result<(), String> {
+        for address in [&self.from, &message.to] {
+            if address.contains(['\r', '\n', '<', '>']) || !address.contains('@') {
+                return Err(format!("Endereço de e-mail inválido: {:?}", address));
+            }
+        }
+
+        let stream = TcpStream::connect(&self.server)
+            .await
+            .map_err(|e| format!("Falha ao conectar em {}: {}", self.server, e))?;
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+
+        expect_reply(&mut reader, 220).await?;
+        let domain = self.from.rsplit('@').next().unwrap_or("localhost");
+        command(&mut reader, &mut writer, &format!("EHLO {}", domain), 250).await?;
+        command(&mut reader, &mut writer, &format!("MAIL FROM:<{}>", self.from), 250).await?;
+        command(&mut reader, &mut writer, &format!("RCPT TO:<{}>", message.to), 250).await?;
+        command(&mut reader, &mut writer, "DATA", 354).await?;
+
+        writer
+            .write_all(format_message(&self.from, message).as_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+        command(&mut reader, &mut writer, ".", 250).await?;
+
+        // O e-mail já foi aceito; falhas no QUIT não importam
+        let _ = command(&mut reader, &mut writer, "QUIT", 221).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+    fn channel(&self) -> Channel {
+        Channel::Email
+    }
+
+    async fn send(&self, message: &Message) -> Result<(), String> {
+        tokio::time::timeout(SMTP_TIMEOUT, self.deliver(message))
+            .await
+            .map_err(|_| format!("Timeout na conversa SMTP com {}", self.server))?
+    }
+}
+
+/// ✅ Cabeçalhos + corpo (UTF-8, linhas CRLF e "dot-stuffing"), sem o "." final
+pub fn format_message(from: &str, message: &Message) -> String {
+    let domain = from.rsplit('@').next().unwrap_or("localhost");
+    let mut data = format!(
+        "From: <{from}>\r\n\
+         To: <{to}>\r\n\
+         Subject: =?UTF-8?B?{subject}?=\r\n\
+         Date: {date}\r\n\
+         Message-ID: <{id}@{domain}>\r\n\
+         MIME-Version: 1.0\r\n\
+         Content-Type: text/plain; charset=utf-8\r\n\
+         Content-Transfer-Encoding: 8bit\r\n\
+         \r\n",
+        from = from,
+        to = message.to,
+        subject = STANDARD.encode(message.subject.as_bytes()),
+        date = Utc::now().to_rfc2822(),
+        id = Uuid::new_v4(),
+        domain = domain,
+    );
+
+    for line in message.body.lines() {
+        if line.starts_with('.') {
+            data.push('.');
+        }
+        data.push_str(line);
+        data.push_str("\r\n");
+    }
+    data
+}
+
+async fn command<R, W>(reader: &mut BufReader<R>, writer: &mut W, line: &str, expected: u16) -> Result<(), String>
+where
+    R: tokio::io::AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    writer
+        .write_all(format!("{}\r\n", line).as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    expect_reply(reader, expected).await
+}
+
+/// 🔹 Lê uma resposta (possivelmente multilinha, `250-...`) e compara a classe do código (2xx, 3xx...)
+async fn expect_reply<R: tokio::io::AsyncRead + Unpin>(reader: &mut BufReader<R>, expected: u16) -> Result<(), String> {
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await.map_err(|e| e.to_string())? == 0 {
+            return Err("Servidor SMTP encerrou a conexão".to_string());
+        }
+
+        let code = line.get(..3).and_then(|code| code.parse::<u16>().ok());
+        let last = line.as_bytes().get(3) != Some(&b'-');
+        match code {
+            Some(code) if last && code / 100 == expected / 100 => return Ok(()),
+            Some(_) if last => return Err(format!("Servidor SMTP respondeu: {}", line.trim_end())),
+            Some(_) => continue,
+            None => return Err(format!("Resposta SMTP inválida: {}", line.trim_end())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// Servidor de captura mínimo: aceita uma mensagem e devolve o conteúdo do DATA
+    async fn capture_server(listener: TcpListener) -> String {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+        writer.write_all(b"220 capture ESMTP\r\n").await.unwrap();
+
+        let mut data = String::new();
+        let mut in_data = false;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await.unwrap() == 0 {
+                return data;
+            }
+            if in_data {
+                if line == ".\r\n" {
+                    in_data = false;
+                    writer.write_all(b"250 OK queued\r\n").await.unwrap();
+                } else {
+                    data.push_str(&line);
+                }
+                continue;
+            }
+
+            let reply: &[u8] = match line.get(..4).unwrap_or_default() {
+                "EHLO" => b"250-capture\r\n250 8BITMIME\r\n",
+                "DATA" => {
+                    in_data = true;
+                    b"354 End data with <CR><LF>.<CR><LF>\r\n"
+                }
+                "QUIT" => {
+                    writer.write_all(b"221 Bye\r\n").await.unwrap();
+                    return data;
+                }
+                _ => b"250 OK\r\n",
+            };
+            writer.write_all(reply).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_smtp_delivery_to_capture_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = listener.local_addr().unwrap().to_string();
+        let captured = tokio::spawn(capture_server(listener));
+
+        let notifier = SmtpNotifier::new(server, "agenda@salao.test".to_string());
+        let message = Message {
+            to: "ana@example.com".to_string(),
+            subject: "Agendamento confirmado: Corte".to_string(),
+            body: "Olá Ana,\n.linha com ponto\nAté breve!".to_string(),
+        };
+        notifier.send(&message).await.unwrap();
+
+        let data = captured.await.unwrap();
+        assert!(data.contains("To: <ana@example.com>\r\n"));
+        assert!(data.contains(&format!("Subject: =?UTF-8?B?{}?=", STANDARD.encode("Agendamento confirmado: Corte"))));
+        assert!(data.contains("\r\n\r\nOlá Ana,\r\n..linha com ponto\r\nAté breve!\r\n"));
+
+        let invalid = Message { to: "x\r\nRCPT TO:<y@z>".to_string(), ..message };
+        assert!(notifier.send(&invalid).await.is_err());
+    }
+}
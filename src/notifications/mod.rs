@@ -0,0 +1,209 @@
+pub mod email;
+pub mod sms;
+pub mod templates;
+pub mod whatsapp;
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+use crate::db::Pool;
+use crate::events::DomainEvent;
+use crate::models::notification_preference::NotificationPreference;
+use crate::models::outbox_event::OutboxEvent;
+use crate::models::user::User;
+use crate::schema::{notification_preferences, users};
+use crate::services::outbox::OutboxConsumer;
+use crate::telemetry;
+
+/// 🔹 Canais de entrega suportados
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    Sms,
+    Email,
+    WhatsApp,
+}
+
+impl Channel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Channel::Sms => "sms",
+            Channel::Email => "email",
+            Channel::WhatsApp => "whatsapp",
+        }
+    }
+
+    /// ✅ Endereço do usuário neste canal, se ele optou por recebê-lo
+    pub fn recipient(&self, user: &User, preferences: &NotificationPreference) -> Option<String> {
+        match self {
+            Channel::Sms => preferences.sms.then(|| user.phone.clone()),
+            Channel::WhatsApp => preferences.whatsapp.then(|| user.phone.clone()),
+            Channel::Email => preferences.email.then(|| preferences.email_address.clone()).flatten(),
+        }
+    }
+}
+
+/// 🔹 Mensagem já renderizada para um destinatário
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub to: String,      // Telefone (SMS/WhatsApp) ou e-mail
+    pub subject: String, // Usado apenas pelo e-mail
+    pub body: String,
+}
+
+/// 🔹 Um canal de entrega (SMS, e-mail, WhatsApp...)
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    fn channel(&self) -> Channel;
+
+    async fn send(&self, message: &Message) -> Result<(), String>;
+}
+
+/// ✅ Canais configurados no ambiente (canais sem configuração ficam desativados)
+pub fn notifiers_from_config(config: &Config) -> Vec<Arc<dyn Notifier>> {
+    let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
+
+    if let Some(url) = &config.sms_gateway_url {
+        notifiers.push(Arc::new(sms::SmsNotifier::new(url.clone(), config.sms_gateway_token.clone())));
+    }
+    if let Some(server) = &config.smtp_server {
+        notifiers.push(Arc::new(email::SmtpNotifier::new(server.clone(), config.smtp_from.clone())));
+    }
+    if let Some(url) = &config.whatsapp_webhook_url {
+        notifiers.push(Arc::new(whatsapp::WhatsAppNotifier::new(url.clone(), config.whatsapp_token.clone())));
+    }
+
+    if notifiers.is_empty() {
+        info!("🔕 Nenhum canal de notificação configurado");
+    }
+    notifiers
+}
+
+/// ✅ Consumidor do outbox que transforma eventos de agendamentos/reservas em notificações
+pub struct NotificationConsumer {
+    pool: Arc<Pool>,
+    notifiers: Vec<Arc<dyn Notifier>>,
+}
+
+impl NotificationConsumer {
+    pub fn new(pool: Arc<Pool>, notifiers: Vec<Arc<dyn Notifier>>) -> Self {
+        Self { pool, notifiers }
+    }
+}
+
+#[async_trait]
+impl OutboxConsumer for NotificationConsumer {
+    fn name(&self) -> &'static str {
+        "notifications"
+    }
+
+    async fn handle(&self, event: &OutboxEvent) -> Result<(), String> {
+        // Apenas eventos de agendamentos/reservas geram notificações (ex: `user.registered` não)
+        let Ok(event) = serde_json::from_value::<DomainEvent>(event.payload.clone()) else {
+            return Ok(());
+        };
+        dispatch(self.pool.clone(), &self.notifiers, event).await
+    }
+}
+
+/// 🔹 Resultado da entrega nos canais ativos de um usuário
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Delivery {
+    pub sent: usize,
+    pub failed: usize,
+}
+
+/// 🔹 Entrega o evento em todos os canais ativos do usuário
+/// (erro só se nenhum canal funcionou: a nova tentativa não duplica mensagens já entregues)
+async fn dispatch(pool: Arc<Pool>, notifiers: &[Arc<dyn Notifier>], event: DomainEvent) -> Result<(), String> {
+    let name = event.name();
+    let delivery = notify_user(pool, notifiers, event.user_id, &name, |locale, user_name| {
+        templates::render(&event, locale, user_name)
+    })
+    .await?;
+
+    if delivery.sent == 0 && delivery.failed > 0 {
+        return Err(format!("{} não entregue em nenhum dos {} canais", name, delivery.failed));
+    }
+    Ok(())
+}
+
+/// ✅ Renderiza (no idioma do usuário) e envia uma mensagem por todos os canais em que ele optou
+pub async fn notify_user<F>(
+    pool: Arc<Pool>,
+    notifiers: &[Arc<dyn Notifier>],
+    user_id: uuid::Uuid,
+    label: &str,
+    render: F,
+) -> Result<Delivery, String>
+where
+    F: Fn(&str, &str) -> templates::Rendered,
+{
+    if notifiers.is_empty() {
+        return Ok(Delivery::default());
+    }
+
+    let loaded = tokio::task::spawn_blocking(move || load_recipient(&pool, user_id))
+        .await
+        .map_err(|e| e.to_string())??;
+    let Some((user, preferences)) = loaded else {
+        debug!("🔕 {} sem usuário {} para notificar", label, user_id);
+        return Ok(Delivery::default());
+    };
+
+    let rendered = render(&preferences.locale, &user.name);
+    let mut delivery = Delivery::default();
+    for notifier in notifiers {
+        let channel = notifier.channel();
+        let Some(to) = channel.recipient(&user, &preferences) else {
+            continue;
+        };
+
+        let message = Message {
+            to,
+            subject: rendered.subject.clone(),
+            body: rendered.body.clone(),
+        };
+        match notifier.send(&message).await {
+            Ok(()) => {
+                info!("📨 {} enviado por {} ({})", label, channel.as_str(), user_id);
+                telemetry::notification_sent(channel.as_str(), true);
+                delivery.sent += 1;
+            }
+            Err(e) => {
+                warn!("⚠️ Falha ao enviar {} por {}: {}", label, channel.as_str(), e);
+                telemetry::notification_sent(channel.as_str(), false);
+                delivery.failed += 1;
+            }
+        }
+    }
+
+    Ok(delivery)
+}
+
+fn load_recipient(pool: &Pool, user_id: uuid::Uuid) -> Result<Option<(User, NotificationPreference)>, String> {
+    let mut conn = pool.get().map_err(|e| e.to_string())?;
+
+    let Some(user) = users::table
+        .find(user_id)
+        .filter(users::deleted_at.is_null())  // Contas excluídas não recebem notificações
+        .first::<User>(&mut conn)
+        .optional()
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(None);
+    };
+
+    let preferences = notification_preferences::table
+        .find(user_id)
+        .first::<NotificationPreference>(&mut conn)
+        .optional()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_else(|| NotificationPreference::defaults(user_id));
+
+    Ok(Some((user, preferences)))
+}
@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{Channel, Message, Notifier};
+use crate::services::http_client;
+
+/// 🔹 SMS via gateway HTTP: `POST {"to": "+55...", "message": "..."}`
+pub struct SmsNotifier {
+    gateway_url: String,
+    token: Option<String>, // Enviado como `Authorization: Bearer`
+}
+
+impl SmsNotifier {
+    pub fn new(gateway_url: String, token: Option<String>) -> Self {
+        Self { gateway_url, token }
+    }
+}
+
+#[async_trait]
+impl Notifier for SmsNotifier {
+    fn channel(&self) -> Channel {
+        Channel::Sms
+    }
+
+    async fn send(&self, message: &Message) -> Result<(), String> {
+        let headers: Vec<(&str, String)> = self
+            .token
+            .iter()
+            .map(|token| ("authorization", format!("Bearer {}", token)))
+            .collect();
+        let body = json!({ "to": message.to, "message": message.body }).to_string();
+
+        let response = http_client::post_json(&self.gateway_url, &headers, body).await?;
+        if !response.is_success() {
+            return Err(format!("Gateway de SMS respondeu {}: {}", response.status, response.body));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use chrono::NaiveDateTime;
+
+use crate::events::{DomainEvent, EventKind};
+
+/// 🔹 Idiomas com templates (o primeiro é o padrão)
+pub const SUPPORTED_LOCALES: &[&str] = &["pt-BR", "en", "es"];
+
+/// 🔹 Assunto e corpo já preenchidos
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rendered {
+    pub subject: String,
+    pub body: String,
+}
+
+/// 🔹 Template de um evento: `{name}`, `{service}`, `{date}` e `{link}` são substituídos na renderização
+struct Template {
+    subject: &'static str,
+    body: &'static str,
+}
+
+/// ✅ Renderiza a notificação do evento no idioma do usuário (idiomas desconhecidos caem no pt-BR)
+pub fn render(event: &DomainEvent, locale: &str, name: &str) -> Rendered {
+    let locale = normalize_locale(locale);
+    fill(&template(locale, event.kind), locale, name, &event.service, event.starts_at, "")
+}
+
+/// ✅ Lembrete com o link de confirmação de presença
+pub fn render_reminder(locale: &str, name: &str, service: &str, starts_at: NaiveDateTime, confirm_url: &str) -> Rendered {
+    let locale = normalize_locale(locale);
+    fill(&reminder_template(locale), locale, name, service, starts_at, confirm_url)
+}
+
+/// ✅ Código de verificação do agendamento sem cadastro (SMS)
+pub fn render_verification_code(locale: &str, code: &str, valid_minutes: i64) -> Rendered {
+    let body = match normalize_locale(locale) {
+        "en" => "Your booking code is {code}. It expires in {minutes} minutes. Do not share it.",
+        "es" => "Tu código de reserva es {code}. Vence en {minutes} minutos. No lo compartas.",
+        _ => "Seu código de agendamento é {code}. Ele vale por {minutes} minutos. Não compartilhe.",
+    };
+    let body = body.replace("{code}", code).replace("{minutes}", &valid_minutes.to_string());
+    Rendered { subject: body.clone(), body }
+}
+
+fn fill(template: &Template, locale: &str, name: &str, service: &str, starts_at: NaiveDateTime, link: &str) -> Rendered {
+    let date = starts_at.format(date_format(locale)).to_string();
+    let fill = |text: &str| {
+        text.replace("{name}", name)
+            .replace("{service}", service)
+            .replace("{date}", &date)
+            .replace("{link}", link)
+    };
+
+    Rendered {
+        subject: fill(template.subject),
+        body: fill(template.body),
+    }
+}
+
+/// ✅ Idioma suportado mais próximo (ex: "en-US" → "en", "pt" → "pt-BR")
+pub fn normalize_locale(locale: &str) -> &'static str {
+    let language = locale.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+    SUPPORTED_LOCALES
+        .iter()
+        .find(|supported| supported.split('-').next() == Some(language.as_str()))
+        .copied()
+        .unwrap_or(SUPPORTED_LOCALES[0])
+}
+
+fn date_format(locale: &str) -> &'static str {
+    match locale {
+        "en" => "%Y-%m-%d at %H:%M",
+        "es" => "%d/%m/%Y a las %H:%M",
+        _ => "%d/%m/%Y às %H:%M",
+    }
+}
+
+fn reminder_template(locale: &str) -> Template {
+    match locale {
+        "en" => Template {
+            subject: "Reminder: {service} on {date}",
+            body: "Hi {name}, this is a reminder of your {service} booking on {date}. Confirm your attendance at {link} or reply YES.",
+        },
+        "es" => Template {
+            subject: "Recordatorio: {service} el {date}",
+            body: "Hola {name}, te recordamos tu reserva de {service} el {date}. Confirma tu asistencia en {link} o responde SI.",
+        },
+        _ => Template {
+            subject: "Lembrete: {service} em {date}",
+            body: "Olá {name}, lembrete do seu agendamento de {service} em {date}. Confirme sua presença em {link} ou responda SIM.",
+        },
+    }
+}
+
+fn template(locale: &str, kind: EventKind) -> Template {
+    match (locale, kind) {
+        ("en", EventKind::Created) => Template {
+            subject: "Booking received: {service}",
+            body: "Hi {name}, we received your booking for {service} on {date}. We will let you know once it is confirmed.",
+        },
+        ("en", EventKind::Confirmed) => Template {
+            subject: "Booking confirmed: {service}",
+            body: "Hi {name}, your booking for {service} on {date} is confirmed. See you soon!",
+        },
+        ("en", EventKind::Rescheduled) => Template {
+            subject: "Booking rescheduled: {service}",
+            body: "Hi {name}, your booking for {service} was moved to {date}.",
+        },
+        ("en", EventKind::Canceled) => Template {
+            subject: "Booking canceled: {service}",
+            body: "Hi {name}, your booking for {service} on {date} was canceled.",
+        },
+        ("es", EventKind::Created) => Template {
+            subject: "Reserva recibida: {service}",
+            body: "Hola {name}, recibimos tu reserva de {service} para el {date}. Te avisaremos cuando sea confirmada.",
+        },
+        ("es", EventKind::Confirmed) => Template {
+            subject: "Reserva confirmada: {service}",
+            body: "Hola {name}, tu reserva de {service} para el {date} está confirmada. ¡Hasta pronto!",
+        },
+        ("es", EventKind::Rescheduled) => Template {
+            subject: "Reserva reprogramada: {service}",
+            body: "Hola {name}, tu reserva de {service} fue cambiada al {date}.",
+        },
+        ("es", EventKind::Canceled) => Template {
+            subject: "Reserva cancelada: {service}",
+            body: "Hola {name}, tu reserva de {service} para el {date} fue cancelada.",
+        },
+        (_, EventKind::Created) => Template {
+            subject: "Agendamento recebido: {service}",
+            body: "Olá {name}, recebemos seu agendamento de {service} para {date}. Avisaremos quando for confirmado.",
+        },
+        (_, EventKind::Confirmed) => Template {
+            subject: "Agendamento confirmado: {service}",
+            body: "Olá {name}, seu agendamento de {service} para {date} está confirmado. Até breve!",
+        },
+        (_, EventKind::Rescheduled) => Template {
+            subject: "Agendamento remarcado: {service}",
+            body: "Olá {name}, seu agendamento de {service} foi remarcado para {date}.",
+        },
+        (_, EventKind::Canceled) => Template {
+            subject: "Agendamento cancelado: {service}",
+            body: "Olá {name}, seu agendamento de {service} para {date} foi cancelado.",
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::BookingKind;
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    fn event(kind: EventKind) -> DomainEvent {
+        let starts_at = NaiveDate::from_ymd_opt(2025, 5, 10).unwrap().and_hms_opt(14, 30, 0).unwrap();
+        DomainEvent {
+            kind,
+            booking: BookingKind::Appointment,
+            booking_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            professional_id: None,
+            service: "Corte".to_string(),
+            starts_at,
+            occurred_at: starts_at,
+        }
+    }
+
+    #[test]
+    fn test_render_localized_templates() {
+        let rendered = render(&event(EventKind::Confirmed), "pt-BR", "Ana");
+        assert_eq!(rendered.subject, "Agendamento confirmado: Corte");
+        assert_eq!(rendered.body, "Olá Ana, seu agendamento de Corte para 10/05/2025 às 14:30 está confirmado. Até breve!");
+
+        let rendered = render(&event(EventKind::Canceled), "en-US", "Ana");
+        assert_eq!(rendered.body, "Hi Ana, your booking for Corte on 2025-05-10 at 14:30 was canceled.");
+
+        // Idioma sem template → pt-BR
+        assert_eq!(render(&event(EventKind::Created), "fr", "Ana").subject, "Agendamento recebido: Corte");
+        assert_eq!(normalize_locale("es_AR"), "es");
+    }
+
+    #[test]
+    fn test_render_reminder() {
+        let starts_at = NaiveDate::from_ymd_opt(2025, 5, 10).unwrap().and_hms_opt(9, 0, 0).unwrap();
+        let rendered = render_reminder("pt-BR", "Ana", "Corte", starts_at, "http://salao/attendance/abc");
+
+        assert_eq!(rendered.subject, "Lembrete: Corte em 10/05/2025 às 09:00");
+        assert!(rendered.body.contains("Confirme sua presença em http://salao/attendance/abc ou responda SIM."));
+    }
+}
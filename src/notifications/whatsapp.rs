@@ -0,0 +1,45 @@
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{Channel, Message, Notifier};
+use crate::services::http_client;
+
+/// 🔹 WhatsApp via webhook (formato de mensagem de texto da Cloud API)
+pub struct WhatsAppNotifier {
+    webhook_url: String,
+    token: Option<String>, // Enviado como `Authorization: Bearer`
+}
+
+impl WhatsAppNotifier {
+    pub fn new(webhook_url: String, token: Option<String>) -> Self {
+        Self { webhook_url, token }
+    }
+}
+
+#[async_trait]
+impl Notifier for WhatsAppNotifier {
+    fn channel(&self) -> Channel {
+        Channel::WhatsApp
+    }
+
+    async fn send(&self, message: &Message) -> Result<(), String> {
+        let headers: Vec<(&str, String)> = self
+            .token
+            .iter()
+            .map(|token| ("authorization", format!("Bearer {}", token)))
+            .collect();
+        let body = json!({
+            "messaging_product": "whatsapp",
+            "to": message.to.trim_start_matches('+'),
+            "type": "text",
+            "text": { "body": message.body },
+        })
+        .to_string();
+
+        let response = http_client::post_json(&self.webhook_url, &headers, body).await?;
+        if !response.is_success() {
+            return Err(format!("Webhook do WhatsApp respondeu {}: {}", response.status, response.body));
+        }
+        Ok(())
+    }
+}
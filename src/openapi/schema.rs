@@ -0,0 +1,364 @@
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// 🔹 Esquemas nomeados (`#/components/schemas/...`)
+pub type Components = BTreeMap<String, Value>;
+
+/// ✅ Esquema JSON (OpenAPI 3.1) de um tipo, extraído da própria implementação de `Deserialize`.
+/// Structs viram componentes nomeados; campos `Option` e com `#[serde(default)]` não são obrigatórios.
+pub fn schema_of<T: DeserializeOwned>(components: &mut Components) -> Result<Value, String> {
+    let mut slot = Slot::default();
+    T::deserialize(Tracer { components, slot: &mut slot, skip: None }).map_err(|e| e.0)?;
+
+    // Campos opcionais do struct de primeiro nível: o tipo ainda desserializa sem eles
+    if let Some(name) = component_name(&slot.schema) {
+        let fields: Vec<String> = components
+            .get(&name)
+            .and_then(|schema| schema["properties"].as_object())
+            .map(|properties| properties.keys().cloned().collect())
+            .unwrap_or_default();
+        let mut required: Vec<String> = fields
+            .into_iter()
+            .filter(|field| {
+                let mut scratch = Components::new();
+                let mut ignored = Slot::default();
+                T::deserialize(Tracer { components: &mut scratch, slot: &mut ignored, skip: Some(field) }).is_err()
+            })
+            .collect();
+        required.sort();
+        if let Some(schema) = components.get_mut(&name).and_then(Value::as_object_mut) {
+            if required.is_empty() {
+                schema.remove("required");
+            } else {
+                schema.insert("required".to_string(), json!(required));
+            }
+        }
+    }
+    Ok(slot.schema)
+}
+
+/// ✅ Parâmetros de query (`in: query`) a partir de um struct desserializado com `Query<T>`
+pub fn query_parameters<T: DeserializeOwned>() -> Result<Vec<Value>, String> {
+    let mut components = Components::new();
+    let schema = schema_of::<T>(&mut components)?;
+    let name = component_name(&schema).ok_or("Parâmetros de query precisam ser um struct")?;
+    let query = &components[&name];
+    let required = query["required"].as_array().cloned().unwrap_or_default();
+
+    Ok(query["properties"]
+        .as_object()
+        .map(|properties| {
+            properties
+                .iter()
+                .map(|(field, schema)| {
+                    json!({
+                        "name": field,
+                        "in": "query",
+                        "required": required.contains(&Value::String(field.clone())),
+                        "schema": inline(schema, &components),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// 🔹 Substitui referências por esquemas (parâmetros de query não podem apontar para componentes de outro documento)
+fn inline(schema: &Value, components: &Components) -> Value {
+    match component_name(schema) {
+        Some(name) => components.get(&name).cloned().unwrap_or_else(|| json!({})),
+        None => schema.clone(),
+    }
+}
+
+fn component_name(schema: &Value) -> Option<String> {
+    schema["$ref"].as_str()?.strip_prefix("#/components/schemas/").map(str::to_string)
+}
+
+/// 🔹 Aceita `null` além do esquema original
+fn nullable(schema: Value) -> Value {
+    match schema.get("type").and_then(Value::as_str) {
+        Some(kind) => {
+            let mut schema = schema.clone();
+            schema["type"] = json!([kind, "null"]);
+            if let Some(variants) = schema.get_mut("enum").and_then(Value::as_array_mut) {
+                variants.push(Value::Null);
+            }
+            schema
+        }
+        None => json!({ "anyOf": [schema, { "type": "null" }] }),
+    }
+}
+
+#[derive(Default)]
+struct Slot {
+    schema: Value,
+    optional: bool, // `Option<T>`: o campo pode faltar
+}
+
+#[derive(Debug)]
+struct TraceError(String);
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+impl de::Error for TraceError {
+    fn custom<M: fmt::Display>(msg: M) -> Self {
+        TraceError(msg.to_string())
+    }
+}
+
+/// 🔹 Mensagem `expecting` do visitor: identifica UUIDs e datas, que o serde pede como texto
+struct Expecting<'v, V>(&'v V);
+
+impl<'de, V: Visitor<'de>> fmt::Display for Expecting<'_, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(f)
+    }
+}
+
+/// 🔹 Desserializador que não lê nada: responde a cada pedido com um valor de exemplo e anota o esquema
+struct Tracer<'a> {
+    components: &'a mut Components,
+    slot: &'a mut Slot,
+    skip: Option<&'a str>, // Campo omitido do struct de primeiro nível (detecção de campos opcionais)
+}
+
+macro_rules! trace_primitive {
+    ($method:ident, $visit:ident, $sample:expr, $schema:expr) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+            self.slot.schema = $schema;
+            visitor.$visit($sample)
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Tracer<'_> {
+    type Error = TraceError;
+
+    trace_primitive!(deserialize_bool, visit_bool, false, json!({ "type": "boolean" }));
+    trace_primitive!(deserialize_i8, visit_i8, 0, json!({ "type": "integer", "format": "int32" }));
+    trace_primitive!(deserialize_i16, visit_i16, 0, json!({ "type": "integer", "format": "int32" }));
+    trace_primitive!(deserialize_i32, visit_i32, 0, json!({ "type": "integer", "format": "int32" }));
+    trace_primitive!(deserialize_i64, visit_i64, 0, json!({ "type": "integer", "format": "int64" }));
+    trace_primitive!(deserialize_u8, visit_u8, 0, json!({ "type": "integer", "minimum": 0 }));
+    trace_primitive!(deserialize_u16, visit_u16, 0, json!({ "type": "integer", "minimum": 0 }));
+    trace_primitive!(deserialize_u32, visit_u32, 0, json!({ "type": "integer", "minimum": 0 }));
+    trace_primitive!(deserialize_u64, visit_u64, 0, json!({ "type": "integer", "minimum": 0 }));
+    trace_primitive!(deserialize_f32, visit_f32, 0.0, json!({ "type": "number", "format": "float" }));
+    trace_primitive!(deserialize_f64, visit_f64, 0.0, json!({ "type": "number", "format": "double" }));
+    trace_primitive!(deserialize_char, visit_char, 'a', json!({ "type": "string", "minLength": 1, "maxLength": 1 }));
+    trace_primitive!(deserialize_bytes, visit_bytes, &[], json!({ "type": "string", "format": "binary" }));
+    trace_primitive!(deserialize_byte_buf, visit_byte_buf, Vec::new(), json!({ "type": "string", "format": "binary" }));
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        // Qualquer JSON (ex: `serde_json::Value`)
+        self.slot.schema = json!({});
+        visitor.visit_unit()
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        let expecting = Expecting(&visitor).to_string();
+        let (schema, sample) = if expecting.contains("UUID") {
+            (json!({ "type": "string", "format": "uuid" }), "00000000-0000-0000-0000-000000000000")
+        } else if expecting.contains("date and time") {
+            (json!({ "type": "string", "format": "date-time" }), "2025-01-01T00:00:00")
+        } else if expecting.contains("date") {
+            (json!({ "type": "string", "format": "date" }), "2025-01-01")
+        } else if expecting.contains("time") {
+            (json!({ "type": "string", "format": "time" }), "00:00:00")
+        } else {
+            (json!({ "type": "string" }), "")
+        };
+        self.slot.schema = schema;
+        visitor.visit_str(sample)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        let mut inner = Slot::default();
+        let value = visitor.visit_some(Tracer { components: self.components, slot: &mut inner, skip: None })?;
+        self.slot.schema = nullable(inner.schema);
+        self.slot.optional = true;
+        Ok(value)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        self.slot.schema = json!({ "type": "null" });
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, TraceError> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, TraceError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        let mut items = Slot::default();
+        let value = visitor.visit_seq(OneElement { components: self.components, slot: &mut items, done: false })?;
+        self.slot.schema = json!({ "type": "array", "items": items.schema });
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, TraceError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, TraceError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        self.slot.schema = json!({ "type": "object" });
+        visitor.visit_map(Fields { components: self.components, fields: Vec::new().into_iter(), properties: Map::new(), required: Vec::new(), current: None })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        let fields: Vec<&'static str> = fields.iter().copied().filter(|field| Some(*field) != self.skip).collect();
+        let mut access = Fields { components: self.components, fields: fields.into_iter(), properties: Map::new(), required: Vec::new(), current: None };
+        let value = visitor.visit_map(&mut access)?;
+
+        let mut schema = json!({ "type": "object", "properties": access.properties });
+        if !access.required.is_empty() {
+            schema["required"] = json!(access.required);
+        }
+        access.components.insert(name.to_string(), schema);
+        self.slot.schema = json!({ "$ref": format!("#/components/schemas/{}", name) });
+        Ok(value)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        // Só enums de variantes simples (viram strings)
+        self.slot.schema = json!({ "type": "string", "enum": variants });
+        let first = variants.first().ok_or_else(|| TraceError("Enum sem variantes".to_string()))?;
+        visitor.visit_enum(first.into_deserializer())
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        self.deserialize_str(visitor)
+    }
+}
+
+/// 🔹 Campos de um struct, entregues um a um com valores de exemplo
+struct Fields<'a> {
+    components: &'a mut Components,
+    fields: std::vec::IntoIter<&'static str>,
+    properties: Map<String, Value>,
+    required: Vec<Value>,
+    current: Option<&'static str>,
+}
+
+impl<'de> de::MapAccess<'de> for Fields<'_> {
+    type Error = TraceError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, TraceError> {
+        match self.fields.next() {
+            Some(field) => {
+                self.current = Some(field);
+                seed.deserialize(field.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, TraceError> {
+        let field = self.current.take().ok_or_else(|| TraceError("Valor sem campo".to_string()))?;
+        let mut slot = Slot::default();
+        let value = seed.deserialize(Tracer { components: self.components, slot: &mut slot, skip: None })?;
+        if !slot.optional {
+            self.required.push(Value::String(field.to_string()));
+        }
+        self.properties.insert(field.to_string(), slot.schema);
+        Ok(value)
+    }
+}
+
+/// 🔹 Sequência com um único elemento, usado para descobrir o esquema dos itens
+struct OneElement<'a> {
+    components: &'a mut Components,
+    slot: &'a mut Slot,
+    done: bool,
+}
+
+impl<'de> de::SeqAccess<'de> for OneElement<'_> {
+    type Error = TraceError;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>, TraceError> {
+        if self.done {
+            return Ok(None);
+        }
+        self.done = true;
+        seed.deserialize(Tracer { components: self.components, slot: self.slot, skip: None }).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveDateTime};
+    use serde::Deserialize;
+    use uuid::Uuid;
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Kind {
+        Corte,
+        Barba,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    struct Booking {
+        id: Uuid,
+        at: NaiveDateTime,
+        day: Option<NaiveDate>,
+        #[serde(default)]
+        tags: Vec<String>,
+        kind: Kind,
+        price: f64,
+    }
+
+    #[test]
+    fn test_schema_from_deserialize() {
+        let mut components = Components::new();
+        let schema = schema_of::<Booking>(&mut components).unwrap();
+        assert_eq!(schema, json!({ "$ref": "#/components/schemas/Booking" }));
+
+        let booking = &components["Booking"];
+        assert_eq!(booking["properties"]["id"]["format"], "uuid");
+        assert_eq!(booking["properties"]["at"]["format"], "date-time");
+        assert_eq!(booking["properties"]["day"]["type"], json!(["string", "null"]));
+        assert_eq!(booking["properties"]["tags"]["items"]["type"], "string");
+        assert_eq!(booking["properties"]["kind"]["enum"], json!(["corte", "barba"]));
+        assert_eq!(nullable(booking["properties"]["kind"].clone())["enum"], json!(["corte", "barba", null]));
+        assert_eq!(booking["required"], json!(["at", "id", "kind", "price"]));
+    }
+}
@@ -0,0 +1,310 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 🔹 Itens por página (padrão e máximo)
+pub const DEFAULT_LIMIT: i64 = 20;
+pub const MAX_LIMIT: i64 = 100;
+
+/// 🔹 Direção da ordenação
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// 🔹 Parâmetros aceitos por todas as listagens (`?limit=20&cursor=...&sort=name&order=desc&status=...`)
+#[derive(Debug, Default, Deserialize)]
+pub struct ListQuery {
+    limit: Option<i64>,
+    cursor: Option<String>,
+    sort: Option<String>,
+    order: Option<SortOrder>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    status: Option<String>,
+    professional_id: Option<Uuid>,
+    service_id: Option<Uuid>,
+    active: Option<bool>,
+    q: Option<String>,
+    include_deleted: Option<bool>,
+    actor_id: Option<Uuid>,
+    entity_type: Option<String>,
+    entity_id: Option<Uuid>,
+    action: Option<String>,
+}
+
+/// 🔹 Filtros tipados; cada listagem declara quais aceita
+#[derive(Debug, Default)]
+pub struct ListFilters {
+    pub from: Option<NaiveDate>, // Inclusivo
+    pub to: Option<NaiveDate>,   // Inclusivo
+    pub status: Option<String>,
+    pub professional_id: Option<Uuid>,
+    pub service_id: Option<Uuid>,
+    pub active: Option<bool>,
+    pub q: Option<String>,       // Busca por nome (sem diferenciar maiúsculas)
+    pub include_deleted: Option<bool>, // Inclui excluídos logicamente (apenas admin)
+    pub actor_id: Option<Uuid>,        // Trilha de auditoria: quem fez
+    pub entity_type: Option<String>,   // Trilha de auditoria: em que tipo de registro
+    pub entity_id: Option<Uuid>,
+    pub action: Option<String>,
+}
+
+impl ListFilters {
+    /// ✅ Início do intervalo (`from` às 00:00)
+    pub fn starts_at(&self) -> Option<NaiveDateTime> {
+        self.from.map(|from| from.and_time(NaiveTime::MIN))
+    }
+
+    /// ✅ Fim exclusivo do intervalo (dia seguinte a `to` às 00:00)
+    pub fn ends_before(&self) -> Option<NaiveDateTime> {
+        self.to.and_then(|to| to.succ_opt()).map(|to| to.and_time(NaiveTime::MIN))
+    }
+
+    /// ✅ Padrão `ILIKE` da busca textual, com `%` e `_` escapados
+    pub fn search_pattern(&self) -> Option<String> {
+        self.q.as_deref().map(str::trim).filter(|q| !q.is_empty()).map(|q| {
+            let escaped = q.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+            format!("%{}%", escaped)
+        })
+    }
+
+    fn present(&self) -> Vec<&'static str> {
+        [
+            ("from", self.from.is_some()),
+            ("to", self.to.is_some()),
+            ("status", self.status.is_some()),
+            ("professional_id", self.professional_id.is_some()),
+            ("service_id", self.service_id.is_some()),
+            ("active", self.active.is_some()),
+            ("q", self.q.is_some()),
+            ("include_deleted", self.include_deleted.is_some()),
+            ("actor_id", self.actor_id.is_some()),
+            ("entity_type", self.entity_type.is_some()),
+            ("entity_id", self.entity_id.is_some()),
+            ("action", self.action.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, present)| present.then_some(name))
+        .collect()
+    }
+}
+
+/// 🔹 Posição na listagem: valor do campo ordenado e ID do último item da página anterior
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cursor {
+    #[serde(rename = "s")]
+    pub sort: String,
+    #[serde(rename = "o")]
+    pub order: SortOrder,
+    #[serde(rename = "v")]
+    pub value: serde_json::Value,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    fn decode(token: &str) -> Option<Self> {
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(token).ok()?).ok()
+    }
+
+    /// ✅ Valor do campo ordenado, no tipo da coluna
+    pub fn value<T: DeserializeOwned>(&self) -> Result<T, (StatusCode, String)> {
+        serde_json::from_value(self.value.clone()).map_err(|_| invalid_cursor())
+    }
+}
+
+/// 🔹 Extractor compartilhado pelas listagens: paginação por cursor (keyset), ordenação e filtros
+#[derive(Debug)]
+pub struct ListParams {
+    pub limit: i64,
+    pub sort: Option<String>,
+    pub order: Option<SortOrder>,
+    pub cursor: Option<Cursor>,
+    pub filters: ListFilters,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ListParams {
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(raw) = Query::<ListQuery>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.body_text()))?;
+
+        let limit = raw.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err((StatusCode::BAD_REQUEST, format!("`limit` deve estar entre 1 e {}", MAX_LIMIT)));
+        }
+        let cursor = raw.cursor.as_deref().map(|token| Cursor::decode(token).ok_or_else(invalid_cursor)).transpose()?;
+        if let (Some(from), Some(to)) = (raw.from, raw.to) {
+            if to < from {
+                return Err((StatusCode::BAD_REQUEST, "`to` deve ser igual ou posterior a `from`".to_string()));
+            }
+        }
+
+        Ok(ListParams {
+            limit,
+            sort: raw.sort,
+            order: raw.order,
+            cursor,
+            filters: ListFilters {
+                from: raw.from,
+                to: raw.to,
+                status: raw.status,
+                professional_id: raw.professional_id,
+                service_id: raw.service_id,
+                active: raw.active,
+                q: raw.q,
+                include_deleted: raw.include_deleted,
+                actor_id: raw.actor_id,
+                entity_type: raw.entity_type,
+                entity_id: raw.entity_id,
+                action: raw.action,
+            },
+        })
+    }
+}
+
+/// 🔹 Ordenação efetiva de uma listagem
+#[derive(Debug, Clone, Copy)]
+pub struct Sort {
+    pub field: &'static str,
+    pub order: SortOrder,
+}
+
+impl ListParams {
+    /// ✅ Valida filtros e ordenação contra o que a listagem suporta (o primeiro campo de `sort_fields` é o padrão).
+    /// O cursor precisa ter sido gerado com a mesma ordenação.
+    pub fn accept(
+        &self,
+        filters: &[&str],
+        sort_fields: &[&'static str],
+        default_order: SortOrder,
+    ) -> Result<Sort, (StatusCode, String)> {
+        let unsupported: Vec<&str> = self.filters.present().into_iter().filter(|name| !filters.contains(name)).collect();
+        if !unsupported.is_empty() {
+            return Err((StatusCode::BAD_REQUEST, format!("Filtros não suportados: {}", unsupported.join(", "))));
+        }
+
+        let field = match self.sort.as_deref() {
+            None => sort_fields[0],
+            Some(requested) => sort_fields.iter().copied().find(|field| *field == requested).ok_or_else(|| {
+                (StatusCode::BAD_REQUEST, format!("`sort` deve ser um de: {}", sort_fields.join(", ")))
+            })?,
+        };
+        let sort = Sort { field, order: self.order.unwrap_or(default_order) };
+
+        if let Some(cursor) = &self.cursor {
+            if cursor.sort != sort.field || cursor.order != sort.order {
+                return Err(invalid_cursor());
+            }
+        }
+        Ok(sort)
+    }
+}
+
+/// 🔹 Envelope das listagens
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>, // `null` na última página
+    pub total: Option<i64>,          // Total com os filtros aplicados (ignora o cursor)
+}
+
+impl<T> Page<T> {
+    /// ✅ Monta a página a partir de `limit + 1` linhas; a linha extra só indica que há próxima página
+    pub fn new(
+        mut rows: Vec<T>,
+        params: &ListParams,
+        sort: Sort,
+        total: Option<i64>,
+        key: impl Fn(&T) -> (serde_json::Value, Uuid),
+    ) -> Self {
+        let has_more = rows.len() as i64 > params.limit;
+        rows.truncate(params.limit as usize);
+        let next_cursor = rows.last().filter(|_| has_more).map(|last| {
+            let (value, id) = key(last);
+            Cursor { sort: sort.field.to_string(), order: sort.order, value, id }.encode()
+        });
+        Page { items: rows, next_cursor, total }
+    }
+}
+
+/// ✅ Aplica o cursor e a ordenação (`campo, id`) numa consulta boxed e limita a `limit + 1` linhas.
+/// `keyset!(consulta, coluna, tipo_do_valor, coluna_id, params, sort)`; usar com `?` (o cursor pode ser inválido).
+macro_rules! keyset {
+    ($query:expr, $column:expr, $value:ty, $id:expr, $params:expr, $sort:expr) => {{
+        let mut query = $query;
+        if let Some(cursor) = &$params.cursor {
+            query = match $sort.order {
+                $crate::pagination::SortOrder::Asc => query.filter(
+                    $column.gt(cursor.value::<$value>()?).or($column.eq(cursor.value::<$value>()?).and($id.gt(cursor.id))),
+                ),
+                $crate::pagination::SortOrder::Desc => query.filter(
+                    $column.lt(cursor.value::<$value>()?).or($column.eq(cursor.value::<$value>()?).and($id.lt(cursor.id))),
+                ),
+            };
+        }
+        let query = match $sort.order {
+            $crate::pagination::SortOrder::Asc => query.order(($column.asc(), $id.asc())),
+            $crate::pagination::SortOrder::Desc => query.order(($column.desc(), $id.desc())),
+        };
+        query.limit($params.limit + 1)
+    }};
+}
+pub(crate) use keyset;
+
+fn invalid_cursor() -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, "Cursor inválido para esta listagem/ordenação".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(cursor: Option<Cursor>, q: Option<&str>) -> ListParams {
+        ListParams {
+            limit: 2,
+            sort: Some("name".to_string()),
+            order: None,
+            cursor,
+            filters: ListFilters { q: q.map(str::to_string), ..Default::default() },
+        }
+    }
+
+    #[test]
+    fn test_page_cursor_and_validation() {
+        let list = params(None, Some("50%_off"));
+        let sort = list.accept(&["q"], &["created_at", "name"], SortOrder::Asc).unwrap();
+        assert_eq!(sort.field, "name");
+        assert_eq!(list.filters.search_pattern().as_deref(), Some("%50\\%\\_off%"));
+        assert!(list.accept(&[], &["name"], SortOrder::Asc).is_err()); // `q` não suportado
+        assert!(params(None, None).accept(&[], &["created_at"], SortOrder::Asc).is_err()); // `sort` fora da lista
+
+        let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let rows = vec![("Ana", ids[0]), ("Bia", ids[1]), ("Caio", ids[2])];
+        let page = Page::new(rows, &list, sort, Some(3), |(name, id)| (serde_json::json!(name), *id));
+        assert_eq!(page.items.len(), 2);
+
+        let cursor = Cursor::decode(page.next_cursor.as_deref().unwrap()).unwrap();
+        assert_eq!((cursor.value::<String>().unwrap().as_str(), cursor.id), ("Bia", ids[1]));
+        assert!(params(Some(cursor.clone()), None).accept(&[], &["name"], SortOrder::Asc).is_ok());
+        assert!(params(Some(cursor), None).accept(&[], &["name"], SortOrder::Desc).is_err()); // Ordem diferente do cursor
+
+        let last = Page::new(vec![("Caio", ids[2])], &list, sort, None, |(name, id)| (serde_json::json!(name), *id));
+        assert!(last.next_cursor.is_none());
+    }
+}
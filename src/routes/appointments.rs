@@ -1,11 +1,12 @@
-use axum::{Router, routing::{get, post, put, delete}, Extension};
+use axum::{Router, routing::{get, post}, Extension};
 use std::sync::Arc;
 
 use crate::{
     db::Pool,
     config::Config,
     handlers::appointment::{
-        create_appointment, list_appointments_by_client, update_appointment, delete_appointment,
+        create_appointment, get_appointment, list_appointments_by_client, update_appointment, delete_appointment,
+        restore_appointment,
     },
 };
 
@@ -13,7 +14,8 @@ pub fn router(pool: Arc<Pool>, config: Arc<Config>) -> Router {
     Router::new()
         .route("/", post(create_appointment))
         .route("/client/:client_id", get(list_appointments_by_client))  // Modificado
-        .route("/appointment/:id", put(update_appointment).delete(delete_appointment))  // Modificado
+        .route("/appointment/:id", get(get_appointment).put(update_appointment).delete(delete_appointment))  // Modificado
+        .route("/appointment/:id/restore", post(restore_appointment))  // Restaura um agendamento excluído (admin)
         .layer(Extension(pool))
         .layer(Extension(config))
 }
@@ -0,0 +1,15 @@
+use axum::{Router, routing::get, Extension};
+use std::sync::Arc;
+
+use crate::{
+    db::Pool,
+    config::Config,
+    handlers::audit::list_audit_events,
+};
+
+pub fn router(pool: Arc<Pool>, config: Arc<Config>) -> Router {
+    Router::new()
+        .route("/", get(list_audit_events)) // Trilha de auditoria (admin)
+        .layer(Extension(pool))  // Compartilhar a conexão com o banco
+        .layer(Extension(config)) // Compartilhar a configuração
+}
@@ -5,7 +5,8 @@ use crate::{
     db::Pool,
     config::Config,
     handlers::availability::{
-        create_availability, list_availabilities_by_professional // Corrigido para o nome correto da função
+        create_availability, list_availabilities_by_professional, // Corrigido para o nome correto da função
+        list_free_intervals,
     },
 };
 
@@ -13,6 +14,7 @@ pub fn router(pool: Arc<Pool>, config: Arc<Config>) -> Router {
     Router::new()
         .route("/", post(create_availability))  // Rota para criação de disponibilidade
         .route("/:professional_id", get(list_availabilities_by_professional))  // Corrigido para o nome correto da função
+        .route("/:professional_id/free", get(list_free_intervals))  // Disponibilidade menos compromissos externos
         .layer(Extension(pool))  // Passando o Arc<Pool> diretamente
         .layer(Extension(config)) // Passando a Config compartilhada
 }
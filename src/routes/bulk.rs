@@ -0,0 +1,16 @@
+use axum::{Router, routing::{get, post}, Extension};
+use std::sync::Arc;
+
+use crate::{
+    db::Pool,
+    config::Config,
+    handlers::bulk::{import, export},
+};
+
+pub fn router(pool: Arc<Pool>, config: Arc<Config>) -> Router {
+    Router::new()
+        .route("/:entity/import", post(import)) // Importação em massa (CSV ou JSON)
+        .route("/:entity/export", get(export))  // Exportação no mesmo formato
+        .layer(Extension(pool))  // Compartilhar a conexão com o banco
+        .layer(Extension(config)) // Compartilhar a configuração
+}
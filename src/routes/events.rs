@@ -0,0 +1,17 @@
+use axum::{Router, routing::get, Extension};
+use std::sync::Arc;
+
+use crate::{
+    db::Pool,
+    config::Config,
+    handlers::event_stream::stream_events,
+    shutdown::Shutdown,
+};
+
+pub fn router(pool: Arc<Pool>, config: Arc<Config>, shutdown: Shutdown) -> Router {
+    Router::new()
+        .route("/stream", get(stream_events)) // Alterações da agenda ao vivo (SSE)
+        .layer(Extension(pool))  // Compartilhar a conexão com o banco
+        .layer(Extension(config)) // Compartilhar a configuração
+        .layer(Extension(shutdown)) // Encerra os streams abertos no shutdown
+}
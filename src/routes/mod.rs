@@ -4,4 +4,10 @@ pub mod users;
 pub mod services;
 pub mod availabilities;
 pub mod appointments;
-pub mod salon_settings;
\ No newline at end of file
+pub mod salon_settings;
+pub mod webhooks;
+pub mod events;
+pub mod reports;
+pub mod bulk;
+pub mod audit_events;
+pub mod v1;
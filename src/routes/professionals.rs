@@ -6,6 +6,11 @@ use crate::{
     config::Config,
     handlers::professional::{
         create_professional, get_professional_by_id, update_professional, delete_professional, list_professionals,
+        restore_professional,
+    },
+    handlers::busy_block::{
+        import_busy_blocks, list_busy_blocks, create_calendar_source, list_calendar_sources,
+        sync_calendar_source, delete_calendar_source,
     },
 };
 
@@ -15,6 +20,12 @@ pub fn router(pool: Arc<Pool>, config: Arc<Config>) -> Router { // Alterando par
         .route("/:id", get(get_professional_by_id))  // Buscar um profissional específico
         .route("/:id", put(update_professional))  // Atualizar um profissional específico
         .route("/:id", delete(delete_professional))  // Deletar um profissional específico
+        .route("/:id/restore", post(restore_professional))  // Restaurar um profissional excluído (admin)
+        .route("/:id/busy-blocks", get(list_busy_blocks))  // Compromissos externos que bloqueiam a agenda
+        .route("/:id/busy-blocks/import", post(import_busy_blocks))  // Upload de arquivo .ics
+        .route("/:id/calendar-sources", post(create_calendar_source).get(list_calendar_sources))  // Calendários relidos periodicamente
+        .route("/:id/calendar-sources/:source_id", delete(delete_calendar_source))
+        .route("/:id/calendar-sources/:source_id/sync", post(sync_calendar_source))  // Força uma releitura
         .layer(Extension(pool))  // Passando a pool de conexões
         .layer(Extension(config))  // Passando a configuração do sistema
 }
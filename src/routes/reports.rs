@@ -0,0 +1,15 @@
+use axum::{Router, routing::get, Extension};
+use std::sync::Arc;
+
+use crate::{
+    db::Pool,
+    config::Config,
+    handlers::report::get_report,
+};
+
+pub fn router(pool: Arc<Pool>, config: Arc<Config>) -> Router {
+    Router::new()
+        .route("/", get(get_report)) // Relatório do período (JSON ou CSV)
+        .layer(Extension(pool))  // Compartilhar a conexão com o banco
+        .layer(Extension(config)) // Compartilhar a configuração
+}
@@ -1,42 +1,54 @@
 use axum::{
     extract::{Extension, Path, Json},
-    routing::{get, post, put, delete},
+    routing::get,
     Router,
     http::StatusCode,
 };
+use std::sync::Arc;
 use uuid::Uuid;
+use chrono::NaiveDateTime;
 use serde_json::json;
 use diesel::prelude::*;
 use crate::db::Pool;
-use crate::middleware::auth_middleware::{AuthMiddleware, RequireRole};  // Importação corrigida
-use crate::models::reservation::{Reservation, NewReservation, UpdateReservation};
-use crate::services::reservation_service;
+use crate::events::{CalendarChange, ChangeAction, DomainEvent, EventBus, EventKind};
+use crate::models::reservation::{CreateReservation, Reservation, NewReservation, UpdateReservation};
+use crate::pagination::{keyset, ListParams, Page, SortOrder};
+use crate::schema::reservations;
+use crate::services::{outbox, reservation_service};
 
 /// 🔹 Cria uma reserva.
 pub async fn create_reservation(
-    Extension(pool): Extension<Pool>,
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(events): Extension<EventBus>,
     Extension(user_id): Extension<Uuid>,  // ✅ Obtém `user_id` autenticado via middleware
-    Json(payload): Json<NewReservation>,
+    Json(payload): Json<CreateReservation>,
 ) -> Result<Json<Reservation>, (StatusCode, String)> {
     let mut conn = pool.get().map_err(map_db_error)?;
 
     // Criação da reserva
     let new_reservation = NewReservation {
         user_id,  // ✅ AGORA USANDO `user_id`
-        service: payload.service.clone(),
+        service: payload.service,
         appointment_time: payload.appointment_time,
         status: "pending".to_string(),
     };
 
-    // ✅ Chama a função de serviço para criar a reserva no banco
-    reservation_service::create_reservation(&mut conn, new_reservation)
-        .map(Json)
-        .map_err(map_internal_error)
+    // ✅ Chama a função de serviço para criar a reserva no banco (evento gravado na mesma transação)
+    let reservation = conn
+        .transaction(|conn| {
+            let reservation = reservation_service::create_reservation(conn, new_reservation)?;
+            outbox::record_event(conn, &DomainEvent::from_reservation(EventKind::Created, &reservation))?;
+            Ok(reservation)
+        })
+        .map_err(map_internal_error)?;
+
+    events.publish(CalendarChange::reservation(ChangeAction::Created, &reservation, None));
+    Ok(Json(reservation))
 }
 
 /// 🔹 Busca uma reserva específica por ID.
 pub async fn get_reservation(
-    Extension(pool): Extension<Pool>,
+    Extension(pool): Extension<Arc<Pool>>,
     Extension(user_id): Extension<Uuid>,  // ✅ Obtém `user_id` autenticado via middleware
     Extension(role): Extension<String>,   // ✅ Obtém o papel do usuário (role)
     Path(reservation_id): Path<Uuid>,
@@ -54,30 +66,55 @@ pub async fn get_reservation(
     Ok(Json(reservation))
 }
 
-/// 🔹 Lista todas as reservas.
+/// 🔹 Lista as reservas, paginado.
+/// Filtros: `from`/`to`, `status`, `q` (nome do serviço). Ordenação: `appointment_time` (padrão, asc).
 pub async fn get_reservations(
-    Extension(pool): Extension<Pool>,
+    Extension(pool): Extension<Arc<Pool>>,
     Extension(user_id): Extension<Uuid>,  // ✅ Obtém `user_id` autenticado via middleware
     Extension(role): Extension<String>,   // ✅ Obtém o papel do usuário (role)
-) -> Result<Json<Vec<Reservation>>, (StatusCode, String)> {
+    params: ListParams,
+) -> Result<Json<Page<Reservation>>, (StatusCode, String)> {
+    let sort = params.accept(&["from", "to", "status", "q"], &["appointment_time"], SortOrder::Asc)?;
     let mut conn = pool.get().map_err(map_db_error)?;
 
-    let reservations = if role == "client" {
-        // 🔒 Clients só podem ver suas próprias reservas
-        reservation_service::list_reservations_by_user(&mut conn, user_id)
-    } else {
-        // 🔒 Admins e Admin Masters podem ver tudo
-        reservation_service::list_reservations(&mut conn)
+    let filtered = || {
+        let mut query = reservations::table.into_boxed();
+        if role == "client" {
+            // 🔒 Clients só podem ver suas próprias reservas; Admins e Admin Masters podem ver tudo
+            query = query.filter(reservations::user_id.eq(user_id));
+        }
+        if let Some(start) = params.filters.starts_at() {
+            query = query.filter(reservations::appointment_time.ge(start));
+        }
+        if let Some(end) = params.filters.ends_before() {
+            query = query.filter(reservations::appointment_time.lt(end));
+        }
+        if let Some(status) = params.filters.status.clone() {
+            query = query.filter(reservations::status.eq(status));
+        }
+        if let Some(pattern) = params.filters.search_pattern() {
+            query = query.filter(reservations::service.ilike(pattern));
+        }
+        query
     };
 
-    reservations
-        .map(Json)
-        .map_err(map_internal_error)
+    let total = match params.cursor {
+        None => Some(filtered().count().get_result::<i64>(&mut conn).map_err(map_internal_error)?),
+        Some(_) => None,
+    };
+    let results = keyset!(filtered(), reservations::appointment_time, NaiveDateTime, reservations::id, params, sort)
+        .load::<Reservation>(&mut conn)
+        .map_err(map_internal_error)?;
+
+    Ok(Json(Page::new(results, &params, sort, total, |reservation| {
+        (json!(reservation.appointment_time), reservation.id)
+    })))
 }
 
 /// 🔹 Atualiza uma reserva existente.
 pub async fn update_reservation(
-    Extension(pool): Extension<Pool>,
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(events): Extension<EventBus>,
     Extension(user_id): Extension<Uuid>,  // ✅ Obtém `user_id` autenticado via middleware
     Extension(role): Extension<String>,   // ✅ Obtém o papel do usuário (role)
     Path(reservation_id): Path<Uuid>,
@@ -93,14 +130,31 @@ pub async fn update_reservation(
         return Err((StatusCode::FORBIDDEN, "You are not allowed to update this reservation.".to_string()));
     }
 
-    reservation_service::update_reservation(&mut conn, reservation_id, payload)
-        .map(Json)
-        .map_err(map_internal_error)
+    let reservation = conn
+        .transaction(|conn| {
+            let reservation = reservation_service::update_reservation(conn, reservation_id, payload)?;
+
+            // ✅ Confirmação, cancelamento ou remarcação viram notificação para o cliente
+            if let Some(kind) = EventKind::from_transition(
+                &existing_reservation.status,
+                existing_reservation.appointment_time,
+                &reservation.status,
+                reservation.appointment_time,
+            ) {
+                outbox::record_event(conn, &DomainEvent::from_reservation(kind, &reservation))?;
+            }
+            Ok(reservation)
+        })
+        .map_err(map_internal_error)?;
+
+    events.publish(CalendarChange::reservation(ChangeAction::Updated, &reservation, Some(&existing_reservation)));
+    Ok(Json(reservation))
 }
 
 /// 🔹 Remove uma reserva por ID.
 pub async fn delete_reservation(
-    Extension(pool): Extension<Pool>,
+    Extension(pool): Extension<Arc<Pool>>,
+    Extension(events): Extension<EventBus>,
     Extension(user_id): Extension<Uuid>,  // ✅ Obtém `user_id` autenticado via middleware
     Extension(role): Extension<String>,   // ✅ Obtém o papel do usuário (role)
     Path(reservation_id): Path<Uuid>,
@@ -115,8 +169,19 @@ pub async fn delete_reservation(
         return Err((StatusCode::FORBIDDEN, "You are not allowed to delete this reservation.".to_string()));
     }
 
-    match reservation_service::delete_reservation(&mut conn, reservation_id) {
-        Ok(deleted) if deleted > 0 => Ok(Json(json!({"message": "Reservation deleted"}))),
+    let deleted = conn.transaction(|conn| {
+        let deleted = reservation_service::delete_reservation(conn, reservation_id)?;
+        if deleted > 0 && existing_reservation.status != "canceled" {
+            outbox::record_event(conn, &DomainEvent::from_reservation(EventKind::Canceled, &existing_reservation))?;
+        }
+        Ok::<_, diesel::result::Error>(deleted)
+    });
+
+    match deleted {
+        Ok(deleted) if deleted > 0 => {
+            events.publish(CalendarChange::reservation(ChangeAction::Deleted, &existing_reservation, None));
+            Ok(Json(json!({"message": "Reservation deleted"})))
+        }
         Ok(_) => Err((StatusCode::NOT_FOUND, "Reservation not found".to_string())),
         Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
     }
@@ -141,24 +206,62 @@ fn map_not_found_error(e: diesel::result::Error) -> (StatusCode, String) {
 }
 
 /// 🔹 Agrega as rotas de reservas.
-pub fn router(pool: Pool) -> Router {
-    // Cria um ServiceBuilder com todas as camadas de middleware necessárias
-    let middleware_stack = tower::ServiceBuilder::new()
-        .layer(AuthMiddleware) // Primeiro verifica a autenticação
-        .layer(RequireRole::new("client".to_string())); // Depois verifica a role
-
+/// Autenticação, idempotência e auditoria vêm da pilha protegida em `v1.rs`; clients só veem as próprias reservas.
+pub fn router(pool: Arc<Pool>) -> Router {
     Router::new()
-        .route(
-            "/",
-            get(get_reservations)    // Rota GET para listar as reservas
-                .post(create_reservation) // Rota POST para criar uma nova reserva
-        )
-        .route(
-            "/:reservation_id",
-            get(get_reservation)     // Rota GET para buscar uma reserva por ID
-                .put(update_reservation)  // Rota PUT para atualizar uma reserva existente
-                .delete(delete_reservation) // Rota DELETE para remover uma reserva
-        )
-        .layer(middleware_stack)
+        .route("/", get(get_reservations).post(create_reservation))  // Lista (paginado) / cria
+        .route("/:reservation_id", get(get_reservation).put(update_reservation).delete(delete_reservation))
         .layer(Extension(pool)) // Compartilha o pool de conexões com o banco de dados
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db::{self, fixtures};
+    use axum::{body::{to_bytes, Body}, http::Request};
+    use tower::ServiceExt;
+
+    fn as_user(pool: &Arc<Pool>, user_id: Uuid, role: &str) -> Router {
+        router(pool.clone())
+            .layer(Extension(user_id))
+            .layer(Extension(role.to_string()))
+            .layer(Extension(EventBus::new()))
+    }
+
+    #[tokio::test]
+    async fn test_client_creates_and_sees_only_own_reservations() {
+        let Some(db) = test_db::setup() else { return };
+        let mut conn = db.pool.get().unwrap();
+        let owner = fixtures::user(&mut conn, "client", "5511900000080");
+        let other = fixtures::user(&mut conn, "client", "5511900000081");
+        drop(conn);
+
+        let request = Request::post("/")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"service":"Corte","appointment_time":"2026-11-02T10:00:00"}"#))
+            .unwrap();
+        let response = as_user(&db.pool, owner, "client").oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let created: serde_json::Value = serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        assert_eq!(created["user_id"], owner.to_string());
+        assert_eq!(created["status"], "pending");
+
+        let list = |user_id, role| {
+            as_user(&db.pool, user_id, role).oneshot(Request::get("/").body(Body::empty()).unwrap())
+        };
+        let total = |response: axum::response::Response| async {
+            let page: serde_json::Value = serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+            page["total"].as_i64().unwrap()
+        };
+        assert_eq!(total(list(owner, "client").await.unwrap()).await, 1);
+        assert_eq!(total(list(other, "client").await.unwrap()).await, 0);
+        assert_eq!(total(list(other, "admin").await.unwrap()).await, 1);
+
+        let path = format!("/{}", created["id"].as_str().unwrap());
+        let response = as_user(&db.pool, other, "client")
+            .oneshot(Request::get(path.as_str()).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
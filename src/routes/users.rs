@@ -1,6 +1,6 @@
 use axum::{
     Router,
-    routing::{get, put, delete, patch},
+    routing::{get, delete, patch, post},
     Extension,
 };
 use std::sync::Arc;
@@ -14,8 +14,21 @@ use crate::{
         get_user_by_id,
         update_user,
         delete_user,
+        restore_user,
         update_user_role,
     },
+    handlers::calendar_feed::rotate_feed_token,
+    handlers::app_password::{create_app_password, list_app_passwords, delete_app_password},
+    handlers::notification_preference::{get_notification_preferences, update_notification_preferences},
+    handlers::privacy::{export_user_data, erase_user_data},
+    handlers::client_profile::{
+        get_client_profile,
+        update_client_profile,
+        list_client_notes,
+        create_client_note,
+        delete_client_note,
+        get_service_history,
+    },
 };
 
 pub fn router(pool: Arc<Pool>, config: Arc<Config>) -> Router {
@@ -23,6 +36,17 @@ pub fn router(pool: Arc<Pool>, config: Arc<Config>) -> Router {
         .route("/", get(list_users)) // Rota para listar usuários
         .route("/:id", get(get_user_by_id).put(update_user).delete(delete_user)) // Rota para obter, atualizar ou excluir usuário por ID
         .route("/:id/role", patch(update_user_role)) // Rota para atualizar o papel de um usuário
+        .route("/:id/restore", post(restore_user)) // Restaura um usuário excluído (admin)
+        .route("/:id/calendar-token", post(rotate_feed_token)) // Gera/rotaciona o token do feed .ics
+        .route("/:id/app-passwords", get(list_app_passwords).post(create_app_password)) // Senhas de aplicativo (CalDAV)
+        .route("/:id/app-passwords/:password_id", delete(delete_app_password))
+        .route("/:id/notification-preferences", get(get_notification_preferences).put(update_notification_preferences)) // Opt-in por canal
+        .route("/:id/profile", get(get_client_profile).put(update_client_profile)) // Ficha do cliente (preferências, alergias, fórmulas)
+        .route("/:id/notes", get(list_client_notes).post(create_client_note)) // Anotações da equipe (staff ou shared)
+        .route("/:id/notes/:note_id", delete(delete_client_note))
+        .route("/:id/service-history", get(get_service_history)) // Atendimentos passados com serviço e profissional
+        .route("/:id/data-export", get(export_user_data)) // Portabilidade (LGPD): titular ou admin_master
+        .route("/:id/erasure", post(erase_user_data)) // Eliminação dos dados pessoais (LGPD): titular ou admin_master
         .layer(Extension(pool))  // Passando o pool de conexões
         .layer(Extension(config)) // Passando as configurações
         .layer(AuthMiddleware)  // Middleware de autenticação para todas as rotas
@@ -0,0 +1,89 @@
+use axum::Router;
+use std::sync::Arc;
+use tower::ServiceBuilder;
+
+use crate::{
+    db::Pool,
+    config::Config,
+    shutdown::Shutdown,
+    handlers::{attendance::attendance_router, auth::auth_router, calendar_feed::calendar_feed_router, guest::guest_router},
+    middleware::{
+        auth_middleware::AuthMiddleware,
+        audit::AuditLayer,
+        cors::cors_middleware,
+        idempotency::IdempotencyLayer,
+        rate_limit::{rate_limit_middleware, strict_rate_limit_middleware},
+    },
+    routes::{appointments, audit_events, availabilities, bulk, events, professionals, reports, reservations, salon_settings, services, users, webhooks},
+};
+
+/// 🔹 Prefixo da versão atual da API (também usado nos links enviados aos clientes)
+pub const PREFIX: &str = "/v1";
+
+/// ✅ Rotas da API v1 (sem o prefixo; `main.rs` monta em `/v1` e mantém os caminhos antigos como aliases obsoletos).
+pub fn router(pool: Arc<Pool>, config: Arc<Config>, shutdown: Shutdown) -> Router {
+    // ✅ Rotas abertas (sem autenticação) → RATE LIMIT + CORS
+    let auth_routes = auth_router(pool.clone(), config.clone())
+        .layer(
+            ServiceBuilder::new()
+                .layer(rate_limit_middleware())
+                .layer(cors_middleware())
+        );
+
+    // ✅ Feeds iCalendar (token secreto na URL, sem Bearer) → RATE LIMIT + CORS
+    let feed_routes = calendar_feed_router(pool.clone())
+        .layer(
+            ServiceBuilder::new()
+                .layer(rate_limit_middleware())
+                .layer(cors_middleware())
+        );
+
+    // ✅ Confirmação de presença (link do lembrete / respostas por mensagem) → RATE LIMIT + CORS
+    let attendance_routes = attendance_router(pool.clone(), config.clone())
+        .layer(AuditLayer) // ✅ Trilha de auditoria (sem usuário: o link/resposta identifica o agendamento)
+        .layer(
+            ServiceBuilder::new()
+                .layer(rate_limit_middleware())
+                .layer(cors_middleware())
+        );
+
+    // ✅ Agendamento sem cadastro (código por SMS / link assinado) → RATE LIMIT + CORS
+    // (os limites por telefone e por IP dos códigos ficam no banco, valem entre instâncias)
+    let guest_routes = guest_router(pool.clone(), config.clone())
+        .layer(AuditLayer) // ✅ Trilha de auditoria (sem usuário: o código/link identifica o convidado)
+        .layer(
+            ServiceBuilder::new()
+                .layer(strict_rate_limit_middleware())
+                .layer(cors_middleware())
+        );
+
+    // ✅ Rotas protegidas (com autenticação) → RATE LIMIT + CORS + AUTH + IDEMPOTÊNCIA + AUDITORIA
+    let protected_routes = Router::new()
+        .nest("/professionals", professionals::router(pool.clone(), config.clone()))
+        .nest("/users", users::router(pool.clone(), config.clone()))
+        .nest("/services", services::router(pool.clone(), config.clone()))
+        .nest("/availabilities", availabilities::router(pool.clone(), config.clone()))
+        .nest("/appointments", appointments::router(pool.clone(), config.clone()))
+        .nest("/reservations", reservations::router(pool.clone()))
+        .nest("/salon-settings", salon_settings::router(pool.clone(), config.clone()))
+        .nest("/webhooks", webhooks::router(pool.clone(), config.clone()))
+        .nest("/reports", reports::router(pool.clone(), config.clone()))
+        .nest("/bulk", bulk::router(pool.clone(), config.clone()))
+        .nest("/audit-events", audit_events::router(pool.clone(), config.clone()))
+        .nest("/events", events::router(pool, config, shutdown))
+        .layer(AuditLayer)       // ✅ Trilha de auditoria das alterações (respostas reenviadas por idempotência não contam de novo)
+        .layer(IdempotencyLayer) // ✅ `Idempotency-Key` (precisa do usuário autenticado)
+        .layer(AuthMiddleware)  // ✅ Middleware de autenticação como layer
+        .layer(
+            ServiceBuilder::new()
+                .layer(strict_rate_limit_middleware())
+                .layer(cors_middleware())
+        );
+
+    Router::new()
+        .nest("/auth", auth_routes)
+        .merge(feed_routes)
+        .merge(attendance_routes)
+        .merge(guest_routes)
+        .merge(protected_routes)
+}
@@ -0,0 +1,28 @@
+use axum::{Router, routing::{get, post}, Extension};
+use std::sync::Arc;
+
+use crate::{
+    db::Pool,
+    config::Config,
+    handlers::webhook::{
+        create_webhook,
+        list_webhooks,
+        get_webhook,
+        update_webhook,
+        delete_webhook,
+        ping_webhook,
+        list_deliveries,
+        redeliver,
+    },
+};
+
+pub fn router(pool: Arc<Pool>, config: Arc<Config>) -> Router {
+    Router::new()
+        .route("/", get(list_webhooks).post(create_webhook)) // Listar / criar assinaturas
+        .route("/:id", get(get_webhook).put(update_webhook).delete(delete_webhook))
+        .route("/:id/ping", post(ping_webhook)) // Evento de teste
+        .route("/:id/deliveries", get(list_deliveries)) // Log de entregas
+        .route("/:id/deliveries/:delivery_id/redeliver", post(redeliver))
+        .layer(Extension(pool))  // Compartilhar a conexão com o banco
+        .layer(Extension(config)) // Compartilhar a configuração
+}
@@ -10,6 +10,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    app_passwords (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        name -> Text,
+        password_hash -> Text,
+        last_used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     appointments (id) {
         id -> Uuid,
@@ -18,6 +29,34 @@ diesel::table! {
         service_id -> Uuid,
         appointment_time -> Timestamp,
         status -> Text,
+        sequence -> Int4,
+        version -> Int4,
+        deleted_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    attendance_confirmations (appointment_id) {
+        appointment_id -> Uuid,
+        token -> Text,
+        confirmed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    audit_events (id) {
+        id -> Uuid,
+        actor_id -> Nullable<Uuid>,
+        actor_role -> Nullable<Text>,
+        action -> Text,
+        entity_type -> Text,
+        entity_id -> Nullable<Uuid>,
+        before -> Nullable<Jsonb>,
+        after -> Nullable<Jsonb>,
+        ip -> Nullable<Text>,
+        request_id -> Nullable<Text>,
+        created_at -> Timestamp,
     }
 }
 
@@ -32,6 +71,91 @@ diesel::table! {
 }
 
 
+diesel::table! {
+    background_jobs (id) {
+        id -> Uuid,
+        kind -> Text,
+        payload -> Jsonb,
+        reference_id -> Nullable<Uuid>,
+        status -> Text,
+        run_at -> Timestamp,
+        attempts -> Int4,
+        max_attempts -> Int4,
+        last_error -> Nullable<Text>,
+        locked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    busy_blocks (id) {
+        id -> Uuid,
+        professional_id -> Uuid,
+        source_id -> Uuid,
+        uid -> Text,
+        summary -> Nullable<Text>,
+        start_time -> Timestamp,
+        end_time -> Timestamp,
+        synced_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    calendar_feed_tokens (user_id) {
+        user_id -> Uuid,
+        created_at -> Timestamp,
+        token_hash -> Text,
+    }
+}
+
+diesel::table! {
+    calendar_sources (id) {
+        id -> Uuid,
+        professional_id -> Uuid,
+        location -> Nullable<Text>,
+        last_synced_at -> Nullable<Timestamp>,
+        last_error -> Nullable<Text>,
+        created_at -> Timestamp,
+        kind -> Text,
+    }
+}
+
+diesel::table! {
+    caldav_objects (id) {
+        id -> Uuid,
+        professional_id -> Uuid,
+        name -> Text,
+        uid -> Text,
+        ics_data -> Text,
+        etag -> Text,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    client_notes (id) {
+        id -> Uuid,
+        client_id -> Uuid,
+        author_id -> Nullable<Uuid>,
+        body -> Text,
+        visibility -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    client_profiles (user_id) {
+        user_id -> Uuid,
+        hair_formula -> Nullable<Text>,
+        allergies -> Nullable<Text>,
+        preferred_professional_ids -> Array<Uuid>,
+        preferred_contact_time -> Nullable<Text>,
+        updated_by -> Nullable<Uuid>,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     clients (id) {
         id -> Uuid,
@@ -41,6 +165,67 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    guest_verifications (id) {
+        id -> Uuid,
+        phone -> Text,
+        code_hash -> Nullable<Text>,
+        attempts -> Int4,
+        ip -> Nullable<Text>,
+        expires_at -> Timestamp,
+        consumed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    idempotency_keys (user_id, idempotency_key) {
+        user_id -> Uuid,
+        idempotency_key -> Text,
+        request_hash -> Text,
+        response_status -> Nullable<Int4>,
+        response_headers -> Nullable<Jsonb>,
+        response_body -> Nullable<Bytea>,
+        locked_at -> Timestamp,
+        created_at -> Timestamp,
+        completed_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    notification_preferences (user_id) {
+        user_id -> Uuid,
+        sms -> Bool,
+        email -> Bool,
+        whatsapp -> Bool,
+        email_address -> Nullable<Text>,
+        locale -> Text,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    outbox_consumptions (consumer, event_id) {
+        consumer -> Text,
+        event_id -> Uuid,
+        processed_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    outbox_events (id) {
+        id -> Uuid,
+        event_type -> Text,
+        payload -> Jsonb,
+        available_at -> Timestamp,
+        attempts -> Int4,
+        last_error -> Nullable<Text>,
+        locked_at -> Nullable<Timestamp>,
+        published_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     professionals (id) {
         id -> Uuid,
@@ -48,6 +233,8 @@ diesel::table! {
         bio -> Nullable<Text>,
         specialties -> Nullable<Array<Nullable<Text>>>,
         created_at -> Timestamp,
+        version -> Int4,
+        deleted_at -> Nullable<Timestamp>,
     }
 }
 
@@ -58,6 +245,7 @@ diesel::table! {
         service -> Text,
         appointment_time -> Timestamp,
         status -> Text,
+        sequence -> Int4,
     }
 }
 
@@ -69,6 +257,8 @@ diesel::table! {
         closing_hour -> Time,
         working_days -> Text, // Armazenado como JSON em formato string
         created_at -> Timestamp,
+        reminder_offsets_hours -> Array<Int4>,
+        version -> Int4,
     }
 }
 
@@ -80,6 +270,8 @@ diesel::table! {
         preco -> Float8,
         duracao_min -> Int4,
         ativo -> Bool,
+        version -> Int4,
+        deleted_at -> Nullable<Timestamp>,
     }
 }
 
@@ -92,23 +284,85 @@ diesel::table! {
         role -> Text,
         sms_verified -> Bool,
         created_at -> Timestamp,
+        version -> Int4,
+        deleted_at -> Nullable<Timestamp>,
+        anonymized_at -> Nullable<Timestamp>,
     }
 }
 
+diesel::joinable!(app_passwords -> users (user_id));
 diesel::joinable!(appointments -> clients (client_id));
 diesel::joinable!(appointments -> professionals (professional_id));
 diesel::joinable!(appointments -> services (service_id));
+diesel::joinable!(attendance_confirmations -> appointments (appointment_id));
 diesel::joinable!(availabilities -> professionals (professional_id));
+diesel::joinable!(busy_blocks -> calendar_sources (source_id));
+diesel::joinable!(busy_blocks -> professionals (professional_id));
+diesel::joinable!(calendar_feed_tokens -> users (user_id));
+diesel::joinable!(caldav_objects -> professionals (professional_id));
+diesel::joinable!(calendar_sources -> professionals (professional_id));
+diesel::joinable!(client_notes -> users (client_id));
+diesel::joinable!(client_profiles -> users (user_id));
+diesel::table! {
+    webhook_deliveries (id) {
+        id -> Uuid,
+        subscription_id -> Uuid,
+        event_type -> Text,
+        payload -> Jsonb,
+        status -> Text,
+        attempts -> Int4,
+        response_status -> Nullable<Int4>,
+        response_body -> Nullable<Text>,
+        last_error -> Nullable<Text>,
+        delivered_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    webhook_subscriptions (id) {
+        id -> Uuid,
+        url -> Text,
+        event_types -> Array<Text>,
+        secret -> Text,
+        active -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::joinable!(idempotency_keys -> users (user_id));
+diesel::joinable!(notification_preferences -> users (user_id));
+diesel::joinable!(outbox_consumptions -> outbox_events (event_id));
 diesel::joinable!(professionals -> users (user_id));
+diesel::joinable!(webhook_deliveries -> webhook_subscriptions (subscription_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     admins,
+    app_passwords,
     appointments,
+    attendance_confirmations,
+    audit_events,
     availabilities,
+    background_jobs,
+    busy_blocks,
+    caldav_objects,
+    calendar_feed_tokens,
+    calendar_sources,
+    client_notes,
+    client_profiles,
     clients,
+    guest_verifications,
+    idempotency_keys,
+    notification_preferences,
+    outbox_consumptions,
+    outbox_events,
     professionals,
     reservations,
     salon_settings,
     services,
     users,
+    webhook_deliveries,
+    webhook_subscriptions,
 );
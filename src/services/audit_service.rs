@@ -0,0 +1,74 @@
+use diesel::prelude::*;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::models::audit_event::NewAuditEvent;
+use crate::schema::audit_events;
+
+/// 🔹 Campos que nunca vão para a trilha (aparecem como alterados, mas sem o valor)
+const REDACTED_FIELDS: [&str; 4] = ["password_hash", "secret", "token", "token_hash"];
+const REDACTED: &str = "[redacted]";
+
+/// ✅ Grava um registro de auditoria (a tabela só aceita INSERT)
+pub fn record(conn: &mut PgConnection, event: &NewAuditEvent) -> QueryResult<()> {
+    diesel::insert_into(audit_events::table).values(event).execute(conn).map(|_| ())
+}
+
+/// ✅ Representação JSON de um registro, com os campos sensíveis mascarados
+pub fn snapshot<T: Serialize>(value: &T) -> Value {
+    let mut value = serde_json::to_value(value).unwrap_or(Value::Null);
+    if let Value::Object(fields) = &mut value {
+        for (field, value) in fields.iter_mut() {
+            if REDACTED_FIELDS.contains(&field.as_str()) && !value.is_null() {
+                *value = Value::String(REDACTED.to_string());
+            }
+        }
+    }
+    value
+}
+
+/// ✅ Diferença entre duas versões de um registro: apenas os campos que mudaram, com os valores de antes e depois.
+/// `version` fica de fora (muda em toda atualização) e campos sensíveis alterados aparecem mascarados.
+pub fn diff<B: Serialize, A: Serialize>(before: &B, after: &A) -> (Value, Value) {
+    let raw = (serde_json::to_value(before).unwrap_or(Value::Null), serde_json::to_value(after).unwrap_or(Value::Null));
+    let (before, after) = match raw {
+        (Value::Object(before), Value::Object(after)) => (before, after),
+        (before, after) => return (before, after),
+    };
+
+    let (mut old, mut new) = (Map::new(), Map::new());
+    let fields = before.keys().chain(after.keys().filter(|field| !before.contains_key(*field)));
+    for field in fields.filter(|field| field.as_str() != "version") {
+        let (previous, current) = (before.get(field).unwrap_or(&Value::Null), after.get(field).unwrap_or(&Value::Null));
+        if previous == current {
+            continue;
+        }
+        let masked = REDACTED_FIELDS.contains(&field.as_str());
+        let mask = |value: &Value| match value {
+            Value::Null => Value::Null,
+            _ if masked => Value::String(REDACTED.to_string()),
+            value => value.clone(),
+        };
+        old.insert(field.clone(), mask(previous));
+        new.insert(field.clone(), mask(current));
+    }
+    (Value::Object(old), Value::Object(new))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_keeps_only_changed_fields() {
+        let before = json!({ "id": 1, "preco": 50.0, "nome": "Corte", "version": 3, "password_hash": "a" });
+        let after = json!({ "id": 1, "preco": 60.0, "nome": "Corte", "version": 4, "password_hash": "b" });
+
+        let (old, new) = diff(&before, &after);
+        assert_eq!(old, json!({ "preco": 50.0, "password_hash": "[redacted]" }));
+        assert_eq!(new, json!({ "preco": 60.0, "password_hash": "[redacted]" }));
+
+        assert_eq!(snapshot(&json!({ "role": "client", "password_hash": "x" }))["password_hash"], "[redacted]");
+    }
+}
@@ -6,6 +6,7 @@ use std::time::{SystemTime, Duration, UNIX_EPOCH};
 use crate::config::Config;
 use crate::models::user::User;
 use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
 
 /// 🔹 Estrutura para os "claims" do token JWT
 #[derive(Debug, Serialize, Deserialize)]
@@ -65,3 +66,10 @@ pub fn verify_password(hash: &str, password: &str) -> bool {
         false
     }
 }
+
+/// 🔐 Compara um segredo recebido com o configurado em tempo constante
+/// (os dois lados viram SHA-256, então nem o tamanho do segredo vaza; todos os bytes são comparados)
+pub fn secrets_match(expected: &str, given: &str) -> bool {
+    let (expected, given) = (Sha256::digest(expected.as_bytes()), Sha256::digest(given.as_bytes()));
+    expected.iter().zip(given.iter()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
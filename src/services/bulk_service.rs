@@ -0,0 +1,674 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::models::professional::Professional;
+use crate::models::service::{NewService, Service};
+use crate::models::user::{NewUser, User};
+use crate::schema::{professionals, services, users};
+use crate::services::outbox;
+
+/// 🔹 Hash que nunca confere com nenhuma senha: clientes importados só entram depois de receber uma senha
+const UNUSABLE_PASSWORD: &str = "!imported";
+
+/// 🔹 Separador de especialidades dentro de uma célula CSV
+const SPECIALTY_SEPARATOR: char = '|';
+
+/// 🔹 Cadastros aceitos na importação/exportação em massa
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkEntity {
+    Services,
+    Professionals,
+    Clients,
+}
+
+/// 🔹 Formato do arquivo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// 🔹 Opções da importação
+#[derive(Debug, Clone, Copy)]
+pub struct ImportOptions {
+    pub dry_run: bool, // Valida e simula tudo, sem gravar
+    pub atomic: bool,  // Tudo ou nada: qualquer linha com erro desfaz a importação inteira
+}
+
+/// 🔹 O que aconteceu com cada linha
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowAction {
+    Created,
+    Updated,
+    Unchanged,
+    Failed,
+}
+
+/// 🔹 Resultado de uma linha (`row` 1 = primeiro registro, sem contar o cabeçalho do CSV)
+#[derive(Debug, Clone, Serialize)]
+pub struct RowResult {
+    pub row: usize,
+    pub key: Option<String>,
+    pub action: RowAction,
+    pub error: Option<String>,
+}
+
+/// 🔹 Relatório da importação
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub entity: BulkEntity,
+    pub dry_run: bool,
+    pub atomic: bool,
+    pub committed: bool, // `false` em simulações e importações atômicas com erro
+    pub total: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub failed: usize,
+    pub rows: Vec<RowResult>,
+}
+
+/// 🔹 Registro de um CSV, acessado pelo nome da coluna (células vazias contam como ausentes)
+pub struct CsvRecord<'a> {
+    columns: &'a HashMap<String, usize>,
+    values: &'a [String],
+}
+
+impl CsvRecord<'_> {
+    pub fn get(&self, column: &str) -> Option<&str> {
+        let value = self.values.get(*self.columns.get(column)?)?.trim();
+        (!value.is_empty()).then_some(value)
+    }
+
+    pub fn required(&self, column: &str) -> Result<&str, String> {
+        self.get(column).ok_or_else(|| format!("Coluna `{}` obrigatória", column))
+    }
+
+    pub fn parse<T: std::str::FromStr>(&self, column: &str) -> Result<Option<T>, String> {
+        self.get(column)
+            .map(|value| value.parse::<T>().map_err(|_| format!("Valor inválido na coluna `{}`: {}", column, value)))
+            .transpose()
+    }
+}
+
+/// 🔹 Linha de um cadastro importável/exportável
+pub trait BulkRow: DeserializeOwned + Serialize + Sized {
+    /// Colunas do CSV, na ordem da exportação
+    const COLUMNS: &'static [&'static str];
+    /// Colunas que precisam existir no cabeçalho do CSV
+    const REQUIRED: &'static [&'static str];
+
+    fn from_csv(record: &CsvRecord) -> Result<Self, String>;
+    fn to_csv(&self) -> Vec<String>;
+    /// Normaliza e valida a linha antes de gravar
+    fn validate(self) -> Result<Self, String>;
+    /// Chave do upsert (linhas com a mesma chave no arquivo são recusadas)
+    fn key(&self) -> String;
+    /// Cria ou atualiza pela chave; um registro excluído logicamente com a mesma chave é restaurado
+    fn upsert(&self, conn: &mut PgConnection) -> QueryResult<RowAction>;
+    /// Registros ativos (os excluídos logicamente ficam de fora)
+    fn export(conn: &mut PgConnection) -> QueryResult<Vec<Self>>;
+}
+
+/// 🔹 Serviço do catálogo (chave: `nome`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ServiceRow {
+    pub nome: String,
+    pub descricao: Option<String>,
+    pub preco: f64,
+    pub duracao_min: i32,
+    #[serde(default = "default_active")]
+    pub ativo: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+impl BulkRow for ServiceRow {
+    const COLUMNS: &'static [&'static str] = &["nome", "descricao", "preco", "duracao_min", "ativo"];
+    const REQUIRED: &'static [&'static str] = &["nome", "preco", "duracao_min"];
+
+    fn from_csv(record: &CsvRecord) -> Result<Self, String> {
+        Ok(ServiceRow {
+            nome: record.required("nome")?.to_string(),
+            descricao: record.get("descricao").map(str::to_string),
+            preco: record.parse("preco")?.ok_or("Coluna `preco` obrigatória")?,
+            duracao_min: record.parse("duracao_min")?.ok_or("Coluna `duracao_min` obrigatória")?,
+            ativo: record.parse("ativo")?.unwrap_or(true),
+        })
+    }
+
+    fn to_csv(&self) -> Vec<String> {
+        vec![
+            self.nome.clone(),
+            self.descricao.clone().unwrap_or_default(),
+            self.preco.to_string(),
+            self.duracao_min.to_string(),
+            self.ativo.to_string(),
+        ]
+    }
+
+    fn validate(mut self) -> Result<Self, String> {
+        self.nome = self.nome.trim().to_string();
+        self.descricao = self.descricao.map(|descricao| descricao.trim().to_string()).filter(|descricao| !descricao.is_empty());
+        if self.nome.is_empty() {
+            return Err("`nome` não pode ser vazio".to_string());
+        }
+        if !self.preco.is_finite() || self.preco < 0.0 {
+            return Err("`preco` deve ser maior ou igual a zero".to_string());
+        }
+        if self.duracao_min <= 0 {
+            return Err("`duracao_min` deve ser maior que zero".to_string());
+        }
+        Ok(self)
+    }
+
+    fn key(&self) -> String {
+        self.nome.clone()
+    }
+
+    fn upsert(&self, conn: &mut PgConnection) -> QueryResult<RowAction> {
+        let existing = services::table
+            .filter(services::nome.eq(&self.nome))
+            .first::<Service>(conn)
+            .optional()?;
+
+        match existing {
+            None => {
+                diesel::insert_into(services::table)
+                    .values(&NewService {
+                        nome: self.nome.clone(),
+                        descricao: self.descricao.clone(),
+                        preco: self.preco,
+                        duracao_min: self.duracao_min,
+                        ativo: self.ativo,
+                    })
+                    .execute(conn)?;
+                Ok(RowAction::Created)
+            }
+            Some(service)
+                if service.descricao == self.descricao
+                    && service.preco == self.preco
+                    && service.duracao_min == self.duracao_min
+                    && service.ativo == self.ativo
+                    && service.deleted_at.is_none() =>
+            {
+                Ok(RowAction::Unchanged)
+            }
+            Some(service) => {
+                diesel::update(services::table.find(service.id))
+                    .set((
+                        services::descricao.eq(&self.descricao),
+                        services::preco.eq(self.preco),
+                        services::duracao_min.eq(self.duracao_min),
+                        services::ativo.eq(self.ativo),
+                        services::deleted_at.eq(None::<NaiveDateTime>),  // Reimportar restaura um serviço excluído
+                    ))
+                    .execute(conn)?;
+                Ok(RowAction::Updated)
+            }
+        }
+    }
+
+    fn export(conn: &mut PgConnection) -> QueryResult<Vec<Self>> {
+        Ok(services::table
+            .filter(services::deleted_at.is_null())
+            .order(services::nome.asc())
+            .load::<Service>(conn)?
+            .into_iter()
+            .map(|service| ServiceRow {
+                nome: service.nome,
+                descricao: service.descricao,
+                preco: service.preco,
+                duracao_min: service.duracao_min,
+                ativo: service.ativo,
+            })
+            .collect())
+    }
+}
+
+/// 🔹 Cliente (chave: `phone`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ClientRow {
+    pub name: String,
+    pub phone: String,
+}
+
+impl BulkRow for ClientRow {
+    const COLUMNS: &'static [&'static str] = &["name", "phone"];
+    const REQUIRED: &'static [&'static str] = &["name", "phone"];
+
+    fn from_csv(record: &CsvRecord) -> Result<Self, String> {
+        Ok(ClientRow {
+            name: record.required("name")?.to_string(),
+            phone: record.required("phone")?.to_string(),
+        })
+    }
+
+    fn to_csv(&self) -> Vec<String> {
+        vec![self.name.clone(), self.phone.clone()]
+    }
+
+    fn validate(mut self) -> Result<Self, String> {
+        self.name = self.name.trim().to_string();
+        self.phone = self.phone.trim().to_string();
+        if self.name.is_empty() || self.phone.is_empty() {
+            return Err("`name` e `phone` são obrigatórios".to_string());
+        }
+        Ok(self)
+    }
+
+    fn key(&self) -> String {
+        self.phone.clone()
+    }
+
+    fn upsert(&self, conn: &mut PgConnection) -> QueryResult<RowAction> {
+        match find_user(conn, &self.phone)? {
+            None => {
+                create_user(conn, &self.name, &self.phone, "client")?;
+                Ok(RowAction::Created)
+            }
+            Some(user) if user.name == self.name && user.deleted_at.is_none() => Ok(RowAction::Unchanged),
+            Some(user) => {
+                diesel::update(users::table.find(user.id))
+                    .set((users::name.eq(&self.name), users::deleted_at.eq(None::<NaiveDateTime>)))
+                    .execute(conn)?;
+                Ok(RowAction::Updated)
+            }
+        }
+    }
+
+    fn export(conn: &mut PgConnection) -> QueryResult<Vec<Self>> {
+        Ok(users::table
+            .filter(users::role.eq("client"))
+            .filter(users::deleted_at.is_null())
+            .order(users::name.asc())
+            .select((users::name, users::phone))
+            .load::<(String, String)>(conn)?
+            .into_iter()
+            .map(|(name, phone)| ClientRow { name, phone })
+            .collect())
+    }
+}
+
+/// 🔹 Profissional (chave: `phone` do usuário; o usuário é criado se não existir)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProfessionalRow {
+    pub name: String,
+    pub phone: String,
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub specialties: Vec<String>,
+}
+
+impl BulkRow for ProfessionalRow {
+    const COLUMNS: &'static [&'static str] = &["name", "phone", "bio", "specialties"];
+    const REQUIRED: &'static [&'static str] = &["name", "phone"];
+
+    fn from_csv(record: &CsvRecord) -> Result<Self, String> {
+        Ok(ProfessionalRow {
+            name: record.required("name")?.to_string(),
+            phone: record.required("phone")?.to_string(),
+            bio: record.get("bio").map(str::to_string),
+            specialties: record
+                .get("specialties")
+                .map(|specialties| specialties.split(SPECIALTY_SEPARATOR).map(str::to_string).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    fn to_csv(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.phone.clone(),
+            self.bio.clone().unwrap_or_default(),
+            self.specialties.join(&SPECIALTY_SEPARATOR.to_string()),
+        ]
+    }
+
+    fn validate(mut self) -> Result<Self, String> {
+        self.name = self.name.trim().to_string();
+        self.phone = self.phone.trim().to_string();
+        self.bio = self.bio.map(|bio| bio.trim().to_string()).filter(|bio| !bio.is_empty());
+        self.specialties = self
+            .specialties
+            .iter()
+            .map(|specialty| specialty.trim().to_string())
+            .filter(|specialty| !specialty.is_empty())
+            .collect();
+        if self.name.is_empty() || self.phone.is_empty() {
+            return Err("`name` e `phone` são obrigatórios".to_string());
+        }
+        Ok(self)
+    }
+
+    fn key(&self) -> String {
+        self.phone.clone()
+    }
+
+    fn upsert(&self, conn: &mut PgConnection) -> QueryResult<RowAction> {
+        let specialties = (!self.specialties.is_empty()).then(|| self.specialties.iter().cloned().map(Some).collect::<Vec<_>>());
+        let mut action = RowAction::Unchanged;
+
+        let user = match find_user(conn, &self.phone)? {
+            None => {
+                action = RowAction::Created;
+                create_user(conn, &self.name, &self.phone, "professional")?
+            }
+            Some(user) => {
+                // Clientes promovidos a profissional; admins mantêm o papel
+                let role = if user.role == "client" { "professional" } else { user.role.as_str() };
+                if user.name != self.name || user.role != role || user.deleted_at.is_some() {
+                    action = RowAction::Updated;
+                    diesel::update(users::table.find(user.id))
+                        .set((users::name.eq(&self.name), users::role.eq(role), users::deleted_at.eq(None::<NaiveDateTime>)))
+                        .execute(conn)?;
+                }
+                user
+            }
+        };
+
+        let existing = professionals::table
+            .filter(professionals::user_id.eq(user.id))
+            .first::<Professional>(conn)
+            .optional()?;
+        match existing {
+            None => {
+                diesel::insert_into(professionals::table)
+                    .values((
+                        professionals::user_id.eq(user.id),
+                        professionals::bio.eq(&self.bio),
+                        professionals::specialties.eq(&specialties),
+                    ))
+                    .execute(conn)?;
+                Ok(RowAction::Created)
+            }
+            Some(professional)
+                if professional.bio != self.bio || professional.specialties != specialties || professional.deleted_at.is_some() =>
+            {
+                diesel::update(professionals::table.find(professional.id))
+                    .set((
+                        professionals::bio.eq(&self.bio),
+                        professionals::specialties.eq(&specialties),
+                        professionals::deleted_at.eq(None::<NaiveDateTime>),
+                    ))
+                    .execute(conn)?;
+                Ok(RowAction::Updated)
+            }
+            Some(_) => Ok(action),
+        }
+    }
+
+    fn export(conn: &mut PgConnection) -> QueryResult<Vec<Self>> {
+        Ok(professionals::table
+            .inner_join(users::table)
+            .filter(professionals::deleted_at.is_null())
+            .order(users::name.asc())
+            .select((users::name, users::phone, professionals::bio, professionals::specialties))
+            .load::<(String, String, Option<String>, Option<Vec<Option<String>>>)>(conn)?
+            .into_iter()
+            .map(|(name, phone, bio, specialties)| ProfessionalRow {
+                name,
+                phone,
+                bio,
+                specialties: specialties.unwrap_or_default().into_iter().flatten().collect(),
+            })
+            .collect())
+    }
+}
+
+fn find_user(conn: &mut PgConnection, phone: &str) -> QueryResult<Option<User>> {
+    users::table.filter(users::phone.eq(phone)).first::<User>(conn).optional()
+}
+
+/// 🔹 Cadastra o usuário e grava o evento `user.registered` (mesmo conteúdo do cadastro pela API)
+fn create_user(conn: &mut PgConnection, name: &str, phone: &str, role: &str) -> QueryResult<User> {
+    let user: User = diesel::insert_into(users::table)
+        .values(&NewUser {
+            name: name.to_string(),
+            phone: phone.to_string(),
+            password_hash: UNUSABLE_PASSWORD.to_string(),
+            role: role.to_string(),
+        })
+        .get_result(conn)?;
+    outbox::record(conn, "user.registered", serde_json::json!({
+        "id": user.id,
+        "name": user.name,
+        "phone": user.phone,
+        "role": user.role,
+        "created_at": user.created_at,
+    }))?;
+    Ok(user)
+}
+
+/// ✅ Importa um arquivo; `Err` quando o arquivo inteiro é inválido (JSON malformado, colunas faltando...)
+pub fn import(
+    conn: &mut PgConnection,
+    entity: BulkEntity,
+    format: BulkFormat,
+    body: &str,
+    options: ImportOptions,
+) -> Result<ImportReport, String> {
+    match entity {
+        BulkEntity::Services => import_rows(conn, entity, parse_rows::<ServiceRow>(format, body)?, options),
+        BulkEntity::Professionals => import_rows(conn, entity, parse_rows::<ProfessionalRow>(format, body)?, options),
+        BulkEntity::Clients => import_rows(conn, entity, parse_rows::<ClientRow>(format, body)?, options),
+    }
+}
+
+/// ✅ Exporta o cadastro no mesmo formato aceito pela importação
+pub fn export(conn: &mut PgConnection, entity: BulkEntity, format: BulkFormat) -> QueryResult<String> {
+    match entity {
+        BulkEntity::Services => Ok(render(&ServiceRow::export(conn)?, format)),
+        BulkEntity::Professionals => Ok(render(&ProfessionalRow::export(conn)?, format)),
+        BulkEntity::Clients => Ok(render(&ClientRow::export(conn)?, format)),
+    }
+}
+
+fn render<R: BulkRow>(rows: &[R], format: BulkFormat) -> String {
+    match format {
+        BulkFormat::Json => serde_json::to_string(rows).unwrap_or_else(|_| "[]".to_string()),
+        BulkFormat::Csv => {
+            let mut csv = write_csv_line(R::COLUMNS.iter().map(|column| column.to_string()));
+            for row in rows {
+                csv.push_str(&write_csv_line(row.to_csv()));
+            }
+            csv
+        }
+    }
+}
+
+/// 🔹 Converte o arquivo em linhas; erros de uma linha não impedem a leitura das demais
+fn parse_rows<R: BulkRow>(format: BulkFormat, body: &str) -> Result<Vec<Result<R, String>>, String> {
+    match format {
+        BulkFormat::Json => {
+            let values: Vec<serde_json::Value> =
+                serde_json::from_str(body).map_err(|e| format!("JSON inválido (esperado um array de objetos): {}", e))?;
+            Ok(values
+                .into_iter()
+                .map(|value| serde_json::from_value::<R>(value).map_err(|e| e.to_string()))
+                .collect())
+        }
+        BulkFormat::Csv => {
+            let mut records = parse_csv(body)?.into_iter();
+            let header = records.next().ok_or("CSV vazio")?;
+            let columns: HashMap<String, usize> = header
+                .iter()
+                .enumerate()
+                .map(|(index, column)| (column.trim().to_lowercase(), index))
+                .collect();
+            let missing: Vec<&str> = R::REQUIRED.iter().copied().filter(|column| !columns.contains_key(*column)).collect();
+            if !missing.is_empty() {
+                return Err(format!("Colunas obrigatórias ausentes no cabeçalho: {}", missing.join(", ")));
+            }
+
+            Ok(records
+                .filter(|values| values.iter().any(|value| !value.trim().is_empty()))
+                .map(|values| R::from_csv(&CsvRecord { columns: &columns, values: &values }))
+                .collect())
+        }
+    }
+}
+
+/// 🔹 Aplica as linhas numa transação; cada linha roda num savepoint para que uma falha não derrube as outras
+fn import_rows<R: BulkRow>(
+    conn: &mut PgConnection,
+    entity: BulkEntity,
+    rows: Vec<Result<R, String>>,
+    options: ImportOptions,
+) -> Result<ImportReport, String> {
+    let mut results = Vec::with_capacity(rows.len());
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    let outcome = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        for (index, parsed) in rows.into_iter().enumerate() {
+            let row = index + 1;
+            let validated = parsed.and_then(R::validate);
+            let result = match validated {
+                Err(error) => RowResult { row, key: None, action: RowAction::Failed, error: Some(error) },
+                Ok(record) => {
+                    let key = record.key();
+                    let applied = match seen.get(&key) {
+                        Some(first) => Err(format!("Chave repetida no arquivo (linha {})", first)),
+                        None => {
+                            seen.insert(key.clone(), row);
+                            conn.transaction(|conn| record.upsert(conn)).map_err(|e| e.to_string())
+                        }
+                    };
+                    match applied {
+                        Ok(action) => RowResult { row, key: Some(key), action, error: None },
+                        Err(error) => RowResult { row, key: Some(key), action: RowAction::Failed, error: Some(error) },
+                    }
+                }
+            };
+            results.push(result);
+        }
+
+        let failed = results.iter().any(|result| result.action == RowAction::Failed);
+        if options.dry_run || (options.atomic && failed) {
+            return Err(diesel::result::Error::RollbackTransaction);
+        }
+        Ok(())
+    });
+
+    let committed = match outcome {
+        Ok(()) => true,
+        Err(diesel::result::Error::RollbackTransaction) => false,
+        Err(e) => return Err(e.to_string()),
+    };
+    let count = |action: RowAction| results.iter().filter(|result| result.action == action).count();
+
+    Ok(ImportReport {
+        entity,
+        dry_run: options.dry_run,
+        atomic: options.atomic,
+        committed,
+        total: results.len(),
+        created: count(RowAction::Created),
+        updated: count(RowAction::Updated),
+        unchanged: count(RowAction::Unchanged),
+        failed: count(RowAction::Failed),
+        rows: results,
+    })
+}
+
+/// ✅ Lê um CSV (RFC 4180: aspas, aspas duplicadas e quebras de linha dentro de aspas).
+/// O separador é `,` ou `;` (planilhas em português), detectado pelo cabeçalho.
+pub fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let header = text.lines().next().unwrap_or_default();
+    let delimiter = if header.contains(';') && !header.contains(',') { ';' } else { ',' };
+
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err("CSV inválido: aspas não fechadas".to_string());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// 🔹 Linha CSV com aspas onde necessário
+fn write_csv_line(fields: impl IntoIterator<Item = String>) -> String {
+    let mut line = fields
+        .into_iter()
+        .map(|field| {
+            if field.contains([',', ';', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_round_trip_and_row_errors() {
+        let rows = vec![
+            ServiceRow { nome: "Corte, lavagem".to_string(), descricao: Some("Inclui \"escova\"\nfinal".to_string()), preco: 80.0, duracao_min: 60, ativo: true },
+            ServiceRow { nome: "Barba".to_string(), descricao: None, preco: 35.5, duracao_min: 30, ativo: false },
+        ];
+        let csv = render(&rows, BulkFormat::Csv);
+        let parsed = parse_rows::<ServiceRow>(BulkFormat::Csv, &csv).unwrap();
+        assert_eq!(parsed.len(), 2);
+        let first = parsed[0].as_ref().unwrap();
+        assert_eq!(first.nome, "Corte, lavagem");
+        assert_eq!(first.descricao.as_deref(), Some("Inclui \"escova\"\nfinal"));
+        assert!(!parsed[1].as_ref().unwrap().ativo);
+
+        // Planilha com `;`, linha com preço inválido e linha em branco
+        let semicolon = "\u{feff}nome;preco;duracao_min\r\nManicure;40;45\r\nPedicure;abc;45\r\n;;\r\n";
+        let parsed = parse_rows::<ServiceRow>(BulkFormat::Csv, semicolon).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].as_ref().unwrap().preco, 40.0);
+        assert!(parsed[1].as_ref().unwrap_err().contains("preco"));
+
+        assert!(parse_rows::<ServiceRow>(BulkFormat::Csv, "nome,preco\nCorte,10\n").is_err());
+        assert!(parse_csv("nome\n\"aberto\n").is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use chrono::{Duration as ChronoDuration, FixedOffset, NaiveDateTime, SubsecRound, Utc};
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::jobs::JobRegistry;
+use crate::models::busy_block::{CalendarSource, NewBusyBlock};
+use crate::schema::{appointments, busy_blocks, caldav_objects, calendar_sources, services};
+use crate::services::http_client;
+use crate::services::ical_service::{parse_busy_intervals, BusyInterval};
+use crate::shutdown::Shutdown;
+
+/// 🔹 Quantos dias à frente as recorrências são expandidas em cada importação
+pub const IMPORT_HORIZON_DAYS: i64 = 180;
+
+/// 🔹 Linhas por INSERT (limite de parâmetros do Postgres)
+const INSERT_CHUNK: usize = 1000;
+
+const SYNC_JOB: &str = "calendar_sync";
+
+/// 🔹 Tipos de origem (`calendar_sources.kind`)
+pub const SOURCE_URL: &str = "url";
+pub const SOURCE_UPLOAD: &str = "upload";
+pub const SOURCE_CALDAV: &str = "caldav";
+
+/// 🔹 Resultado de uma importação
+#[derive(Debug, Serialize)]
+pub struct ImportSummary {
+    pub events: usize,  // UIDs distintos no arquivo
+    pub blocks: usize,  // Ocorrências gravadas (criadas ou atualizadas)
+    pub removed: usize, // Blocos que sumiram do calendário de origem
+}
+
+/// ✅ Janela expandida nas importações: de ontem até `IMPORT_HORIZON_DAYS` à frente
+pub fn import_window(now: NaiveDateTime) -> (NaiveDateTime, NaiveDateTime) {
+    (now - ChronoDuration::days(1), now + ChronoDuration::days(IMPORT_HORIZON_DAYS))
+}
+
+/// ✅ Origem interna do profissional (`upload` ou `caldav`), criada no primeiro uso
+pub fn managed_source(conn: &mut PgConnection, professional_id: Uuid, kind: &str) -> QueryResult<CalendarSource> {
+    diesel::insert_into(calendar_sources::table)
+        .values((calendar_sources::professional_id.eq(professional_id), calendar_sources::kind.eq(kind)))
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+
+    calendar_sources::table
+        .filter(calendar_sources::professional_id.eq(professional_id))
+        .filter(calendar_sources::kind.eq(kind))
+        .first::<CalendarSource>(conn)
+}
+
+/// ✅ Recalcula os blocos da origem CalDAV a partir dos eventos gravados pelos clientes
+pub fn rebuild_caldav_blocks(
+    conn: &mut PgConnection,
+    professional_id: Uuid,
+    utc_offset: FixedOffset,
+    now: NaiveDateTime,
+) -> QueryResult<ImportSummary> {
+    let source = managed_source(conn, professional_id, SOURCE_CALDAV)?;
+    let objects = caldav_objects::table
+        .filter(caldav_objects::professional_id.eq(professional_id))
+        .select((caldav_objects::name, caldav_objects::ics_data))
+        .load::<(String, String)>(conn)?;
+
+    let (window_start, window_end) = import_window(now);
+    let mut intervals = Vec::new();
+    for (name, ics) in objects {
+        // Os objetos são validados no PUT; um arquivo inválido aqui não derruba os demais
+        match parse_busy_intervals(&ics, window_start, window_end, utc_offset) {
+            Ok(parsed) => intervals.extend(parsed),
+            Err(e) => warn!("⚠️ Evento CalDAV {} ignorado: {}", name, e),
+        }
+    }
+
+    replace_blocks(conn, &source, &intervals, now)
+}
+
+/// ✅ Substitui os blocos de uma origem pelos intervalos importados.
+/// Blocos são identificados por (UID, início): reimportar o mesmo arquivo não muda nada,
+/// eventos alterados são atualizados e eventos que sumiram do arquivo são removidos.
+pub fn replace_blocks(
+    conn: &mut PgConnection,
+    source: &CalendarSource,
+    intervals: &[BusyInterval],
+    now: NaiveDateTime,
+) -> QueryResult<ImportSummary> {
+    // O Postgres guarda microssegundos; truncar garante que a comparação abaixo case
+    let synced_at = now.trunc_subsecs(6);
+
+    let mut seen = HashSet::new();
+    let rows: Vec<NewBusyBlock> = intervals
+        .iter()
+        .filter(|interval| seen.insert((interval.uid.clone(), interval.start)))
+        .map(|interval| NewBusyBlock {
+            professional_id: source.professional_id,
+            source_id: source.id,
+            uid: interval.uid.clone(),
+            summary: interval.summary.clone(),
+            start_time: interval.start,
+            end_time: interval.end,
+            synced_at,
+        })
+        .collect();
+    let events = rows.iter().map(|row| row.uid.as_str()).collect::<HashSet<_>>().len();
+
+    conn.transaction(|conn| {
+        for chunk in rows.chunks(INSERT_CHUNK) {
+            diesel::insert_into(busy_blocks::table)
+                .values(chunk)
+                .on_conflict((busy_blocks::source_id, busy_blocks::uid, busy_blocks::start_time))
+                .do_update()
+                .set((
+                    busy_blocks::summary.eq(excluded(busy_blocks::summary)),
+                    busy_blocks::end_time.eq(excluded(busy_blocks::end_time)),
+                    busy_blocks::synced_at.eq(excluded(busy_blocks::synced_at)),
+                ))
+                .execute(conn)?;
+        }
+
+        let removed = diesel::delete(
+            busy_blocks::table
+                .filter(busy_blocks::source_id.eq(source.id))
+                .filter(busy_blocks::synced_at.ne(synced_at)),
+        )
+        .execute(conn)?;
+
+        Ok(ImportSummary { events, blocks: rows.len(), removed })
+    })
+}
+
+/// ✅ Verifica se o profissional tem algum bloco ocupado que cruze `[start, end)`
+pub fn has_overlap(conn: &mut PgConnection, professional_id: Uuid, start: NaiveDateTime, end: NaiveDateTime) -> QueryResult<bool> {
+    diesel::select(diesel::dsl::exists(
+        busy_blocks::table
+            .filter(busy_blocks::professional_id.eq(professional_id))
+            .filter(busy_blocks::start_time.lt(end))
+            .filter(busy_blocks::end_time.gt(start)),
+    ))
+    .get_result(conn)
+}
+
+/// ✅ Intervalos ocupados do profissional que cruzam `[start, end)`
+pub fn busy_between(
+    conn: &mut PgConnection,
+    professional_id: Uuid,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> QueryResult<Vec<(NaiveDateTime, NaiveDateTime)>> {
+    busy_blocks::table
+        .filter(busy_blocks::professional_id.eq(professional_id))
+        .filter(busy_blocks::start_time.lt(end))
+        .filter(busy_blocks::end_time.gt(start))
+        .order(busy_blocks::start_time.asc())
+        .select((busy_blocks::start_time, busy_blocks::end_time))
+        .load(conn)
+}
+
+/// ✅ Agendamentos ativos do profissional (não cancelados nem excluídos) que cruzam o período, com a duração do serviço
+pub fn booked_between(
+    conn: &mut PgConnection,
+    professional_id: Uuid,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> QueryResult<Vec<(NaiveDateTime, NaiveDateTime)>> {
+    // O fim depende da duração do serviço: a busca começa um dia antes e o corte final é feito aqui
+    let booked = appointments::table
+        .inner_join(services::table)
+        .filter(appointments::professional_id.eq(professional_id))
+        .filter(appointments::deleted_at.is_null())
+        .filter(appointments::status.ne("canceled"))
+        .filter(appointments::appointment_time.lt(end))
+        .filter(appointments::appointment_time.ge(start - ChronoDuration::days(1)))
+        .order(appointments::appointment_time.asc())
+        .select((appointments::appointment_time, services::duracao_min))
+        .load::<(NaiveDateTime, i32)>(conn)?;
+
+    Ok(booked
+        .into_iter()
+        .map(|(starts_at, duration)| (starts_at, starts_at + ChronoDuration::minutes(duration.into())))
+        .filter(|(_, ends_at)| *ends_at > start)
+        .collect())
+}
+
+/// ✅ Remove os intervalos ocupados das janelas livres
+pub fn subtract_intervals(
+    windows: &[(NaiveDateTime, NaiveDateTime)],
+    busy: &[(NaiveDateTime, NaiveDateTime)],
+) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    let mut free = windows.to_vec();
+
+    for (busy_start, busy_end) in busy {
+        free = free
+            .into_iter()
+            .flat_map(|(start, end)| {
+                if *busy_end <= start || *busy_start >= end {
+                    return vec![(start, end)];
+                }
+                [(start, *busy_start), (*busy_end, end)]
+                    .into_iter()
+                    .filter(|(piece_start, piece_end)| piece_start < piece_end)
+                    .collect()
+            })
+            .collect();
+    }
+
+    free
+}
+
+/// ✅ Relê uma origem (URL, ou os eventos CalDAV) e atualiza seus blocos e seu status.
+/// Reler também avança a janela das recorrências.
+pub async fn sync_source(pool: Arc<Pool>, source: CalendarSource, utc_offset: FixedOffset) -> Result<ImportSummary, String> {
+    let fetched = match (source.kind.as_str(), &source.location) {
+        (SOURCE_URL, Some(location)) => Some(read_location(source.id, location).await),
+        (SOURCE_CALDAV, _) => None,
+        _ => return Err("Uploads manuais não são relidos automaticamente".to_string()),
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get().map_err(|e| e.to_string())?;
+        let now = Utc::now().naive_utc();
+
+        let result = match fetched {
+            Some(fetched) => fetched.and_then(|ics| {
+                let (window_start, window_end) = import_window(now);
+                let intervals = parse_busy_intervals(&ics, window_start, window_end, utc_offset)?;
+                replace_blocks(&mut conn, &source, &intervals, now).map_err(|e| e.to_string())
+            }),
+            None => rebuild_caldav_blocks(&mut conn, source.professional_id, utc_offset, now).map_err(|e| e.to_string()),
+        };
+
+        let status = diesel::update(calendar_sources::table.find(source.id));
+        match &result {
+            Ok(_) => status
+                .set((calendar_sources::last_synced_at.eq(now), calendar_sources::last_error.eq(None::<String>)))
+                .execute(&mut conn),
+            Err(error) => status.set(calendar_sources::last_error.eq(error)).execute(&mut conn),
+        }
+        .map_err(|e| e.to_string())?;
+
+        result
+    })
+    .await
+    .map_err(|e| format!("Sincronização abortada: {}", e))?
+}
+
+/// 🔹 Conteúdo de uma URL http(s) pública.
+/// O erro gravado em `last_error` (visível ao profissional) é genérico; o detalhe fica só no log.
+async fn read_location(source_id: Uuid, location: &str) -> Result<String, String> {
+    if !(location.starts_with("http://") || location.starts_with("https://")) {
+        return Err("Origem sem URL http(s); cadastre o calendário novamente".to_string());
+    }
+
+    match http_client::get_public(location, "text/calendar").await {
+        Ok(response) if response.is_success() => Ok(response.body),
+        Ok(response) => Err(format!("O calendário respondeu HTTP {}", response.status)),
+        Err(e) => {
+            warn!("⚠️ Falha ao baixar o calendário {}: {}", source_id, e);
+            Err("Não foi possível baixar o calendário".to_string())
+        }
+    }
+}
+
+/// ✅ Worker que relê periodicamente os calendários configurados (URLs e CalDAV)
+pub fn spawn_calendar_sync(
+    pool: Arc<Pool>,
+    utc_offset: FixedOffset,
+    every: Duration,
+    jobs: JobRegistry,
+    shutdown: Shutdown,
+) -> tokio::task::JoinHandle<()> {
+    jobs.register(SYNC_JOB, every);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    sync_all(pool.clone(), utc_offset).await;
+                    jobs.heartbeat(SYNC_JOB);
+                }
+                _ = shutdown.wait() => break,
+            }
+        }
+
+        jobs.stopped(SYNC_JOB);
+    })
+}
+
+async fn sync_all(pool: Arc<Pool>, utc_offset: FixedOffset) {
+    let loader = pool.clone();
+    let sources = tokio::task::spawn_blocking(move || {
+        let mut conn = loader.get().map_err(|e| e.to_string())?;
+        calendar_sources::table
+            .filter(calendar_sources::kind.ne(SOURCE_UPLOAD))
+            .load::<CalendarSource>(&mut conn)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|sources| sources);
+
+    let sources = match sources {
+        Ok(sources) => sources,
+        Err(e) => {
+            warn!("⚠️ Falha ao carregar calendários externos: {}", e);
+            return;
+        }
+    };
+
+    for source in sources {
+        let source_id = source.id;
+        match sync_source(pool.clone(), source, utc_offset).await {
+            Ok(summary) => info!("📅 Calendário {} sincronizado: {} blocos, {} removidos", source_id, summary.blocks, summary.removed),
+            Err(e) => warn!("⚠️ Falha ao sincronizar calendário {}: {}", source_id, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_subtract_intervals() {
+        let at = |hour, minute| NaiveDate::from_ymd_opt(2025, 5, 10).unwrap().and_hms_opt(hour, minute, 0).unwrap();
+        let windows = [(at(8, 0), at(12, 0)), (at(14, 0), at(18, 0))];
+        let busy = [(at(9, 0), at(10, 30)), (at(11, 30), at(15, 0)), (at(17, 0), at(19, 0))];
+
+        assert_eq!(subtract_intervals(&windows, &busy), vec![
+            (at(8, 0), at(9, 0)),
+            (at(10, 30), at(11, 30)),
+            (at(15, 0), at(17, 0)),
+        ]);
+    }
+}
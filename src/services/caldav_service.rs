@@ -0,0 +1,149 @@
+use chrono::{Duration, FixedOffset, NaiveDate, NaiveDateTime};
+use once_cell::sync::Lazy;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use regex::Regex;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// 🔹 Caracteres mantidos literais nos nomes de recursos
+const RESOURCE_NAME: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+static HREF: Lazy<Regex> = Lazy::new(|| Regex::new(r"<(?:[A-Za-z0-9]+:)?href>\s*([^<\s]+)\s*</").unwrap());
+static TIME_RANGE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<(?:[A-Za-z0-9]+:)?time-range\b([^>]*)>").unwrap());
+static ATTRIBUTE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\b(start|end)\s*=\s*"([0-9TZ]+)""#).unwrap());
+
+/// 🔹 Uma entrada `<d:response>` do Multi-Status
+pub struct DavResponse {
+    pub href: String,
+    pub props: Vec<String>, // Propriedades já serializadas (ex: `<d:getetag>"1"</d:getetag>`)
+    pub found: bool,        // `false` gera `404 Not Found` (ex: href inexistente no multiget)
+}
+
+impl DavResponse {
+    pub fn found(href: impl Into<String>, props: Vec<String>) -> Self {
+        Self { href: href.into(), props, found: true }
+    }
+
+    pub fn missing(href: impl Into<String>) -> Self {
+        Self { href: href.into(), props: Vec::new(), found: false }
+    }
+}
+
+/// ✅ Corpo `207 Multi-Status` (RFC 4918) com os namespaces DAV, CalDAV e CalendarServer
+pub fn multistatus(responses: &[DavResponse]) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <d:multistatus xmlns:d=\"DAV:\" xmlns:c=\"urn:ietf:params:xml:ns:caldav\" xmlns:cs=\"http://calendarserver.org/ns/\">\n",
+    );
+
+    for response in responses {
+        xml.push_str(&format!("  <d:response>\n    <d:href>{}</d:href>\n", xml_escape(&response.href)));
+        if response.found {
+            xml.push_str("    <d:propstat>\n      <d:prop>\n");
+            for prop in &response.props {
+                xml.push_str(&format!("        {}\n", prop));
+            }
+            xml.push_str("      </d:prop>\n      <d:status>HTTP/1.1 200 OK</d:status>\n    </d:propstat>\n");
+        } else {
+            xml.push_str("    <d:status>HTTP/1.1 404 Not Found</d:status>\n");
+        }
+        xml.push_str("  </d:response>\n");
+    }
+
+    xml.push_str("</d:multistatus>\n");
+    xml
+}
+
+/// ✅ Escapa texto para conteúdo/atributos XML
+pub fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// ✅ Nome de recurso seguro para usar em URLs
+pub fn encode_resource_name(name: &str) -> String {
+    utf8_percent_encode(name, RESOURCE_NAME).to_string()
+}
+
+/// ✅ Último segmento (decodificado) de um href, ex: `/caldav/calendars/<id>/a%40b.ics` → `a@b.ics`
+pub fn resource_name_from_href(href: &str) -> Option<String> {
+    let segment = href.trim_end_matches('/').rsplit('/').next()?;
+    let name = percent_decode_str(segment).decode_utf8().ok()?.to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+/// ✅ Hrefs pedidos num REPORT `calendar-multiget`
+pub fn requested_hrefs(body: &str) -> Vec<String> {
+    HREF.captures_iter(body).map(|captures| captures[1].to_string()).collect()
+}
+
+/// ✅ Filtro `<c:time-range start=... end=...>` de um `calendar-query` (horários UTC → horário local)
+pub fn requested_time_range(body: &str, utc_offset: FixedOffset) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    let attributes = TIME_RANGE.captures(body)?.get(1)?.as_str().to_string();
+    let offset = Duration::seconds(utc_offset.local_minus_utc().into());
+
+    // Limites ausentes viram datas distantes (não `MIN`/`MAX`, que estouram na expansão de recorrências)
+    let mut start = NaiveDate::from_ymd_opt(1900, 1, 1)?.and_hms_opt(0, 0, 0)?;
+    let mut end = NaiveDate::from_ymd_opt(2200, 1, 1)?.and_hms_opt(0, 0, 0)?;
+    for captures in ATTRIBUTE.captures_iter(&attributes) {
+        let value = captures[2].trim_end_matches('Z');
+        let parsed = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()? + offset;
+        match &captures[1] {
+            "start" => start = parsed,
+            _ => end = parsed,
+        }
+    }
+
+    Some((start, end))
+}
+
+/// ✅ CTag da coleção: muda sempre que algum recurso (href/ETag) muda
+pub fn collection_tag<'a>(entries: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    let mut hasher = DefaultHasher::new();
+    for (href, etag) in entries {
+        href.hash(&mut hasher);
+        etag.hash(&mut hasher);
+    }
+    format!("{:x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_body_parsing() {
+        let multiget = r#"<?xml version="1.0"?>
+<C:calendar-multiget xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop><D:getetag/><C:calendar-data/></D:prop>
+  <D:href>/caldav/calendars/1/appointment-1.ics</D:href>
+  <D:href>/caldav/calendars/1/gym%40example.com.ics</D:href>
+</C:calendar-multiget>"#;
+        let hrefs = requested_hrefs(multiget);
+        assert_eq!(hrefs.len(), 2);
+        assert_eq!(resource_name_from_href(&hrefs[1]).as_deref(), Some("gym@example.com.ics"));
+        assert_eq!(encode_resource_name("gym@example.com.ics"), "gym%40example.com.ics");
+
+        let query = r#"<c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VEVENT">
+<c:time-range start="20250501T030000Z" end="20250601T030000Z"/></c:comp-filter></c:comp-filter></c:filter>"#;
+        let (start, end) = requested_time_range(query, FixedOffset::west_opt(3 * 3600).unwrap()).unwrap();
+        assert_eq!(start, NaiveDate::from_ymd_opt(2025, 5, 1).unwrap().and_hms_opt(0, 0, 0).unwrap());
+        assert_eq!(end, NaiveDate::from_ymd_opt(2025, 6, 1).unwrap().and_hms_opt(0, 0, 0).unwrap());
+        assert!(requested_time_range("<c:filter/>", FixedOffset::east_opt(0).unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_multistatus() {
+        let xml = multistatus(&[
+            DavResponse::found("/caldav/calendars/1/", vec!["<d:displayname>A &amp; B</d:displayname>".to_string()]),
+            DavResponse::missing("/caldav/calendars/1/x.ics"),
+        ]);
+
+        assert!(xml.contains("<d:href>/caldav/calendars/1/</d:href>"));
+        assert!(xml.contains("<d:displayname>A &amp; B</d:displayname>"));
+        assert!(xml.contains("<d:status>HTTP/1.1 404 Not Found</d:status>"));
+    }
+}
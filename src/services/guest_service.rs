@@ -0,0 +1,388 @@
+use async_trait::async_trait;
+use chrono::{Duration as ChronoDuration, FixedOffset, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, Rng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::models::background_job::BackgroundJob;
+use crate::models::guest_verification::{GuestVerification, NewGuestVerification};
+use crate::models::user::User;
+use crate::notifications::{templates, Channel, Message, Notifier};
+use crate::schema::{guest_verifications, users};
+use crate::services::job_queue::{self, JobHandler};
+use crate::services::outbox;
+use crate::telemetry;
+
+pub const GUEST_CODE_JOB: &str = "guest_verification_code";
+
+/// 🔹 Validade do código enviado por SMS
+pub const CODE_TTL_MINUTES: i64 = 10;
+
+/// 🔹 Códigos errados aceitos antes de o código ser invalidado
+const MAX_ATTEMPTS: i32 = 5;
+
+/// 🔹 Códigos pedidos por hora, por telefone e por IP
+const PHONE_LIMIT_PER_HOUR: i64 = 3;
+const IP_LIMIT_PER_HOUR: i64 = 10;
+
+/// 🔹 Códigos antigos são apagados depois disso (só servem para os limites por hora)
+const RETENTION_HOURS: i64 = 24;
+
+/// 🔹 O link de gerenciamento vale até 24h depois do horário do agendamento
+pub const LINK_GRACE_HOURS: i64 = 24;
+
+/// 🔹 Separa a assinatura do link de outras assinaturas feitas com o mesmo `SECRET_KEY`
+const TOKEN_PURPOSE: &str = "guest-booking";
+
+/// 🔹 Resultado do pedido de código
+pub enum Started {
+    Sent(GuestVerification),
+    PhoneLimited, // Muitos códigos para o mesmo telefone na última hora
+    IpLimited,    // Muitos códigos pedidos do mesmo IP na última hora
+}
+
+/// 🔹 Resultado da conferência do código
+#[derive(Debug, PartialEq, Eq)]
+pub enum Check {
+    Valid,
+    Invalid, // Código errado (conta uma tentativa) ou verificação de outro telefone
+    Expired, // Vencido, já usado ou com tentativas esgotadas
+}
+
+/// 🔹 Payload do job: só o destino e a verificação; o código é gerado no envio e nunca é gravado em texto puro
+#[derive(Debug, Serialize, Deserialize)]
+struct CodePayload {
+    to: String,
+    verification_id: Uuid,
+}
+
+/// 🔹 Conta usada no agendamento do convidado
+pub enum Guest {
+    Client(User),
+    Refused, // Telefone de alguém da equipe ou de uma conta excluída/anonimizada
+}
+
+/// ✅ Telefone só com dígitos (10 a 15, com DDI/DDD); `None` se não parecer um telefone
+pub fn normalize_phone(phone: &str) -> Option<String> {
+    let digits: String = phone.chars().filter(char::is_ascii_digit).collect();
+    (10..=15).contains(&digits.len()).then_some(digits)
+}
+
+/// ✅ Grafias com que o telefone pode estar cadastrado em `users.phone`
+pub fn phone_variants(phone: &str, digits: &str) -> [String; 3] {
+    [phone.trim().to_string(), digits.to_string(), format!("+{}", digits)]
+}
+
+/// ✅ Registra a verificação e enfileira o envio do código (gerado pelo job), respeitando os limites por telefone e por IP.
+/// `to` é o telefone como informado (destino do SMS); `phone` só os dígitos.
+pub fn start_verification(
+    conn: &mut PgConnection,
+    to: &str,
+    phone: &str,
+    ip: Option<String>,
+    now: NaiveDateTime,
+) -> QueryResult<Started> {
+    conn.transaction(|conn| {
+        // Pedidos simultâneos para o mesmo telefone não furam o limite
+        diesel::sql_query("SELECT pg_advisory_xact_lock(hashtext($1))").bind::<Text, _>(phone).execute(conn)?;
+        diesel::delete(guest_verifications::table.filter(guest_verifications::created_at.lt(now - ChronoDuration::hours(RETENTION_HOURS))))
+            .execute(conn)?;
+
+        let since = now - ChronoDuration::hours(1);
+        let by_phone = guest_verifications::table
+            .filter(guest_verifications::phone.eq(phone))
+            .filter(guest_verifications::created_at.ge(since))
+            .count()
+            .get_result::<i64>(conn)?;
+        if by_phone >= PHONE_LIMIT_PER_HOUR {
+            return Ok(Started::PhoneLimited);
+        }
+        if let Some(ip) = &ip {
+            let by_ip = guest_verifications::table
+                .filter(guest_verifications::ip.eq(ip))
+                .filter(guest_verifications::created_at.ge(since))
+                .count()
+                .get_result::<i64>(conn)?;
+            if by_ip >= IP_LIMIT_PER_HOUR {
+                return Ok(Started::IpLimited);
+            }
+        }
+
+        let expires_at = now + ChronoDuration::minutes(CODE_TTL_MINUTES);
+        let verification = diesel::insert_into(guest_verifications::table)
+            .values(&NewGuestVerification { phone: phone.to_string(), ip, expires_at })
+            .returning(GuestVerification::as_returning())
+            .get_result(conn)?;
+
+        let payload = CodePayload { to: to.trim().to_string(), verification_id: verification.id };
+        job_queue::enqueue(conn, GUEST_CODE_JOB, serde_json::json!(payload), Some(verification.id), now)?;
+        Ok(Started::Sent(verification))
+    })
+}
+
+/// ✅ Gera o código de 6 dígitos de uma verificação ainda válida e grava só o hash; `None` se já venceu ou foi usada.
+/// Uma nova tentativa de envio troca o código (só o último enviado vale).
+pub fn issue_code(conn: &mut PgConnection, verification_id: Uuid, now: NaiveDateTime) -> QueryResult<Option<String>> {
+    let code = format!("{:06}", OsRng.gen_range(0..1_000_000));
+    let issued = diesel::update(
+        guest_verifications::table
+            .find(verification_id)
+            .filter(guest_verifications::consumed_at.is_null())
+            .filter(guest_verifications::expires_at.gt(now)),
+    )
+    .set(guest_verifications::code_hash.eq(hash_code(&code)))
+    .execute(conn)?;
+    Ok((issued == 1).then_some(code))
+}
+
+/// ✅ Confere o código (sem consumi-lo). Cada erro conta uma tentativa e é gravado mesmo que o agendamento
+/// não aconteça; depois de `MAX_ATTEMPTS` erros o código deixa de valer.
+pub fn check_code(conn: &mut PgConnection, verification_id: Uuid, phone: &str, code: &str, now: NaiveDateTime) -> QueryResult<Check> {
+    conn.transaction(|conn| {
+        let Some(verification) = guest_verifications::table
+            .find(verification_id)
+            .select(GuestVerification::as_select())
+            .for_update()
+            .first(conn)
+            .optional()?
+        else {
+            return Ok(Check::Invalid);
+        };
+        if verification.phone != phone {
+            return Ok(Check::Invalid);
+        }
+        if verification.consumed_at.is_some() || verification.expires_at <= now || verification.attempts >= MAX_ATTEMPTS {
+            return Ok(Check::Expired);
+        }
+
+        if verification.code_hash.as_deref() == Some(hash_code(code.trim()).as_str()) {
+            return Ok(Check::Valid);
+        }
+        diesel::update(guest_verifications::table.find(verification_id))
+            .set(guest_verifications::attempts.eq(guest_verifications::attempts + 1))
+            .execute(conn)?;
+        Ok(Check::Invalid)
+    })
+}
+
+/// ✅ Marca o código como usado (na transação do agendamento); `false` se outra requisição já o usou
+pub fn consume(conn: &mut PgConnection, verification_id: Uuid, now: NaiveDateTime) -> QueryResult<bool> {
+    let consumed = diesel::update(
+        guest_verifications::table
+            .find(verification_id)
+            .filter(guest_verifications::consumed_at.is_null()),
+    )
+    .set(guest_verifications::consumed_at.eq(now))
+    .execute(conn)?;
+    Ok(consumed == 1)
+}
+
+/// ✅ Cliente já cadastrado com o telefone ou um novo cliente sem senha (convidado).
+/// O telefone foi verificado por SMS, então `sms_verified` passa a valer. Contas da equipe, excluídas ou
+/// anonimizadas com o mesmo telefone não são usadas nem alteradas (`Refused`).
+pub fn find_or_create_guest(conn: &mut PgConnection, name: &str, phone: &str, digits: &str) -> QueryResult<Guest> {
+    let existing = users::table
+        .filter(users::phone.eq_any(phone_variants(phone, digits)))
+        .order(users::created_at.asc())
+        .first::<User>(conn)
+        .optional()?;
+    if let Some(user) = existing {
+        if user.role != "client" || user.deleted_at.is_some() || user.anonymized_at.is_some() {
+            return Ok(Guest::Refused);
+        }
+        return diesel::update(users::table.find(user.id)).set(users::sms_verified.eq(true)).get_result(conn).map(Guest::Client);
+    }
+
+    let user = diesel::insert_into(users::table)
+        .values((
+            users::name.eq(name),
+            users::phone.eq(phone.trim()),
+            users::password_hash.eq("!"), // Não é um hash válido: o convidado não faz login com senha
+            users::role.eq("client"),
+            users::sms_verified.eq(true),
+        ))
+        .get_result::<User>(conn)?;
+    outbox::record(conn, "user.registered", serde_json::json!({
+        "id": user.id,
+        "name": user.name,
+        "phone": user.phone,
+        "role": user.role,
+        "created_at": user.created_at,
+        "guest": true,
+    }))?;
+    Ok(Guest::Client(user))
+}
+
+/// ✅ Fim da validade do link: `LINK_GRACE_HOURS` depois do horário (no fuso do salão) do agendamento
+pub fn link_expiry(appointment_time: NaiveDateTime, utc_offset: FixedOffset) -> NaiveDateTime {
+    appointment_time - ChronoDuration::seconds(utc_offset.local_minus_utc().into()) + ChronoDuration::hours(LINK_GRACE_HOURS)
+}
+
+/// ✅ Token do link de gerenciamento: `<agendamento>.<sequence>.<expiração unix>.<HMAC-SHA256>` assinado com o `SECRET_KEY`.
+/// O `sequence` do agendamento amarra o link à versão atual: depois de remarcar ou cancelar, o link antigo deixa de valer.
+pub fn sign_link(secret: &str, appointment_id: Uuid, sequence: i32, expires_at: NaiveDateTime) -> String {
+    let claims = format!("{}.{}.{}", appointment_id.simple(), sequence, expires_at.and_utc().timestamp());
+    let signature = to_hex(&link_mac(secret, &claims).finalize().into_bytes());
+    format!("{}.{}", claims, signature)
+}
+
+/// ✅ Agendamento e `sequence` do token, se a assinatura confere e o link ainda não venceu
+pub fn verify_link(secret: &str, token: &str, now: NaiveDateTime) -> Option<(Uuid, i32)> {
+    let (claims, signature) = token.rsplit_once('.')?;
+    let mut parts = claims.splitn(3, '.');
+    let (appointment_id, sequence, expires_at) = (parts.next()?, parts.next()?, parts.next()?);
+    link_mac(secret, claims).verify_slice(&from_hex(signature)?).ok()?;
+
+    let expires_at = expires_at.parse::<i64>().ok()?;
+    (now.and_utc().timestamp() < expires_at).then_some(())?;
+    Some((Uuid::parse_str(appointment_id).ok()?, sequence.parse().ok()?))
+}
+
+fn link_mac(secret: &str, claims: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC aceita chaves de qualquer tamanho");
+    mac.update(format!("{}:{}", TOKEN_PURPOSE, claims).as_bytes());
+    mac
+}
+
+fn hash_code(code: &str) -> String {
+    format!("{:x}", Sha256::digest(code.as_bytes()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok()).collect()
+}
+
+/// 🔹 Executor do envio dos códigos (SMS; WhatsApp se o SMS não estiver configurado)
+pub struct GuestCodeJob {
+    pool: Arc<Pool>,
+    notifiers: Vec<Arc<dyn Notifier>>,
+}
+
+impl GuestCodeJob {
+    pub fn new(pool: Arc<Pool>, notifiers: Vec<Arc<dyn Notifier>>) -> Self {
+        Self { pool, notifiers }
+    }
+}
+
+#[async_trait]
+impl JobHandler for GuestCodeJob {
+    fn kind(&self) -> &'static str {
+        GUEST_CODE_JOB
+    }
+
+    async fn run(&self, job: &BackgroundJob) -> Result<(), String> {
+        let payload: CodePayload = serde_json::from_value(job.payload.clone()).map_err(|e| e.to_string())?;
+        let notifier = [Channel::Sms, Channel::WhatsApp]
+            .iter()
+            .find_map(|channel| self.notifiers.iter().find(|notifier| notifier.channel() == *channel))
+            .ok_or_else(|| "Nenhum canal de SMS/WhatsApp configurado".to_string())?;
+
+        let pool = self.pool.clone();
+        let verification_id = payload.verification_id;
+        let code = tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get().map_err(|e| e.to_string())?;
+            issue_code(&mut conn, verification_id, Utc::now().naive_utc()).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        // Código vencido ou já usado antes de sair: o convidado pede outro
+        let Some(code) = code else {
+            return Ok(());
+        };
+
+        let rendered = templates::render_verification_code(templates::SUPPORTED_LOCALES[0], &code, CODE_TTL_MINUTES);
+        let result = notifier.send(&Message { to: payload.to, subject: rendered.subject, body: rendered.body }).await;
+
+        telemetry::notification_sent(notifier.channel().as_str(), result.is_ok());
+        result?;
+        info!("📨 Código de verificação enviado por {}", notifier.channel().as_str());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::background_jobs;
+    use crate::test_db::{self, fixtures};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_management_link_signature_and_expiry() {
+        let id = Uuid::new_v4();
+        let now = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        let token = sign_link("segredo", id, 2, now + ChronoDuration::hours(1));
+
+        assert_eq!(verify_link("segredo", &token, now), Some((id, 2)));
+        assert_eq!(verify_link("outro", &token, now), None);
+        assert_eq!(verify_link("segredo", &token, now + ChronoDuration::hours(2)), None);
+
+        // Trocar o agendamento, a versão ou a expiração invalida a assinatura
+        let forged = token.replacen(&id.simple().to_string(), &Uuid::new_v4().simple().to_string(), 1);
+        assert_eq!(verify_link("segredo", &forged, now), None);
+        let forged = token.replacen(&format!("{}.2.", id.simple()), &format!("{}.3.", id.simple()), 1);
+        assert_eq!(verify_link("segredo", &forged, now), None);
+        assert_eq!(normalize_phone("+55 (11) 99999-0000"), Some("5511999990000".to_string()));
+        assert_eq!(normalize_phone("12345"), None);
+    }
+
+    #[test]
+    fn test_code_never_persisted_and_staff_phone_refused() {
+        let Some(db) = test_db::setup() else { return };
+        let mut conn = db.pool.get().unwrap();
+        let now = Utc::now().naive_utc();
+
+        let Started::Sent(verification) = start_verification(&mut conn, "+55 11 90000-0030", "5511900000030", None, now).unwrap() else {
+            panic!("primeiro pedido não deveria ser limitado");
+        };
+        assert_eq!(verification.code_hash, None);
+        let payload = background_jobs::table
+            .filter(background_jobs::reference_id.eq(verification.id))
+            .select(background_jobs::payload)
+            .first::<serde_json::Value>(&mut conn)
+            .unwrap();
+        assert_eq!(payload, serde_json::json!({ "to": "+55 11 90000-0030", "verification_id": verification.id }));
+
+        // O job gera o código no envio; só o último enviado vale
+        let first = issue_code(&mut conn, verification.id, now).unwrap().unwrap();
+        let code = issue_code(&mut conn, verification.id, now).unwrap().unwrap();
+        if first != code {
+            assert_eq!(check_code(&mut conn, verification.id, "5511900000030", &first, now).unwrap(), Check::Invalid);
+        }
+        assert_eq!(check_code(&mut conn, verification.id, "5511900000030", &code, now).unwrap(), Check::Valid);
+        assert!(issue_code(&mut conn, verification.id, now + ChronoDuration::minutes(CODE_TTL_MINUTES)).unwrap().is_none());
+
+        fixtures::professional(&mut conn, "5511900000031");
+        assert!(matches!(
+            find_or_create_guest(&mut conn, "Intruso", "5511900000031", "5511900000031").unwrap(),
+            Guest::Refused
+        ));
+        let staff_verified = users::table
+            .filter(users::phone.eq("5511900000031"))
+            .select(users::sms_verified)
+            .first::<bool>(&mut conn)
+            .unwrap();
+        assert!(!staff_verified);
+
+        let client = fixtures::user(&mut conn, "client", "5511900000032");
+        let Guest::Client(user) = find_or_create_guest(&mut conn, "Ana", "+5511900000032", "5511900000032").unwrap() else {
+            panic!("cliente existente deveria ser usado");
+        };
+        assert_eq!(user.id, client);
+        assert!(user.sms_verified);
+    }
+}
@@ -0,0 +1,217 @@
+use diesel::prelude::*;
+use diesel_migrations::MigrationHarness;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use chrono::Utc;
+
+use crate::db::{Pool, MIGRATIONS};
+use crate::jobs::{JobRegistry, JobState, JobStatus};
+use crate::shutdown::Shutdown;
+
+/// 🔹 Tempo máximo de cada verificação do readiness
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 🔹 Resultado de uma verificação individual
+#[derive(Debug, Serialize)]
+pub struct CheckResult {
+    pub status: &'static str, // "ok" ou "fail"
+    pub critical: bool,
+    pub latency_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<BTreeMap<String, JobStatus>>,
+}
+
+impl CheckResult {
+    fn from_result(result: Result<(), String>, critical: bool, started_at: Instant) -> Self {
+        Self {
+            status: if result.is_ok() { "ok" } else { "fail" },
+            critical,
+            latency_ms: started_at.elapsed().as_millis(),
+            error: result.err(),
+            jobs: None,
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.status == "ok"
+    }
+}
+
+/// 🔹 Resposta do `/health/ready`
+#[derive(Debug, Serialize)]
+pub struct ReadinessReport {
+    pub status: &'static str, // "ok" ou "fail"
+    pub checks: BTreeMap<&'static str, CheckResult>,
+}
+
+impl ReadinessReport {
+    /// ✅ Falha se qualquer verificação crítica falhar
+    pub fn from_checks(checks: BTreeMap<&'static str, CheckResult>) -> Self {
+        let healthy = checks.values().all(|check| check.is_ok() || !check.critical);
+        Self {
+            status: if healthy { "ok" } else { "fail" },
+            checks,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.status == "ok"
+    }
+}
+
+/// ✅ Obtém uma conexão do pool (com timeout curto) e executa `SELECT 1`
+fn check_database(pool: &Pool) -> Result<(), String> {
+    let mut conn = pool
+        .get_timeout(CHECK_TIMEOUT)
+        .map_err(|e| format!("Falha ao obter conexão: {}", e))?;
+
+    diesel::sql_query("SELECT 1")
+        .execute(&mut conn)
+        .map(|_| ())
+        .map_err(|e| format!("Falha ao consultar o banco: {}", e))
+}
+
+/// ✅ Verifica se todas as migrações embutidas já foram aplicadas
+fn check_migrations(pool: &Pool) -> Result<(), String> {
+    let mut conn = pool
+        .get_timeout(CHECK_TIMEOUT)
+        .map_err(|e| format!("Falha ao obter conexão: {}", e))?;
+
+    match conn.has_pending_migration(MIGRATIONS) {
+        Ok(false) => Ok(()),
+        Ok(true) => Err("Existem migrações pendentes".to_string()),
+        Err(e) => Err(format!("Falha ao verificar migrações: {}", e)),
+    }
+}
+
+/// ✅ Resume o status dos workers em background (não crítico)
+fn check_background_jobs(jobs: &JobRegistry) -> CheckResult {
+    let started_at = Instant::now();
+    let snapshot = jobs.snapshot();
+    let now = Utc::now().naive_utc();
+
+    let unhealthy: Vec<&str> = snapshot
+        .iter()
+        .filter(|(_, status)| status.state == JobState::Failed || status.is_stale(now))
+        .map(|(name, _)| name.as_str())
+        .collect();
+
+    let result = if unhealthy.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Workers com problema: {}", unhealthy.join(", ")))
+    };
+
+    let mut check = CheckResult::from_result(result, false, started_at);
+    check.jobs = Some(snapshot);
+    check
+}
+
+/// 🔹 Executa uma verificação bloqueante fora do runtime, respeitando o timeout
+async fn run_blocking_check(
+    pool: Arc<Pool>,
+    check: fn(&Pool) -> Result<(), String>,
+) -> CheckResult {
+    let started_at = Instant::now();
+    let task = tokio::task::spawn_blocking(move || check(&pool));
+
+    let result = match tokio::time::timeout(CHECK_TIMEOUT, task).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => Err(format!("Verificação abortada: {}", e)),
+        Err(_) => Err(format!("Timeout após {}ms", CHECK_TIMEOUT.as_millis())),
+    };
+
+    CheckResult::from_result(result, true, started_at)
+}
+
+/// ✅ Falha assim que o encerramento começa, para o balanceador parar de enviar tráfego
+fn check_shutdown(shutdown: &Shutdown) -> CheckResult {
+    let result = if shutdown.is_shutting_down() {
+        Err("Servidor em encerramento".to_string())
+    } else {
+        Ok(())
+    };
+
+    CheckResult::from_result(result, true, Instant::now())
+}
+
+/// ✅ Executa todas as verificações do readiness
+pub async fn readiness(pool: Arc<Pool>, jobs: &JobRegistry, shutdown: &Shutdown) -> ReadinessReport {
+    // Durante o encerramento não vale a pena ocupar conexões do pool
+    if shutdown.is_shutting_down() {
+        let mut checks = BTreeMap::new();
+        checks.insert("shutdown", check_shutdown(shutdown));
+        return ReadinessReport::from_checks(checks);
+    }
+
+    let (database, migrations) = tokio::join!(
+        run_blocking_check(pool.clone(), check_database),
+        run_blocking_check(pool, check_migrations),
+    );
+
+    let mut checks = BTreeMap::new();
+    checks.insert("database", database);
+    checks.insert("migrations", migrations);
+    checks.insert("background_jobs", check_background_jobs(jobs));
+    checks.insert("shutdown", check_shutdown(shutdown));
+
+    ReadinessReport::from_checks(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(ok: bool, critical: bool) -> CheckResult {
+        let result = if ok { Ok(()) } else { Err("erro".to_string()) };
+        CheckResult::from_result(result, critical, Instant::now())
+    }
+
+    #[test]
+    fn test_readiness_only_fails_on_critical_checks() {
+        let mut checks = BTreeMap::new();
+        checks.insert("database", check(true, true));
+        checks.insert("background_jobs", check(false, false));
+        assert!(ReadinessReport::from_checks(checks).is_ready());
+
+        let mut checks = BTreeMap::new();
+        checks.insert("database", check(false, true));
+        checks.insert("background_jobs", check(true, false));
+        assert!(!ReadinessReport::from_checks(checks).is_ready());
+    }
+
+    #[test]
+    fn test_background_job_is_stale_after_missed_heartbeats() {
+        let jobs = JobRegistry::new();
+        jobs.register("metrics_upkeep", Duration::from_secs(5));
+        jobs.heartbeat("metrics_upkeep");
+        assert!(check_background_jobs(&jobs).is_ok());
+
+        let status = JobStatus {
+            state: JobState::Running,
+            last_heartbeat: Utc::now().naive_utc() - chrono::Duration::seconds(16),
+            last_error: None,
+            interval: Duration::from_secs(5),
+        };
+        assert!(status.is_stale(Utc::now().naive_utc()));
+    }
+
+    #[test]
+    fn test_shutdown_fails_readiness() {
+        let shutdown = Shutdown::new();
+        assert!(check_shutdown(&shutdown).is_ok());
+
+        // A fase de espera (antes de recusar conexões) já derruba o readiness
+        shutdown.start_draining();
+        assert!(!check_shutdown(&shutdown).is_ok());
+
+        shutdown.trigger();
+        let mut checks = BTreeMap::new();
+        checks.insert("shutdown", check_shutdown(&shutdown));
+        assert!(!ReadinessReport::from_checks(checks).is_ready());
+    }
+}
@@ -0,0 +1,234 @@
+use axum::body::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::{header, Method, Request, Uri};
+use hyper_util::rt::TokioIo;
+use once_cell::sync::Lazy;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{lookup_host, TcpStream};
+use tokio_rustls::rustls::{self, pki_types::ServerName, ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+/// 🔹 Tempo máximo de uma chamada HTTP de saída (conexão + resposta)
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 🔹 Resposta de uma chamada HTTP de saída
+#[derive(Debug)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// 🔹 TLS das URLs `https://`: rustls com as autoridades certificadoras do Mozilla (webpki-roots), sem depender do OpenSSL do sistema
+static TLS: Lazy<TlsConnector> = Lazy::new(|| {
+    let roots = RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let config = ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .expect("versões de TLS padrão do rustls")
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    TlsConnector::from(Arc::new(config))
+});
+
+/// 🔹 Destinos aceitos numa chamada
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Destination {
+    Any,        // Endereços configurados pelo operador (gateway de SMS, WhatsApp...)
+    PublicOnly, // URLs informadas por usuários: nada de loopback, rede privada ou link-local (SSRF)
+}
+
+/// ✅ GET (HTTP/1.1, http ou https) de uma URL informada por usuário: recusa hosts que resolvem para endereços internos
+/// (o endereço verificado é o mesmo usado na conexão, sem nova resolução de DNS)
+pub async fn get_public(url: &str, accept: &str) -> Result<HttpResponse, String> {
+    send(Method::GET, url, &[(header::ACCEPT.as_str(), accept.to_string())], Bytes::new(), Destination::PublicOnly).await
+}
+
+/// ✅ Valida uma URL informada por usuário antes de gravá-la (http/https, host com endereço público)
+pub async fn check_public_url(url: &str) -> Result<(), String> {
+    let uri: Uri = url.parse().map_err(|_| "URL inválida".to_string())?;
+    if !matches!(uri.scheme_str(), Some("http") | Some("https")) {
+        return Err("A URL deve começar com http:// ou https://".to_string());
+    }
+    resolve(&uri, Destination::PublicOnly).await.map(|_| ())
+}
+
+/// 🔹 Endereço roteável na internet (fora de loopback, redes privadas, link-local, CGNAT, multicast...)
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)) // 100.64.0.0/10 (CGNAT)
+                || (a == 198 && (b == 18 || b == 19)) // 198.18.0.0/15 (benchmark)
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_ip(IpAddr::V4(mapped)),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_unspecified()
+                    || ip.is_loopback()
+                    || ip.is_multicast()
+                    || (first & 0xfe00) == 0xfc00 // fc00::/7 (ULA)
+                    || (first & 0xffc0) == 0xfe80 // fe80::/10 (link-local)
+                    || first == 0x2001 && ip.segments()[1] == 0x0db8) // documentação
+            }
+        },
+    }
+}
+
+/// 🔹 Resolve o host da URL; com `PublicOnly`, qualquer endereço interno recusa a URL inteira
+async fn resolve(uri: &Uri, destination: Destination) -> Result<SocketAddr, String> {
+    let host = uri.host().ok_or_else(|| "URL sem host".to_string())?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let default_port = if uri.scheme_str() == Some("https") { 443 } else { 80 };
+    let port = uri.port_u16().unwrap_or(default_port);
+
+    let addresses: Vec<SocketAddr> = lookup_host((host, port))
+        .await
+        .map_err(|_| format!("Host {} não encontrado", host))?
+        .collect();
+    if destination == Destination::PublicOnly && addresses.iter().any(|address| !is_public_ip(address.ip())) {
+        return Err("A URL aponta para um endereço interno".to_string());
+    }
+    addresses.into_iter().next().ok_or_else(|| format!("Host {} não encontrado", host))
+}
+
+/// ✅ POST com corpo JSON e cabeçalhos extras (ex: assinatura, token de API)
+pub async fn post_json(url: &str, headers: &[(&str, String)], body: String) -> Result<HttpResponse, String> {
+    let mut all_headers = vec![(header::CONTENT_TYPE.as_str(), "application/json".to_string())];
+    all_headers.extend(headers.iter().cloned());
+    send(Method::POST, url, &all_headers, Bytes::from(body), Destination::Any).await
+}
+
+async fn send(method: Method, url: &str, headers: &[(&str, String)], body: Bytes, destination: Destination) -> Result<HttpResponse, String> {
+    tokio::time::timeout(REQUEST_TIMEOUT, send_inner(method, url, headers, body, destination))
+        .await
+        .map_err(|_| format!("Timeout ao chamar {}", url))?
+}
+
+async fn send_inner(
+    method: Method,
+    url: &str,
+    headers: &[(&str, String)],
+    body: Bytes,
+    destination: Destination,
+) -> Result<HttpResponse, String> {
+    let uri: Uri = url.parse().map_err(|e| format!("URL inválida: {}", e))?;
+    let host = uri.host().ok_or_else(|| "URL sem host".to_string())?.to_string();
+    let path = uri.path_and_query().map_or("/", |path| path.as_str()).to_string();
+    let host_header = match uri.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.clone(),
+    };
+
+    let mut request = Request::builder().method(method).uri(path).header(header::HOST, host_header);
+    for (name, value) in headers {
+        request = request.header(*name, value);
+    }
+    let request = request.body(Full::new(body)).map_err(|e| e.to_string())?;
+
+    let address = resolve(&uri, destination).await?;
+    let stream = TcpStream::connect(address)
+        .await
+        .map_err(|e| format!("Falha ao conectar em {}: {}", host, e))?;
+    match uri.scheme_str() {
+        Some("https") => {
+            // Certificado validado contra o nome da URL (SNI), mesmo conectando no endereço já resolvido
+            let server_name = ServerName::try_from(host.trim_start_matches('[').trim_end_matches(']').to_string())
+                .map_err(|_| format!("Host {} inválido para TLS", host))?;
+            let stream = TLS
+                .connect(server_name, stream)
+                .await
+                .map_err(|e| format!("Falha no TLS com {}: {}", host, e))?;
+            exchange(stream, request).await
+        }
+        Some("http") => exchange(stream, request).await,
+        _ => Err("A URL deve começar com http:// ou https://".to_string()),
+    }
+}
+
+/// 🔹 Envia a requisição numa conexão HTTP/1.1 já aberta (TCP puro ou TLS) e lê a resposta inteira
+async fn exchange<S>(stream: S, request: Request<Full<Bytes>>) -> Result<HttpResponse, String>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::spawn(connection);
+
+    let response = sender.send_request(request).await.map_err(|e| e.to_string())?;
+    let status = response.status().as_u16();
+    let body = response.into_body().collect().await.map_err(|e| e.to_string())?.to_bytes();
+
+    Ok(HttpResponse {
+        status,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public_ip() {
+        for internal in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.0.10", "169.254.169.254", "100.64.0.1", "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:127.0.0.1"] {
+            assert!(!is_public_ip(internal.parse().unwrap()), "{}", internal);
+        }
+        for public in ["8.8.8.8", "200.147.67.142", "2606:4700:4700::1111"] {
+            assert!(is_public_ip(public.parse().unwrap()), "{}", public);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_user_urls_cannot_reach_files_or_internal_hosts() {
+        for url in ["/etc/passwd", "file:///etc/passwd", ".env", "ftp://example.com/a.ics"] {
+            assert!(check_public_url(url).await.is_err(), "{}", url);
+        }
+        for url in ["http://127.0.0.1:5432/", "http://localhost/agenda.ics", "http://[::1]/", "http://169.254.169.254/latest/meta-data"] {
+            assert_eq!(check_public_url(url).await.unwrap_err(), "A URL aponta para um endereço interno", "{}", url);
+        }
+
+        // Verificado também na conexão (o cadastro pode ser anterior à regra ou o DNS pode ter mudado)
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/agenda.ics", listener.local_addr().unwrap());
+        assert_eq!(get_public(&url, "text/calendar").await.unwrap_err(), "A URL aponta para um endereço interno");
+    }
+
+    #[tokio::test]
+    async fn test_https_urls_negotiate_tls() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Servidor que só lê o primeiro registro: um cliente com TLS abre com um ClientHello (tipo 0x16), não com texto HTTP
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("https://localhost:{}/hooks", listener.local_addr().unwrap().port());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut first = [0u8; 1];
+            socket.read_exact(&mut first).await.unwrap();
+            socket.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n").await.unwrap();
+            first[0]
+        });
+
+        let error = post_json(&url, &[], "{}".to_string()).await.unwrap_err();
+        assert!(error.starts_with("Falha no TLS com localhost"), "{}", error);
+        assert_eq!(server.await.unwrap(), 0x16);
+    }
+}
@@ -0,0 +1,709 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, Weekday};
+use uuid::Uuid;
+
+use crate::models::appointment::Appointment;
+use crate::models::reservation::Reservation;
+use crate::models::service::Service;
+
+/// 🔹 Domínio usado nos UIDs dos eventos (estáveis entre exportações)
+const UID_DOMAIN: &str = "scheduling";
+
+/// 🔹 Quantos dias de histórico entram nos feeds/CalDAV (eventos futuros entram todos)
+pub const FEED_HISTORY_DAYS: i64 = 90;
+
+/// 🔹 Duração assumida para reservas, que não têm serviço cadastrado (mesmo slot do calendário)
+const RESERVATION_DURATION_MIN: i64 = 30;
+
+/// 🔹 Evento (VEVENT) de um feed iCalendar
+#[derive(Debug)]
+pub struct IcsEvent {
+    pub uid: String,
+    pub sequence: i32,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub summary: String,
+    pub description: Option<String>,
+    pub status: &'static str,
+}
+
+impl IcsEvent {
+    /// ✅ Evento a partir de um agendamento e do serviço agendado
+    pub fn from_appointment(appointment: &Appointment, service: &Service) -> Self {
+        Self {
+            uid: event_uid("appointment", appointment.id),
+            sequence: appointment.sequence,
+            start: appointment.appointment_time,
+            end: appointment.appointment_time + Duration::minutes(service.duracao_min.into()),
+            summary: service.nome.clone(),
+            description: service.descricao.clone(),
+            status: event_status(&appointment.status),
+        }
+    }
+
+    /// ✅ Evento a partir de uma reserva
+    pub fn from_reservation(reservation: &Reservation) -> Self {
+        Self {
+            uid: event_uid("reservation", reservation.id),
+            sequence: reservation.sequence,
+            start: reservation.appointment_time,
+            end: reservation.appointment_time + Duration::minutes(RESERVATION_DURATION_MIN),
+            summary: reservation.service.clone(),
+            description: None,
+            status: event_status(&reservation.status),
+        }
+    }
+}
+
+/// 🔹 UID estável: o mesmo registro gera sempre o mesmo UID
+fn event_uid(kind: &str, entity_id: Uuid) -> String {
+    format!("{}-{}@{}", kind, entity_id, UID_DOMAIN)
+}
+
+/// 🔹 Converte o status interno para o STATUS do RFC 5545
+fn event_status(status: &str) -> &'static str {
+    match status {
+        "canceled" => "CANCELLED",
+        "confirmed" | "completed" | "no_show" => "CONFIRMED",
+        _ => "TENTATIVE",
+    }
+}
+
+/// ✅ Gera o documento VCALENDAR (RFC 5545) com os eventos informados
+pub fn render_calendar(name: &str, events: &[IcsEvent], now: NaiveDateTime) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//scheduling//calendar feed//PT".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(name)),
+    ];
+
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}Z", format_datetime(now)));
+        lines.push(format!("DTSTART:{}", format_datetime(event.start)));
+        lines.push(format!("DTEND:{}", format_datetime(event.end)));
+        lines.push(format!("SEQUENCE:{}", event.sequence));
+        lines.push(format!("STATUS:{}", event.status));
+        lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+        if let Some(description) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line)).collect()
+}
+
+/// 🔹 Data/hora no formato básico do iCalendar (horário local, sem fuso)
+fn format_datetime(value: NaiveDateTime) -> String {
+    value.format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// 🔹 Escapa caracteres especiais de valores TEXT (RFC 5545, 3.3.11)
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// 🔹 Quebra linhas com mais de 75 octetos e termina com CRLF (RFC 5545, 3.1)
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut octets = 0;
+
+    for ch in line.chars() {
+        // Linhas de continuação começam com um espaço, que também conta no limite
+        if octets + ch.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(ch);
+        octets += ch.len_utf8();
+    }
+
+    folded.push_str("\r\n");
+    folded
+}
+
+/// 🔹 Limite de ocorrências guardadas por evento recorrente (proteção contra RRULEs sem fim)
+const MAX_OCCURRENCES: usize = 1000;
+
+/// 🔹 Limite de períodos percorridos ao expandir uma RRULE
+const MAX_RECURRENCE_PERIODS: i64 = 100_000;
+
+/// 🔹 Ocorrência ocupada vinda de um calendário importado (RRULE já expandida)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BusyInterval {
+    pub uid: String,
+    pub summary: Option<String>,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+/// 🔹 VEVENT lido do arquivo, antes da expansão
+#[derive(Debug, Default)]
+struct ParsedEvent {
+    uid: Option<String>,
+    summary: Option<String>,
+    start: Option<NaiveDateTime>,
+    all_day: bool,
+    end: Option<NaiveDateTime>,
+    duration: Option<Duration>,
+    rrule: Option<RecurrenceRule>,
+    exdates: Vec<NaiveDateTime>,
+    recurrence_id: Option<NaiveDateTime>,
+    cancelled: bool,
+    transparent: bool,
+}
+
+impl ParsedEvent {
+    fn length(&self, start: NaiveDateTime) -> Duration {
+        match (self.end, self.duration) {
+            (Some(end), _) => end - start,
+            (None, Some(duration)) => duration,
+            (None, None) if self.all_day => Duration::days(1),
+            (None, None) => Duration::zero(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// 🔹 Subconjunto suportado da RRULE: FREQ, INTERVAL, COUNT, UNTIL, BYDAY e BYMONTHDAY
+#[derive(Debug)]
+struct RecurrenceRule {
+    frequency: Frequency,
+    interval: i64,
+    count: Option<usize>,
+    until: Option<NaiveDateTime>,
+    by_day: Vec<(Option<i64>, Weekday)>,
+    by_month_day: Vec<i64>,
+}
+
+/// ✅ Lê um arquivo .ics e devolve as ocorrências ocupadas que tocam a janela informada.
+/// Horários em UTC (sufixo `Z`) são convertidos com `utc_offset`; horários com TZID são
+/// tratados como horário local do salão.
+pub fn parse_busy_intervals(
+    ics: &str,
+    window_start: NaiveDateTime,
+    window_end: NaiveDateTime,
+    utc_offset: FixedOffset,
+) -> Result<Vec<BusyInterval>, String> {
+    let lines = unfold_lines(ics);
+    if !lines.iter().any(|line| line.eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err("Arquivo .ics inválido: VCALENDAR não encontrado".to_string());
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<ParsedEvent> = None;
+    let mut nested = 0; // Componentes dentro do VEVENT (ex: VALARM)
+
+    for line in &lines {
+        let Some(Property { name, params, value }) = parse_property(line) else {
+            continue;
+        };
+
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => current = Some(ParsedEvent::default()),
+            ("BEGIN", Some(_)) => nested += 1,
+            ("END", Some(_)) if nested > 0 => nested -= 1,
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => events.extend(current.take()),
+            (_, Some(event)) if nested == 0 => apply_property(event, &name, &params, value, utc_offset),
+            _ => {}
+        }
+    }
+
+    // Ocorrências alteradas individualmente (RECURRENCE-ID) substituem as da série
+    let mut overridden: HashMap<String, Vec<NaiveDateTime>> = HashMap::new();
+    for event in &events {
+        if let (Some(uid), Some(recurrence_id)) = (&event.uid, event.recurrence_id) {
+            overridden.entry(uid.clone()).or_default().push(recurrence_id);
+        }
+    }
+
+    let mut intervals = Vec::new();
+    for event in &events {
+        let (Some(uid), Some(start)) = (&event.uid, event.start) else {
+            continue;
+        };
+        if event.cancelled || event.transparent {
+            continue;
+        }
+
+        let length = event.length(start);
+        let starts = match (&event.rrule, event.recurrence_id) {
+            (Some(rule), None) => expand_rule(rule, start, window_start - length, window_end),
+            _ => vec![start],
+        };
+
+        let skipped = overridden.get(uid.as_str()).filter(|_| event.recurrence_id.is_none());
+        for occurrence in starts {
+            if event.exdates.contains(&occurrence) || skipped.is_some_and(|dates| dates.contains(&occurrence)) {
+                continue;
+            }
+
+            let end = occurrence + length;
+            if end > occurrence && end > window_start && occurrence < window_end {
+                intervals.push(BusyInterval {
+                    uid: uid.clone(),
+                    summary: event.summary.clone(),
+                    start: occurrence,
+                    end,
+                });
+            }
+        }
+    }
+
+    intervals.sort_by_key(|interval| interval.start);
+    Ok(intervals)
+}
+
+/// ✅ UID do primeiro VEVENT do arquivo
+pub fn first_event_uid(ics: &str) -> Option<String> {
+    let mut in_event = false;
+
+    for line in unfold_lines(ics) {
+        let Some(property) = parse_property(&line) else {
+            continue;
+        };
+        match property.name.as_str() {
+            "BEGIN" if property.value.eq_ignore_ascii_case("VEVENT") => in_event = true,
+            "UID" if in_event => return Some(property.value.trim().to_string()),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// ✅ Indica se o UID pertence a um evento gerado por esta API (agendamentos e reservas)
+pub fn is_generated_uid(uid: &str) -> bool {
+    uid.ends_with(&format!("@{}", UID_DOMAIN))
+}
+
+/// 🔹 Desfaz a quebra de linhas (RFC 5545, 3.1)
+fn unfold_lines(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for raw in ics.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        if let (Some(rest), Some(last)) = (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            last.push_str(rest);
+        } else if !raw.is_empty() {
+            lines.push(raw.to_string());
+        }
+    }
+
+    lines
+}
+
+/// 🔹 Linha de conteúdo `NOME;PARAM=valor:VALOR`
+struct Property<'a> {
+    name: String,
+    params: Vec<(String, String)>,
+    value: &'a str,
+}
+
+/// 🔹 Separa nome, parâmetros e valor (os dois-pontos podem aparecer entre aspas nos parâmetros)
+fn parse_property(line: &str) -> Option<Property<'_>> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(index, ch)| match ch {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(index),
+        _ => None,
+    })?;
+
+    let mut head = line[..colon].split(';');
+    let name = head.next()?.trim().to_ascii_uppercase();
+    let params = head
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| (key.to_ascii_uppercase(), value.trim_matches('"').to_string()))
+        .collect();
+
+    Some(Property { name, params, value: &line[colon + 1..] })
+}
+
+fn apply_property(event: &mut ParsedEvent, name: &str, params: &[(String, String)], value: &str, utc_offset: FixedOffset) {
+    match name {
+        "UID" => event.uid = Some(value.trim().to_string()),
+        "SUMMARY" => event.summary = Some(unescape_text(value)),
+        "DTSTART" => {
+            if let Some((start, all_day)) = parse_datetime(value, params, utc_offset) {
+                event.start = Some(start);
+                event.all_day = all_day;
+            }
+        }
+        "DTEND" => event.end = parse_datetime(value, params, utc_offset).map(|(end, _)| end),
+        "DURATION" => event.duration = parse_duration(value),
+        "RRULE" => event.rrule = parse_rrule(value, utc_offset),
+        "EXDATE" => event.exdates.extend(
+            value
+                .split(',')
+                .filter_map(|date| parse_datetime(date, params, utc_offset))
+                .map(|(date, _)| date),
+        ),
+        "RECURRENCE-ID" => event.recurrence_id = parse_datetime(value, params, utc_offset).map(|(date, _)| date),
+        "STATUS" => event.cancelled = value.trim().eq_ignore_ascii_case("CANCELLED"),
+        "TRANSP" => event.transparent = value.trim().eq_ignore_ascii_case("TRANSPARENT"),
+        _ => {}
+    }
+}
+
+/// 🔹 DATE (`20250510`) ou DATE-TIME (`20250510T143000`, `20250510T173000Z`); indica se é dia inteiro
+fn parse_datetime(value: &str, params: &[(String, String)], utc_offset: FixedOffset) -> Option<(NaiveDateTime, bool)> {
+    let value = value.trim();
+    let is_date = params.iter().any(|(key, param)| key == "VALUE" && param.eq_ignore_ascii_case("DATE"))
+        || value.len() == 8;
+
+    if is_date {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some((date.and_hms_opt(0, 0, 0)?, true));
+    }
+
+    match value.strip_suffix('Z') {
+        Some(utc) => {
+            let utc = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+            Some((utc + Duration::seconds(utc_offset.local_minus_utc().into()), false))
+        }
+        None => Some((NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?, false)),
+    }
+}
+
+/// 🔹 DURATION (`PT1H30M`, `P1D`, `P2W`); durações negativas são ignoradas
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let value = value.strip_prefix('+').unwrap_or(value).strip_prefix('P')?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for ch in value.chars() {
+        match ch {
+            '0'..='9' => number.push(ch),
+            'T' => {}
+            unit => {
+                let amount: i64 = number.parse().ok()?;
+                number.clear();
+                total += match unit {
+                    'W' => Duration::weeks(amount),
+                    'D' => Duration::days(amount),
+                    'H' => Duration::hours(amount),
+                    'M' => Duration::minutes(amount),
+                    'S' => Duration::seconds(amount),
+                    _ => return None,
+                };
+            }
+        }
+    }
+
+    Some(total)
+}
+
+fn parse_rrule(value: &str, utc_offset: FixedOffset) -> Option<RecurrenceRule> {
+    let mut rule = RecurrenceRule {
+        frequency: Frequency::Daily,
+        interval: 1,
+        count: None,
+        until: None,
+        by_day: Vec::new(),
+        by_month_day: Vec::new(),
+    };
+    let mut frequency = None;
+
+    for part in value.trim().split(';') {
+        let Some((key, value)) = part.split_once('=') else {
+            continue;
+        };
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                frequency = match value.to_ascii_uppercase().as_str() {
+                    "DAILY" => Some(Frequency::Daily),
+                    "WEEKLY" => Some(Frequency::Weekly),
+                    "MONTHLY" => Some(Frequency::Monthly),
+                    "YEARLY" => Some(Frequency::Yearly),
+                    _ => None,
+                }
+            }
+            "INTERVAL" => rule.interval = value.parse::<i64>().ok().filter(|interval| *interval > 0)?,
+            "COUNT" => rule.count = value.parse().ok(),
+            "UNTIL" => {
+                // UNTIL como data é inclusivo: vale o dia inteiro
+                rule.until = parse_datetime(value, &[], utc_offset).map(|(until, all_day)| match all_day {
+                    true => until + Duration::days(1) - Duration::seconds(1),
+                    false => until,
+                });
+            }
+            "BYDAY" => rule.by_day = value.split(',').filter_map(parse_weekday).collect(),
+            "BYMONTHDAY" => rule.by_month_day = value.split(',').filter_map(|day| day.parse().ok()).collect(),
+            _ => {}
+        }
+    }
+
+    rule.frequency = frequency?;
+    Some(rule)
+}
+
+/// 🔹 `MO`, `2TU`, `-1FR` → (ordinal, dia da semana)
+fn parse_weekday(value: &str) -> Option<(Option<i64>, Weekday)> {
+    let value = value.trim();
+    let split = value.len().checked_sub(2)?;
+    let weekday = match &value.get(split..)?.to_ascii_uppercase()[..] {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    };
+    let ordinal = match &value[..split] {
+        "" => None,
+        ordinal => Some(ordinal.trim_start_matches('+').parse().ok()?),
+    };
+
+    Some((ordinal, weekday))
+}
+
+/// 🔹 Gera os inícios das ocorrências a partir de DTSTART, guardando só as que caem em `[not_before, until]`
+fn expand_rule(rule: &RecurrenceRule, start: NaiveDateTime, not_before: NaiveDateTime, until: NaiveDateTime) -> Vec<NaiveDateTime> {
+    let until = rule.until.map_or(until, |rule_until| rule_until.min(until));
+    let mut occurrences = Vec::new();
+    let mut generated = 0;
+
+    for period in 0..MAX_RECURRENCE_PERIODS {
+        let (anchor, dates) = period_dates(rule, start.date(), period * rule.interval);
+        if anchor > until.date() {
+            break;
+        }
+
+        for date in dates {
+            let occurrence = date.and_time(start.time());
+            if occurrence < start {
+                continue;
+            }
+            if occurrence > until || rule.count.is_some_and(|count| generated >= count) {
+                return occurrences;
+            }
+
+            generated += 1;
+            if occurrence >= not_before {
+                occurrences.push(occurrence);
+                if occurrences.len() >= MAX_OCCURRENCES {
+                    return occurrences;
+                }
+            }
+        }
+    }
+
+    occurrences
+}
+
+/// 🔹 Datas candidatas de um período da regra (dia, semana, mês ou ano), já ordenadas
+fn period_dates(rule: &RecurrenceRule, start: NaiveDate, offset: i64) -> (NaiveDate, Vec<NaiveDate>) {
+    let weekdays: Vec<Weekday> = rule.by_day.iter().map(|(_, weekday)| *weekday).collect();
+
+    let (anchor, mut dates) = match rule.frequency {
+        Frequency::Daily => {
+            let date = start + Duration::days(offset);
+            let matches = weekdays.is_empty() || weekdays.contains(&date.weekday());
+            (date, if matches { vec![date] } else { Vec::new() })
+        }
+        Frequency::Weekly => {
+            let week = start - Duration::days(start.weekday().num_days_from_monday().into()) + Duration::weeks(offset);
+            let days = if weekdays.is_empty() { vec![start.weekday()] } else { weekdays };
+            let dates = days
+                .iter()
+                .map(|weekday| week + Duration::days(weekday.num_days_from_monday().into()))
+                .collect();
+            (week, dates)
+        }
+        Frequency::Monthly => {
+            let total = i64::from(start.year()) * 12 + i64::from(start.month0()) + offset;
+            let (year, month) = ((total / 12) as i32, (total % 12) as u32 + 1);
+            let first = NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(start);
+            let dates = if !rule.by_day.is_empty() {
+                rule.by_day
+                    .iter()
+                    .flat_map(|(ordinal, weekday)| weekdays_in_month(first, *weekday, *ordinal))
+                    .collect()
+            } else if !rule.by_month_day.is_empty() {
+                rule.by_month_day.iter().filter_map(|day| month_day(first, *day)).collect()
+            } else {
+                month_day(first, start.day().into()).into_iter().collect()
+            };
+            (first, dates)
+        }
+        Frequency::Yearly => {
+            let year = start.year() + offset as i32;
+            let first = NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or(start);
+            (first, NaiveDate::from_ymd_opt(year, start.month(), start.day()).into_iter().collect())
+        }
+    };
+
+    dates.sort();
+    dates.dedup();
+    (anchor, dates)
+}
+
+/// 🔹 Dia do mês (negativo conta a partir do fim: -1 é o último dia)
+fn month_day(first: NaiveDate, day: i64) -> Option<NaiveDate> {
+    let date = if day > 0 {
+        first + Duration::days(day - 1)
+    } else {
+        last_day_of_month(first) + Duration::days(day + 1)
+    };
+    (date.month() == first.month() && day != 0).then_some(date)
+}
+
+/// 🔹 Todas as ocorrências de um dia da semana no mês, ou só a n-ésima (`2TU`, `-1FR`)
+fn weekdays_in_month(first: NaiveDate, weekday: Weekday, ordinal: Option<i64>) -> Vec<NaiveDate> {
+    let offset = (7 + weekday.num_days_from_monday() - first.weekday().num_days_from_monday()) % 7;
+    let all: Vec<NaiveDate> = (0..5)
+        .map(|week| first + Duration::days(i64::from(offset) + week * 7))
+        .filter(|date| date.month() == first.month())
+        .collect();
+
+    match ordinal {
+        None => all,
+        Some(n) if n > 0 => all.get(n as usize - 1).copied().into_iter().collect(),
+        Some(n) => all.len().checked_sub(n.unsigned_abs() as usize).and_then(|index| all.get(index)).copied().into_iter().collect(),
+    }
+}
+
+fn last_day_of_month(first: NaiveDate) -> NaiveDate {
+    let (year, month) = if first.month() == 12 { (first.year() + 1, 1) } else { (first.year(), first.month() + 1) };
+    NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(first) - Duration::days(1)
+}
+
+/// 🔹 Desfaz o escape de valores TEXT
+fn unescape_text(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            unescaped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_calendar() {
+        let start = NaiveDate::from_ymd_opt(2025, 5, 10).unwrap().and_hms_opt(14, 30, 0).unwrap();
+        let reservation = Reservation {
+            id: Uuid::nil(),
+            user_id: Uuid::nil(),
+            service: "Corte, barba; sobrancelha".to_string(),
+            appointment_time: start,
+            status: "canceled".to_string(),
+            sequence: 2,
+        };
+
+        let ics = render_calendar("Agenda", &[IcsEvent::from_reservation(&reservation)], start);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("UID:reservation-00000000-0000-0000-0000-000000000000@scheduling\r\n"));
+        assert!(ics.contains("DTSTART:20250510T143000\r\nDTEND:20250510T150000\r\n"));
+        assert!(ics.contains("SEQUENCE:2\r\nSTATUS:CANCELLED\r\n"));
+        assert!(ics.contains("SUMMARY:Corte\\, barba\\; sobrancelha\r\n"));
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let line = format!("DESCRIPTION:{}", "á".repeat(60));
+        let folded = fold_line(&line);
+
+        assert!(folded.split("\r\n").all(|part| part.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), format!("{}\r\n", line));
+    }
+
+    #[test]
+    fn test_parse_busy_intervals() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+UID:gym@example.com\r\n\
+SUMMARY:Academia\r\n\
+DTSTART;TZID=America/Sao_Paulo:20250505T070000\r\n\
+DTEND;TZID=America/Sao_Paulo:20250505T080000\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4\r\n\
+EXDATE;TZID=America/Sao_Paulo:20250507T070000\r\n\
+BEGIN:VALARM\r\n\
+TRIGGER:-PT15M\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:gym@example.com\r\n\
+RECURRENCE-ID;TZID=America/Sao_Paulo:20250512T070000\r\n\
+DTSTART:20250512T120000Z\r\n\
+DURATION:PT1H30M\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:free@example.com\r\n\
+DTSTART;VALUE=DATE:20250506\r\n\
+TRANSP:TRANSPARENT\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+        let at = |day, hour, minute| NaiveDate::from_ymd_opt(2025, 5, day).unwrap().and_hms_opt(hour, minute, 0).unwrap();
+        let offset = FixedOffset::west_opt(3 * 3600).unwrap();
+        let intervals = parse_busy_intervals(ics, at(1, 0, 0), at(31, 0, 0), offset).unwrap();
+
+        let spans: Vec<_> = intervals.iter().map(|interval| (interval.start, interval.end)).collect();
+        assert_eq!(spans, vec![
+            (at(5, 7, 0), at(5, 8, 0)),
+            (at(12, 9, 0), at(12, 10, 30)),
+            (at(14, 7, 0), at(14, 8, 0)),
+        ]);
+        assert!(intervals.iter().all(|interval| interval.uid == "gym@example.com"));
+        assert!(parse_busy_intervals("not a calendar", at(1, 0, 0), at(31, 0, 0), offset).is_err());
+    }
+
+    #[test]
+    fn test_expand_monthly_rule() {
+        let rule = parse_rrule("FREQ=MONTHLY;BYDAY=-1FR;UNTIL=20250831", FixedOffset::east_opt(0).unwrap()).unwrap();
+        let start = NaiveDate::from_ymd_opt(2025, 5, 30).unwrap().and_hms_opt(18, 0, 0).unwrap();
+        let until = start + Duration::days(365);
+
+        let dates: Vec<String> = expand_rule(&rule, start, start, until)
+            .iter()
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .collect();
+        assert_eq!(dates, vec!["2025-05-30", "2025-06-27", "2025-07-25", "2025-08-29"]);
+    }
+}
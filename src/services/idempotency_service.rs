@@ -0,0 +1,214 @@
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::jobs::JobRegistry;
+use crate::models::idempotency_key::{IdempotencyKey, NewIdempotencyKey};
+use crate::schema::idempotency_keys;
+use crate::shutdown::Shutdown;
+
+const CLEANUP_JOB: &str = "idempotency_cleanup";
+
+/// 🔹 Por quanto tempo uma resposta é reenviada para a mesma chave
+pub const REPLAY_WINDOW_HOURS: i64 = 24;
+
+/// 🔹 Execuções em andamento há mais tempo que isso são consideradas órfãs (processo morreu) e podem ser retomadas
+const LOCK_TIMEOUT_MINUTES: i64 = 5;
+
+/// 🔹 Resposta guardada da requisição original
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>, // Apenas os reenviados (ver `REPLAYED_HEADERS` no middleware)
+    pub body: Option<Vec<u8>>,          // `None`: corpo grande demais para guardar (a execução vale do mesmo jeito)
+}
+
+/// 🔹 O que fazer com uma requisição que trouxe `Idempotency-Key`
+#[derive(Debug, PartialEq)]
+pub enum Begin {
+    Proceed,                // Primeira execução (ou chave expirada/órfã): executar e guardar a resposta
+    Replay(StoredResponse), // Repetição idêntica já concluída
+    Unreplayable,           // Já concluída, mas o corpo não foi guardado → 409 (nunca executa de novo)
+    Mismatch,               // Mesma chave com outra requisição → 422
+    InFlight,               // Execução original ainda em andamento → 409
+}
+
+/// ✅ Reserva a chave ou decide como responder a uma repetição.
+/// A chave primária `(user_id, idempotency_key)` garante que só uma execução concorrente vence a reserva.
+pub fn begin(conn: &mut PgConnection, user_id: Uuid, key: &str, request_hash: &str, now: NaiveDateTime) -> QueryResult<Begin> {
+    conn.transaction(|conn| {
+        let inserted = diesel::insert_into(idempotency_keys::table)
+            .values(&NewIdempotencyKey {
+                user_id,
+                idempotency_key: key.to_string(),
+                request_hash: request_hash.to_string(),
+                locked_at: now,
+                created_at: now,
+            })
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+        if inserted > 0 {
+            return Ok(Begin::Proceed);
+        }
+
+        let existing = idempotency_keys::table
+            .find((user_id, key))
+            .select(IdempotencyKey::as_select())
+            .for_update()
+            .first(conn)?;
+        let decision = decide(&existing, request_hash, now);
+        if decision == Begin::Proceed {
+            // Chave expirada ou execução órfã: reaproveita a linha para a nova execução
+            diesel::update(idempotency_keys::table.find((user_id, key)))
+                .set((
+                    idempotency_keys::request_hash.eq(request_hash),
+                    idempotency_keys::response_status.eq(None::<i32>),
+                    idempotency_keys::response_headers.eq(None::<serde_json::Value>),
+                    idempotency_keys::response_body.eq(None::<Vec<u8>>),
+                    idempotency_keys::locked_at.eq(now),
+                    idempotency_keys::created_at.eq(now),
+                    idempotency_keys::completed_at.eq(None::<NaiveDateTime>),
+                ))
+                .execute(conn)?;
+        }
+        Ok(decision)
+    })
+}
+
+/// 🔹 Decisão para uma chave já registrada
+fn decide(existing: &IdempotencyKey, request_hash: &str, now: NaiveDateTime) -> Begin {
+    if existing.created_at < now - ChronoDuration::hours(REPLAY_WINDOW_HOURS) {
+        return Begin::Proceed;
+    }
+    if existing.request_hash != request_hash {
+        return Begin::Mismatch;
+    }
+    match existing.response_status {
+        Some(_) if existing.response_body.is_none() => Begin::Unreplayable,
+        Some(status) => Begin::Replay(StoredResponse {
+            status: status as u16,
+            headers: existing
+                .response_headers
+                .as_ref()
+                .and_then(serde_json::Value::as_object)
+                .map(|headers| {
+                    headers.iter().filter_map(|(name, value)| Some((name.clone(), value.as_str()?.to_string()))).collect()
+                })
+                .unwrap_or_default(),
+            body: existing.response_body.clone(),
+        }),
+        None if existing.locked_at < now - ChronoDuration::minutes(LOCK_TIMEOUT_MINUTES) => Begin::Proceed,
+        None => Begin::InFlight,
+    }
+}
+
+/// ✅ Guarda a resposta da execução original
+pub fn complete(conn: &mut PgConnection, user_id: Uuid, key: &str, response: &StoredResponse, now: NaiveDateTime) -> QueryResult<usize> {
+    diesel::update(idempotency_keys::table.find((user_id, key)))
+        .set((
+            idempotency_keys::response_status.eq(Some(response.status as i32)),
+            idempotency_keys::response_headers.eq(Some(serde_json::Value::Object(
+                response.headers.iter().map(|(name, value)| (name.clone(), serde_json::Value::String(value.clone()))).collect(),
+            ))),
+            idempotency_keys::response_body.eq(response.body.as_ref()),
+            idempotency_keys::completed_at.eq(Some(now)),
+        ))
+        .execute(conn)
+}
+
+/// ✅ Libera a chave sem guardar resposta (erro 5xx): a próxima tentativa executa de novo
+pub fn release(conn: &mut PgConnection, user_id: Uuid, key: &str) -> QueryResult<usize> {
+    diesel::delete(
+        idempotency_keys::table
+            .find((user_id, key))
+            .filter(idempotency_keys::response_status.is_null()),
+    )
+    .execute(conn)
+}
+
+/// 🔹 Remove chaves fora da janela de repetição
+fn purge_expired(conn: &mut PgConnection, now: NaiveDateTime) -> QueryResult<usize> {
+    diesel::delete(idempotency_keys::table.filter(idempotency_keys::created_at.lt(now - ChronoDuration::hours(REPLAY_WINDOW_HOURS))))
+        .execute(conn)
+}
+
+/// ✅ Worker que remove as chaves expiradas a cada `every`
+pub fn spawn_cleanup(pool: Arc<Pool>, every: Duration, jobs: JobRegistry, shutdown: Shutdown) -> tokio::task::JoinHandle<()> {
+    jobs.register(CLEANUP_JOB, every);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let pool = pool.clone();
+                    let purged = tokio::task::spawn_blocking(move || {
+                        let mut conn = pool.get().map_err(|e| e.to_string())?;
+                        purge_expired(&mut conn, Utc::now().naive_utc()).map_err(|e| e.to_string())
+                    })
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|purged| purged);
+                    match purged {
+                        Ok(_) => jobs.heartbeat(CLEANUP_JOB),
+                        Err(e) => {
+                            warn!("⚠️ Falha ao limpar chaves de idempotência expiradas: {}", e);
+                            jobs.failed(CLEANUP_JOB, &e);
+                        }
+                    }
+                }
+                _ = shutdown.wait() => break,
+            }
+        }
+
+        jobs.stopped(CLEANUP_JOB);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decide_replay_mismatch_and_expiry() {
+        let now = Utc::now().naive_utc();
+        let mut existing = IdempotencyKey {
+            request_hash: "abc".to_string(),
+            response_status: None,
+            response_headers: None,
+            response_body: None,
+            locked_at: now - ChronoDuration::seconds(2),
+            created_at: now - ChronoDuration::seconds(2),
+        };
+        assert_eq!(decide(&existing, "abc", now), Begin::InFlight);
+        assert_eq!(decide(&existing, "other", now), Begin::Mismatch);
+
+        existing.locked_at = now - ChronoDuration::minutes(LOCK_TIMEOUT_MINUTES + 1); // Processo morreu no meio
+        assert_eq!(decide(&existing, "abc", now), Begin::Proceed);
+
+        existing.response_status = Some(201);
+        existing.response_headers = Some(serde_json::json!({ "content-type": "application/json", "location": "/v1/services/1" }));
+        existing.response_body = Some(b"{}".to_vec());
+        assert_eq!(
+            decide(&existing, "abc", now),
+            Begin::Replay(StoredResponse {
+                status: 201,
+                headers: vec![
+                    ("content-type".to_string(), "application/json".to_string()),
+                    ("location".to_string(), "/v1/services/1".to_string()),
+                ],
+                body: Some(b"{}".to_vec()),
+            })
+        );
+
+        existing.response_body = None; // Resposta grande demais para guardar
+        assert_eq!(decide(&existing, "abc", now), Begin::Unreplayable);
+
+        existing.created_at = now - ChronoDuration::hours(REPLAY_WINDOW_HOURS + 1);
+        assert_eq!(decide(&existing, "other", now), Begin::Proceed);
+    }
+}
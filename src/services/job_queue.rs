@@ -0,0 +1,241 @@
+use async_trait::async_trait;
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::jobs::JobRegistry;
+use crate::models::background_job::{BackgroundJob, NewBackgroundJob};
+use crate::schema::background_jobs;
+use crate::shutdown::Shutdown;
+
+/// 🔹 Estados de `background_jobs.status`
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_RUNNING: &str = "running";
+pub const STATUS_DONE: &str = "done";
+pub const STATUS_FAILED: &str = "failed";
+pub const STATUS_CANCELED: &str = "canceled";
+
+const RUNNER_JOB: &str = "job_runner";
+
+/// 🔹 Jobs reservados por ciclo de polling
+const CLAIM_BATCH: i64 = 20;
+
+/// 🔹 Jobs "running" há mais tempo que isso são considerados órfãos (processo morreu) e voltam para a fila
+const LOCK_TIMEOUT_MINUTES: i64 = 10;
+
+/// 🔹 Backoff exponencial entre tentativas: 30s, 60s, 120s... até 1 hora
+const RETRY_BASE_SECS: i64 = 30;
+const RETRY_MAX_SECS: i64 = 3600;
+
+/// 🔹 Executor de um tipo de job
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    fn kind(&self) -> &'static str;
+
+    async fn run(&self, job: &BackgroundJob) -> Result<(), String>;
+}
+
+/// ✅ Enfileira um job para rodar em `run_at`
+pub fn enqueue(
+    conn: &mut PgConnection,
+    kind: &str,
+    payload: serde_json::Value,
+    reference_id: Option<Uuid>,
+    run_at: NaiveDateTime,
+) -> QueryResult<BackgroundJob> {
+    diesel::insert_into(background_jobs::table)
+        .values(&NewBackgroundJob {
+            kind: kind.to_string(),
+            payload,
+            reference_id,
+            run_at,
+        })
+        .get_result(conn)
+}
+
+/// ✅ Cancela os jobs ainda pendentes de um tipo para o registro relacionado
+pub fn cancel_pending(conn: &mut PgConnection, kind: &str, reference_id: Uuid) -> QueryResult<usize> {
+    diesel::update(
+        background_jobs::table
+            .filter(background_jobs::kind.eq(kind))
+            .filter(background_jobs::reference_id.eq(reference_id))
+            .filter(background_jobs::status.eq(STATUS_PENDING)),
+    )
+    .set((
+        background_jobs::status.eq(STATUS_CANCELED),
+        background_jobs::updated_at.eq(Utc::now().naive_utc()),
+    ))
+    .execute(conn)
+}
+
+/// ✅ Espera antes da próxima tentativa, dado o número de tentativas já feitas
+pub fn retry_delay(attempts: i32) -> ChronoDuration {
+    let exponent = attempts.clamp(1, 20) as u32 - 1;
+    let secs = RETRY_BASE_SECS.saturating_mul(2_i64.saturating_pow(exponent));
+    ChronoDuration::seconds(secs.min(RETRY_MAX_SECS))
+}
+
+/// ✅ Worker que executa os jobs vencidos a cada `every`
+/// (vários processos podem rodar em paralelo: a reserva usa `FOR UPDATE SKIP LOCKED`)
+pub fn spawn_runner(
+    pool: Arc<Pool>,
+    handlers: Vec<Arc<dyn JobHandler>>,
+    every: Duration,
+    jobs: JobRegistry,
+    shutdown: Shutdown,
+) -> tokio::task::JoinHandle<()> {
+    jobs.register(RUNNER_JOB, every);
+    let handlers: Arc<HashMap<&'static str, Arc<dyn JobHandler>>> =
+        Arc::new(handlers.into_iter().map(|handler| (handler.kind(), handler)).collect());
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => match run_due(pool.clone(), handlers.clone()).await {
+                    Ok(_) => jobs.heartbeat(RUNNER_JOB),
+                    Err(e) => {
+                        warn!("⚠️ Falha ao buscar jobs: {}", e);
+                        jobs.failed(RUNNER_JOB, &e);
+                    }
+                },
+                _ = shutdown.wait() => break,
+            }
+        }
+
+        jobs.stopped(RUNNER_JOB);
+    })
+}
+
+/// 🔹 Reserva e executa um lote de jobs vencidos; devolve quantos foram executados
+async fn run_due(pool: Arc<Pool>, handlers: Arc<HashMap<&'static str, Arc<dyn JobHandler>>>) -> Result<usize, String> {
+    let claimer = pool.clone();
+    let claimed = tokio::task::spawn_blocking(move || {
+        let mut conn = claimer.get().map_err(|e| e.to_string())?;
+        claim_due(&mut conn, Utc::now().naive_utc()).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let count = claimed.len();
+    for job in claimed {
+        let result = match handlers.get(job.kind.as_str()) {
+            Some(handler) => handler.run(&job).await,
+            None => Err(format!("Nenhum handler para jobs do tipo {}", job.kind)),
+        };
+
+        let recorder = pool.clone();
+        let recorded = tokio::task::spawn_blocking(move || {
+            let mut conn = recorder.get().map_err(|e| e.to_string())?;
+            record_result(&mut conn, &job, result, Utc::now().naive_utc()).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|recorded| recorded);
+
+        if let Err(e) = recorded {
+            warn!("⚠️ Falha ao registrar resultado de job: {}", e);
+        }
+    }
+
+    Ok(count)
+}
+
+/// 🔹 Devolve jobs órfãos para a fila e reserva os vencidos (`SKIP LOCKED`: outros workers pulam as linhas travadas)
+fn claim_due(conn: &mut PgConnection, now: NaiveDateTime) -> QueryResult<Vec<BackgroundJob>> {
+    conn.transaction(|conn| {
+        diesel::update(
+            background_jobs::table
+                .filter(background_jobs::status.eq(STATUS_RUNNING))
+                .filter(background_jobs::locked_at.lt(now - ChronoDuration::minutes(LOCK_TIMEOUT_MINUTES))),
+        )
+        .set((background_jobs::status.eq(STATUS_PENDING), background_jobs::locked_at.eq(None::<NaiveDateTime>)))
+        .execute(conn)?;
+
+        let due = background_jobs::table
+            .filter(background_jobs::status.eq(STATUS_PENDING))
+            .filter(background_jobs::run_at.le(now))
+            .order(background_jobs::run_at.asc())
+            .limit(CLAIM_BATCH)
+            .for_update()
+            .skip_locked()
+            .select(background_jobs::id)
+            .load::<Uuid>(conn)?;
+
+        if due.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        diesel::update(background_jobs::table.filter(background_jobs::id.eq_any(&due)))
+            .set((
+                background_jobs::status.eq(STATUS_RUNNING),
+                background_jobs::locked_at.eq(now),
+                background_jobs::attempts.eq(background_jobs::attempts + 1),
+                background_jobs::updated_at.eq(now),
+            ))
+            .get_results::<BackgroundJob>(conn)
+    })
+}
+
+/// 🔹 Sucesso → "done"; erro → nova tentativa com backoff, ou "failed" ao esgotar as tentativas
+fn record_result(conn: &mut PgConnection, job: &BackgroundJob, result: Result<(), String>, now: NaiveDateTime) -> QueryResult<()> {
+    let target = background_jobs::table.find(job.id);
+
+    match result {
+        Ok(()) => {
+            debug!("✅ Job {} ({}) concluído", job.id, job.kind);
+            diesel::update(target)
+                .set((
+                    background_jobs::status.eq(STATUS_DONE),
+                    background_jobs::locked_at.eq(None::<NaiveDateTime>),
+                    background_jobs::updated_at.eq(now),
+                ))
+                .execute(conn)?;
+        }
+        Err(error) if job.attempts >= job.max_attempts => {
+            warn!("❌ Job {} ({}) falhou definitivamente após {} tentativas: {}", job.id, job.kind, job.attempts, error);
+            diesel::update(target)
+                .set((
+                    background_jobs::status.eq(STATUS_FAILED),
+                    background_jobs::last_error.eq(error),
+                    background_jobs::locked_at.eq(None::<NaiveDateTime>),
+                    background_jobs::updated_at.eq(now),
+                ))
+                .execute(conn)?;
+        }
+        Err(error) => {
+            let retry_at = now + retry_delay(job.attempts);
+            warn!("⚠️ Job {} ({}) falhou (tentativa {}), nova tentativa em {}: {}", job.id, job.kind, job.attempts, retry_at, error);
+            diesel::update(target)
+                .set((
+                    background_jobs::status.eq(STATUS_PENDING),
+                    background_jobs::run_at.eq(retry_at),
+                    background_jobs::last_error.eq(error),
+                    background_jobs::locked_at.eq(None::<NaiveDateTime>),
+                    background_jobs::updated_at.eq(now),
+                ))
+                .execute(conn)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        assert_eq!(retry_delay(1), ChronoDuration::seconds(30));
+        assert_eq!(retry_delay(2), ChronoDuration::seconds(60));
+        assert_eq!(retry_delay(4), ChronoDuration::seconds(240));
+        assert_eq!(retry_delay(10), ChronoDuration::seconds(RETRY_MAX_SECS));
+        assert_eq!(retry_delay(i32::MAX), ChronoDuration::seconds(RETRY_MAX_SECS));
+    }
+}
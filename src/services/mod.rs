@@ -1,2 +1,18 @@
 pub mod reservation_service;
 pub mod auth_service;
+pub mod health_service;
+pub mod ical_service;
+pub mod busy_block_service;
+pub mod caldav_service;
+pub mod http_client;
+pub mod job_queue;
+pub mod reminder_service;
+pub mod webhook_service;
+pub mod outbox;
+pub mod report_service;
+pub mod bulk_service;
+pub mod idempotency_service;
+pub mod retention_service;
+pub mod audit_service;
+pub mod privacy_service;
+pub mod guest_service;
@@ -0,0 +1,338 @@
+use async_trait::async_trait;
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::events::DomainEvent;
+use crate::jobs::JobRegistry;
+use crate::models::outbox_event::{NewOutboxEvent, OutboxEvent};
+use crate::schema::{outbox_consumptions, outbox_events};
+use crate::services::job_queue::retry_delay;
+use crate::shutdown::Shutdown;
+
+const RELAY_JOB: &str = "outbox_relay";
+
+/// 🔹 Eventos reservados por ciclo de polling
+const CLAIM_BATCH: i64 = 50;
+
+/// 🔹 Eventos reservados há mais tempo que isso são considerados órfãos (processo morreu) e voltam para a fila
+const LOCK_TIMEOUT_MINUTES: i64 = 5;
+
+/// 🔹 Eventos publicados ficam disponíveis para auditoria por 7 dias; a limpeza roda a cada hora
+const RETENTION_DAYS: i64 = 7;
+const CLEANUP_EVERY: Duration = Duration::from_secs(3600);
+
+/// 🔹 Consumidor de eventos do outbox (notificações, webhooks...)
+/// Entrega "pelo menos uma vez": o evento volta a ser entregue até todos os consumidores terem sucesso.
+#[async_trait]
+pub trait OutboxConsumer: Send + Sync {
+    /// Nome estável, usado como parte da chave de idempotência
+    fn name(&self) -> &'static str;
+
+    async fn handle(&self, event: &OutboxEvent) -> Result<(), String>;
+}
+
+/// ✅ Grava um evento no outbox (usar dentro da transação da alteração que o gerou)
+pub fn record(conn: &mut PgConnection, event_type: &str, payload: serde_json::Value) -> QueryResult<Uuid> {
+    diesel::insert_into(outbox_events::table)
+        .values(&NewOutboxEvent {
+            event_type: event_type.to_string(),
+            payload,
+        })
+        .returning(outbox_events::id)
+        .get_result(conn)
+}
+
+/// ✅ Grava um evento de agendamento/reserva
+pub fn record_event(conn: &mut PgConnection, event: &DomainEvent) -> QueryResult<Uuid> {
+    record(conn, &event.name(), serde_json::json!(event))
+}
+
+/// ✅ Marca o evento como processado pelo consumidor; `false` se ele já tinha sido processado.
+/// Consumidores que gravam no banco podem chamar dentro da própria transação para processar exatamente uma vez.
+pub fn mark_consumed(conn: &mut PgConnection, consumer: &str, event_id: Uuid) -> QueryResult<bool> {
+    let inserted = diesel::insert_into(outbox_consumptions::table)
+        .values((
+            outbox_consumptions::consumer.eq(consumer),
+            outbox_consumptions::event_id.eq(event_id),
+        ))
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+    Ok(inserted > 0)
+}
+
+/// ✅ Worker que publica os eventos do outbox para os consumidores a cada `every`
+/// (vários processos podem rodar em paralelo: a reserva usa `FOR UPDATE SKIP LOCKED`).
+pub fn spawn_relay(
+    pool: Arc<Pool>,
+    consumers: Vec<Arc<dyn OutboxConsumer>>,
+    every: Duration,
+    jobs: JobRegistry,
+    shutdown: Shutdown,
+) -> tokio::task::JoinHandle<()> {
+    jobs.register(RELAY_JOB, every.max(Duration::from_secs(1)));
+    let consumers = Arc::new(consumers);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        let mut last_cleanup: Option<Instant> = None;
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    match relay_due(pool.clone(), consumers.clone()).await {
+                        Ok(_) => jobs.heartbeat(RELAY_JOB),
+                        Err(e) => {
+                            warn!("⚠️ Falha ao buscar eventos do outbox: {}", e);
+                            jobs.failed(RELAY_JOB, &e);
+                        }
+                    }
+
+                    if last_cleanup.is_none_or(|at| at.elapsed() >= CLEANUP_EVERY) {
+                        last_cleanup = Some(Instant::now());
+                        let pool = pool.clone();
+                        let purged = tokio::task::spawn_blocking(move || {
+                            let mut conn = pool.get().map_err(|e| e.to_string())?;
+                            purge_published(&mut conn, Utc::now().naive_utc()).map_err(|e| e.to_string())
+                        })
+                        .await
+                        .map_err(|e| e.to_string())
+                        .and_then(|purged| purged);
+                        if let Err(e) = purged {
+                            warn!("⚠️ Falha ao limpar eventos publicados do outbox: {}", e);
+                        }
+                    }
+                },
+                _ = shutdown.wait() => break,
+            }
+        }
+
+        jobs.stopped(RELAY_JOB);
+    })
+}
+
+/// 🔹 Reserva e publica um lote de eventos; devolve quantos foram processados
+async fn relay_due(pool: Arc<Pool>, consumers: Arc<Vec<Arc<dyn OutboxConsumer>>>) -> Result<usize, String> {
+    let claimer = pool.clone();
+    let claimed = tokio::task::spawn_blocking(move || {
+        let mut conn = claimer.get().map_err(|e| e.to_string())?;
+        let now = Utc::now().naive_utc();
+        let events = claim_due(&mut conn, now).map_err(|e| e.to_string())?;
+        let ids: Vec<Uuid> = events.iter().map(|event| event.id).collect();
+        let consumed = outbox_consumptions::table
+            .filter(outbox_consumptions::event_id.eq_any(&ids))
+            .select((outbox_consumptions::consumer, outbox_consumptions::event_id))
+            .load::<(String, Uuid)>(&mut conn)
+            .map_err(|e| e.to_string())?;
+        Ok::<_, String>((events, consumed.into_iter().collect::<HashSet<_>>()))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let (events, consumed) = claimed;
+    let count = events.len();
+    for event in events {
+        let mut errors = Vec::new();
+        for consumer in consumers.iter() {
+            if consumed.contains(&(consumer.name().to_string(), event.id)) {
+                continue;
+            }
+            match consumer.handle(&event).await {
+                Ok(()) => {
+                    let (marker, name, event_id) = (pool.clone(), consumer.name(), event.id);
+                    let marked = tokio::task::spawn_blocking(move || {
+                        let mut conn = marker.get().map_err(|e| e.to_string())?;
+                        mark_consumed(&mut conn, name, event_id).map_err(|e| e.to_string())
+                    })
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|marked| marked);
+                    // Sem a marca, o consumidor recebe o evento de novo na próxima tentativa
+                    if let Err(e) = marked {
+                        errors.push(format!("{}: {}", consumer.name(), e));
+                    }
+                }
+                Err(e) => errors.push(format!("{}: {}", consumer.name(), e)),
+            }
+        }
+
+        let published = errors.is_empty();
+
+        let recorder = pool.clone();
+        let recorded = tokio::task::spawn_blocking(move || {
+            let mut conn = recorder.get().map_err(|e| e.to_string())?;
+            let result = if published { Ok(()) } else { Err(errors.join("; ")) };
+            record_outcome(&mut conn, &event, result, Utc::now().naive_utc()).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|recorded| recorded);
+
+        if let Err(e) = recorded {
+            warn!("⚠️ Falha ao registrar publicação de evento do outbox: {}", e);
+        }
+    }
+
+    Ok(count)
+}
+
+/// 🔹 Devolve eventos órfãos para a fila e reserva os disponíveis, do mais antigo para o mais novo
+fn claim_due(conn: &mut PgConnection, now: NaiveDateTime) -> QueryResult<Vec<OutboxEvent>> {
+    conn.transaction(|conn| {
+        let due = outbox_events::table
+            .filter(outbox_events::published_at.is_null())
+            .filter(outbox_events::available_at.le(now))
+            .filter(
+                outbox_events::locked_at
+                    .is_null()
+                    .or(outbox_events::locked_at.lt(now - ChronoDuration::minutes(LOCK_TIMEOUT_MINUTES))),
+            )
+            .order(outbox_events::created_at.asc())
+            .limit(CLAIM_BATCH)
+            .for_update()
+            .skip_locked()
+            .select(outbox_events::id)
+            .load::<Uuid>(conn)?;
+
+        if due.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        diesel::update(outbox_events::table.filter(outbox_events::id.eq_any(&due)))
+            .set(outbox_events::locked_at.eq(now))
+            .execute(conn)?;
+
+        outbox_events::table
+            .filter(outbox_events::id.eq_any(&due))
+            .order(outbox_events::created_at.asc())
+            .select(OutboxEvent::as_select())
+            .load::<OutboxEvent>(conn)
+    })
+}
+
+/// 🔹 Sucesso → publicado; erro → nova tentativa com backoff (eventos nunca são descartados)
+fn record_outcome(conn: &mut PgConnection, event: &OutboxEvent, result: Result<(), String>, now: NaiveDateTime) -> QueryResult<()> {
+    let target = outbox_events::table.find(event.id);
+
+    match result {
+        Ok(()) => {
+            debug!("✅ Evento {} ({}) publicado", event.id, event.event_type);
+            diesel::update(target)
+                .set((
+                    outbox_events::published_at.eq(now),
+                    outbox_events::locked_at.eq(None::<NaiveDateTime>),
+                ))
+                .execute(conn)?;
+        }
+        Err(error) => {
+            let attempts = event.attempts + 1;
+            let retry_at = now + retry_delay(attempts);
+            warn!("⚠️ Evento {} ({}) não publicado (tentativa {}), nova tentativa em {}: {}", event.id, event.event_type, attempts, retry_at, error);
+            diesel::update(target)
+                .set((
+                    outbox_events::attempts.eq(attempts),
+                    outbox_events::available_at.eq(retry_at),
+                    outbox_events::last_error.eq(error),
+                    outbox_events::locked_at.eq(None::<NaiveDateTime>),
+                ))
+                .execute(conn)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 🔹 Remove eventos publicados há mais de `RETENTION_DAYS` (as chaves de idempotência vão junto)
+fn purge_published(conn: &mut PgConnection, now: NaiveDateTime) -> QueryResult<usize> {
+    diesel::delete(outbox_events::table.filter(outbox_events::published_at.lt(now - ChronoDuration::days(RETENTION_DAYS))))
+        .execute(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Consumidor que falha nas primeiras `failures` chamadas
+    struct Flaky {
+        name: &'static str,
+        failures: usize,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl OutboxConsumer for Flaky {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn handle(&self, _event: &OutboxEvent) -> Result<(), String> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err("gateway indisponível".to_string());
+            }
+            Ok(())
+        }
+    }
+
+    /// Espera (até 5s) o consumidor ser chamado `calls` vezes e o relay registrar o resultado
+    async fn wait_for(consumer: &Flaky, calls: usize, pool: &Pool, event_id: Uuid) -> (Option<NaiveDateTime>, i32, Option<String>) {
+        for _ in 0..250 {
+            let state = outbox_events::table
+                .find(event_id)
+                .select((outbox_events::published_at, outbox_events::attempts, outbox_events::last_error, outbox_events::locked_at))
+                .first::<(Option<NaiveDateTime>, i32, Option<String>, Option<NaiveDateTime>)>(&mut pool.get().unwrap())
+                .unwrap();
+            if consumer.calls.load(Ordering::SeqCst) >= calls && state.3.is_none() {
+                return (state.0, state.1, state.2);
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("relay não processou o evento a tempo");
+    }
+
+    #[tokio::test]
+    async fn test_relay_publishes_only_after_every_consumer_and_retries() {
+        let Some(db) = test_db::setup() else { return };
+        let event_id = record(&mut db.pool.get().unwrap(), "appointment.created", serde_json::json!({ "id": "x" })).unwrap();
+        let make_due = || {
+            diesel::update(outbox_events::table.find(event_id))
+                .set(outbox_events::available_at.eq(Utc::now().naive_utc() - ChronoDuration::minutes(1)))
+                .execute(&mut db.pool.get().unwrap())
+                .unwrap();
+        };
+        make_due();
+
+        let steady = Arc::new(Flaky { name: "steady", failures: 0, calls: AtomicUsize::new(0) });
+        let flaky = Arc::new(Flaky { name: "flaky", failures: 1, calls: AtomicUsize::new(0) });
+        let shutdown = Shutdown::new();
+        let relay = spawn_relay(
+            db.pool.clone(),
+            vec![steady.clone(), flaky.clone()],
+            Duration::from_millis(20),
+            JobRegistry::new(),
+            shutdown.clone(),
+        );
+
+        // Um consumidor falhou: o evento não é publicado e volta para a fila com backoff
+        let (published_at, attempts, last_error) = wait_for(&flaky, 1, &db.pool, event_id).await;
+        assert!(published_at.is_none());
+        assert_eq!(attempts, 1);
+        assert!(last_error.unwrap().contains("flaky: gateway indisponível"));
+        assert!(mark_consumed(&mut db.pool.get().unwrap(), "steady", event_id).is_ok_and(|inserted| !inserted));
+
+        // Nova tentativa: só o consumidor que falhou recebe o evento de novo; agora ele é publicado
+        make_due();
+        let (published_at, attempts, _) = wait_for(&flaky, 2, &db.pool, event_id).await;
+        assert!(published_at.is_some());
+        assert_eq!(attempts, 1);
+        assert_eq!(steady.calls.load(Ordering::SeqCst), 1);
+
+        shutdown.trigger();
+        relay.await.unwrap();
+    }
+}
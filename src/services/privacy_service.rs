@@ -0,0 +1,399 @@
+use chrono::NaiveDateTime;
+use diesel::expression::BoxableExpression;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::sql_types::Bool;
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::models::{
+    app_password::AppPassword, appointment::Appointment, attendance_confirmation::AttendanceConfirmation,
+    audit_event::AuditEvent, client_note::ClientNote, client_profile::ClientProfile, notification_preference::NotificationPreference, professional::Professional,
+    reservation::Reservation, user::User,
+};
+use crate::schema::{
+    app_passwords, appointments, attendance_confirmations, audit_events, background_jobs, calendar_feed_tokens, client_notes,
+    client_profiles, clients, guest_verifications, idempotency_keys,
+    notification_preferences, outbox_events, professionals, reservations, users, webhook_deliveries,
+};
+use crate::services::{guest_service, job_queue, reminder_service};
+
+/// 🔹 Versão do formato do arquivo exportado
+pub const EXPORT_FORMAT: &str = "lgpd-export/1";
+
+/// 🔹 Nome exibido no lugar do titular depois da eliminação
+pub const ERASED_NAME: &str = "Titular removido";
+
+/// 🔹 Campos pessoais mascarados na trilha de auditoria (cadastro e ficha do cliente), nos eventos do outbox,
+/// nas entregas de webhooks e nos jobs (`to` é o destino do código por SMS)
+const PERSONAL_FIELDS: [&str; 9] =
+    ["name", "phone", "email", "email_address", "bio", "hair_formula", "allergies", "preferred_contact_time", "to"];
+const ERASED: &str = "[erased]";
+
+/// 🔹 Entidades cujo ID na trilha é o do próprio titular
+const SUBJECT_ENTITIES: [&str; 2] = ["users", "client_profiles"];
+
+/// 🔹 Notificação gerada para o titular (evento do outbox que alimenta e-mail/SMS/WhatsApp e webhooks)
+#[derive(Debug, Queryable, Serialize)]
+pub struct ExportedNotification {
+    pub id: Uuid,
+    pub event_type: String,
+    pub payload: Value,
+    pub created_at: NaiveDateTime,
+    pub published_at: Option<NaiveDateTime>,
+}
+
+/// 🔹 Arquivo de portabilidade: tudo o que o sistema guarda sobre o titular
+#[derive(Debug, Serialize)]
+pub struct DataExport {
+    pub format: &'static str,
+    pub generated_at: NaiveDateTime,
+    pub user: Value, // Sem `password_hash`
+    pub professional: Option<Professional>,
+    pub notification_preferences: Option<NotificationPreference>,
+    pub client_profile: Option<ClientProfile>,
+    pub client_notes: Vec<ClientNote>, // Inclusive as de uso interno da equipe
+    pub app_passwords: Vec<AppPassword>, // Apenas nome e datas (o segredo não é guardado)
+    pub reservations: Vec<Reservation>,
+    pub appointments: Vec<Appointment>,
+    pub attendance_confirmations: Vec<AttendanceConfirmation>,
+    pub notifications: Vec<ExportedNotification>,
+    pub audit_events: Vec<AuditEvent>, // Ações do titular e alterações no seu cadastro
+}
+
+/// ✅ Reúne os dados do titular (inclusive registros excluídos logicamente)
+pub fn export(conn: &mut PgConnection, user_id: Uuid, now: NaiveDateTime) -> QueryResult<DataExport> {
+    let user = users::table.find(user_id).first::<User>(conn)?;
+    let mut user = serde_json::to_value(&user).unwrap_or(Value::Null);
+    if let Value::Object(fields) = &mut user {
+        fields.remove("password_hash");
+    }
+
+    let appointments = appointments::table
+        .filter(appointments::client_id.eq(user_id))
+        .order(appointments::appointment_time.asc())
+        .load::<Appointment>(conn)?;
+    let appointment_ids: Vec<Uuid> = appointments.iter().map(|appointment| appointment.id).collect();
+
+    Ok(DataExport {
+        format: EXPORT_FORMAT,
+        generated_at: now,
+        user,
+        professional: professionals::table
+            .filter(professionals::user_id.eq(user_id))
+            .select(Professional::as_select())
+            .first(conn)
+            .optional()?,
+        notification_preferences: notification_preferences::table
+            .find(user_id)
+            .select(NotificationPreference::as_select())
+            .first(conn)
+            .optional()?,
+        client_profile: client_profiles::table
+            .find(user_id)
+            .select(ClientProfile::as_select())
+            .first(conn)
+            .optional()?,
+        client_notes: client_notes::table
+            .filter(client_notes::client_id.eq(user_id))
+            .order(client_notes::created_at.asc())
+            .select(ClientNote::as_select())
+            .load(conn)?,
+        app_passwords: app_passwords::table
+            .filter(app_passwords::user_id.eq(user_id))
+            .order(app_passwords::created_at.asc())
+            .select(AppPassword::as_select())
+            .load(conn)?,
+        reservations: reservations::table
+            .filter(reservations::user_id.eq(user_id))
+            .order(reservations::appointment_time.asc())
+            .select(Reservation::as_select())
+            .load(conn)?,
+        attendance_confirmations: attendance_confirmations::table
+            .filter(attendance_confirmations::appointment_id.eq_any(&appointment_ids))
+            .load::<AttendanceConfirmation>(conn)?,
+        appointments,
+        notifications: outbox_events::table
+            .filter(about_subject(user_id))
+            .order(outbox_events::created_at.asc())
+            .select((
+                outbox_events::id,
+                outbox_events::event_type,
+                outbox_events::payload,
+                outbox_events::created_at,
+                outbox_events::published_at,
+            ))
+            .load::<ExportedNotification>(conn)?,
+        audit_events: audit_events::table
+            .filter(
+                audit_events::actor_id
+                    .eq(user_id)
+                    .or(audit_events::entity_type.eq_any(SUBJECT_ENTITIES).and(audit_events::entity_id.eq(user_id))),
+            )
+            .order(audit_events::created_at.asc())
+            .select(AuditEvent::as_select())
+            .load(conn)?,
+    })
+}
+
+/// 🔹 O que a eliminação alterou
+#[derive(Debug, Serialize)]
+pub struct Erasure {
+    pub user_id: Uuid,
+    pub anonymized_at: NaiveDateTime,
+    pub canceled_appointments: usize, // Agendamentos futuros cancelados (não há mais como avisar o titular)
+    pub redacted_audit_events: usize,
+    #[serde(skip)]
+    pub canceled: Vec<Appointment>,   // Para avisar a agenda ao vivo depois do commit
+}
+
+/// ✅ Elimina os dados pessoais do titular numa transação: nome, telefone e e-mail viram valores anônimos,
+/// credenciais, preferências, ficha e anotações são apagadas e a conta fica excluída. Agendamentos, reservas e eventos
+/// continuam (só com IDs), então relatórios e o histórico financeiro não mudam.
+/// `local_now` é o horário atual no fuso do salão (agendamentos futuros são cancelados). `None` se já foi eliminado.
+pub fn erase(conn: &mut PgConnection, user_id: Uuid, now: NaiveDateTime, local_now: NaiveDateTime) -> QueryResult<Option<Erasure>> {
+    conn.transaction(|conn| {
+        let user = users::table.find(user_id).for_update().first::<User>(conn)?;
+        if user.anonymized_at.is_some() {
+            return Ok(None);
+        }
+
+        // Códigos de verificação do agendamento sem cadastro guardam o telefone (e os jobs de envio, o destino)
+        let digits: String = user.phone.chars().filter(char::is_ascii_digit).collect();
+        let verifications = diesel::delete(guest_verifications::table.filter(guest_verifications::phone.eq(digits)))
+            .returning(guest_verifications::id)
+            .get_results::<Uuid>(conn)?;
+        let code_jobs = background_jobs::table
+            .filter(background_jobs::kind.eq(guest_service::GUEST_CODE_JOB))
+            .filter(background_jobs::reference_id.eq_any(&verifications))
+            .select((background_jobs::id, background_jobs::payload))
+            .load::<(Uuid, Value)>(conn)?;
+        for (job_id, payload) in code_jobs {
+            diesel::update(background_jobs::table.find(job_id))
+                .set(background_jobs::payload.eq(mask_personal(payload)))
+                .execute(conn)?;
+        }
+
+        diesel::update(users::table.find(user_id))
+            .set((
+                users::name.eq(ERASED_NAME),
+                users::phone.eq(format!("erased:{}", user_id)),
+                users::password_hash.eq("!"), // Não é um hash válido: nenhuma senha confere
+                users::sms_verified.eq(false),
+                users::deleted_at.eq(user.deleted_at.unwrap_or(now)),
+                users::anonymized_at.eq(now),
+            ))
+            .execute(conn)?;
+        diesel::update(clients::table.find(user_id))
+            .set((clients::name.eq(ERASED_NAME), clients::phone.eq(format!("erased:{}", user_id)), clients::email.eq(None::<String>)))
+            .execute(conn)?;
+        diesel::update(professionals::table.filter(professionals::user_id.eq(user_id)))
+            .set((professionals::bio.eq(None::<String>), professionals::deleted_at.eq(now)))
+            .execute(conn)?;
+
+        diesel::delete(notification_preferences::table.find(user_id)).execute(conn)?;
+        diesel::delete(client_profiles::table.find(user_id)).execute(conn)?;
+        diesel::delete(client_notes::table.filter(client_notes::client_id.eq(user_id))).execute(conn)?;
+        diesel::delete(app_passwords::table.filter(app_passwords::user_id.eq(user_id))).execute(conn)?;
+        diesel::delete(calendar_feed_tokens::table.filter(calendar_feed_tokens::user_id.eq(user_id))).execute(conn)?;
+        // Respostas guardadas por `Idempotency-Key` podem conter o cadastro
+        diesel::delete(idempotency_keys::table.filter(idempotency_keys::user_id.eq(user_id))).execute(conn)?;
+
+        let upcoming = diesel::update(
+            appointments::table
+                .filter(appointments::client_id.eq(user_id))
+                .filter(appointments::appointment_time.ge(local_now))
+                .filter(appointments::status.ne("canceled")),
+        )
+        .set((appointments::status.eq("canceled"), appointments::sequence.eq(appointments::sequence + 1)))
+        .get_results::<Appointment>(conn)?;
+        for appointment in &upcoming {
+            job_queue::cancel_pending(conn, reminder_service::REMINDER_JOB, appointment.id)?;
+        }
+
+        redact_events(conn, user_id)?;
+        let redacted_audit_events = redact_audit_trail(conn, user_id)?;
+
+        Ok(Some(Erasure {
+            user_id,
+            anonymized_at: now,
+            canceled_appointments: upcoming.len(),
+            redacted_audit_events,
+            canceled: upcoming,
+        }))
+    })
+}
+
+/// 🔹 Eventos do outbox sobre o titular: os de agendamento/reserva (`user_id`) e o `user.registered` (`id`)
+fn about_subject(user_id: Uuid) -> Box<dyn BoxableExpression<outbox_events::table, Pg, SqlType = Bool>> {
+    let id = user_id.to_string();
+    Box::new(
+        outbox_events::payload
+            .retrieve_as_text("user_id")
+            .eq(id.clone())
+            .or(outbox_events::event_type.eq("user.registered").and(outbox_events::payload.retrieve_as_text("id").eq(id))),
+    )
+}
+
+/// 🔹 Mascara os dados pessoais do titular nos eventos do outbox e nas entregas de webhooks (o corpo fica em `data`)
+fn redact_events(conn: &mut PgConnection, user_id: Uuid) -> QueryResult<()> {
+    let events = outbox_events::table
+        .filter(about_subject(user_id))
+        .select((outbox_events::id, outbox_events::payload))
+        .load::<(Uuid, Value)>(conn)?;
+    for (event_id, payload) in events {
+        let masked = mask_personal(payload.clone());
+        if masked != payload {
+            diesel::update(outbox_events::table.find(event_id)).set(outbox_events::payload.eq(masked)).execute(conn)?;
+        }
+    }
+
+    let id = user_id.to_string();
+    let data = webhook_deliveries::payload.retrieve_as_object("data");
+    let deliveries = webhook_deliveries::table
+        .filter(
+            data.retrieve_as_text("user_id")
+                .eq(id.clone())
+                .or(webhook_deliveries::event_type.eq("user.registered").and(data.retrieve_as_text("id").eq(id))),
+        )
+        .select((webhook_deliveries::id, webhook_deliveries::payload))
+        .load::<(Uuid, Value)>(conn)?;
+    for (delivery_id, mut payload) in deliveries {
+        let Some(data) = payload.get_mut("data") else { continue };
+        let masked = mask_personal(data.clone());
+        if masked != *data {
+            *data = masked;
+            diesel::update(webhook_deliveries::table.find(delivery_id)).set(webhook_deliveries::payload.eq(payload)).execute(conn)?;
+        }
+    }
+    Ok(())
+}
+
+/// 🔹 Mascara os dados pessoais do titular na trilha (valores do cadastro e IPs das suas ações).
+/// O trigger de `audit_events` só aceita essa alteração com `app.audit_erasure` ligado na transação.
+fn redact_audit_trail(conn: &mut PgConnection, user_id: Uuid) -> QueryResult<usize> {
+    diesel::sql_query("SET LOCAL app.audit_erasure = 'on'").execute(conn)?;
+
+    let subject = audit_events::table
+        .filter(audit_events::entity_type.eq_any(SUBJECT_ENTITIES))
+        .filter(audit_events::entity_id.eq(user_id))
+        .select((audit_events::id, audit_events::before, audit_events::after))
+        .load::<(Uuid, Option<Value>, Option<Value>)>(conn)?;
+    let mut redacted = 0;
+    for (event_id, before, after) in subject {
+        let (masked_before, masked_after) = (before.clone().map(mask_personal), after.clone().map(mask_personal));
+        if masked_before != before || masked_after != after {
+            diesel::update(audit_events::table.find(event_id))
+                .set((audit_events::before.eq(masked_before), audit_events::after.eq(masked_after)))
+                .execute(conn)?;
+            redacted += 1;
+        }
+    }
+
+    redacted += diesel::update(audit_events::table.filter(audit_events::actor_id.eq(user_id)).filter(audit_events::ip.is_not_null()))
+        .set(audit_events::ip.eq(None::<String>))
+        .execute(conn)?;
+    Ok(redacted)
+}
+
+/// 🔹 Troca o valor dos campos pessoais por `[erased]` (mantém a chave: a trilha ainda mostra o que mudou)
+fn mask_personal(mut value: Value) -> Value {
+    if let Value::Object(fields) = &mut value {
+        for (field, value) in fields.iter_mut() {
+            if PERSONAL_FIELDS.contains(&field.as_str()) && !value.is_null() {
+                *value = Value::String(ERASED.to_string());
+            }
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{DomainEvent, EventKind};
+    use crate::schema::webhook_subscriptions;
+    use crate::services::{guest_service::Started, outbox};
+    use crate::test_db::{self, fixtures};
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+
+    #[test]
+    fn test_erase_redacts_events_deliveries_and_jobs() {
+        let Some(db) = test_db::setup() else { return };
+        let mut conn = db.pool.get().unwrap();
+        let now = Utc::now().naive_utc();
+
+        let client = fixtures::user(&mut conn, "client", "5511900000050");
+        let registered = json!({ "id": client, "name": "Maria Silva", "phone": "5511900000050", "role": "client" });
+        outbox::record(&mut conn, "user.registered", registered.clone()).unwrap();
+        let professional = fixtures::professional(&mut conn, "5511900000051");
+        let service = fixtures::service(&mut conn, 30);
+        let upcoming = fixtures::appointment(&mut conn, client, professional, service, now + Duration::days(3));
+        let appointment = appointments::table.find(upcoming).first::<Appointment>(&mut conn).unwrap();
+        outbox::record_event(&mut conn, &DomainEvent::from_appointment(EventKind::Created, &appointment, "Corte")).unwrap();
+
+        let subscription = diesel::insert_into(webhook_subscriptions::table)
+            .values((
+                webhook_subscriptions::url.eq("https://exemplo.com/hook"),
+                webhook_subscriptions::event_types.eq(vec!["user.registered".to_string()]),
+                webhook_subscriptions::secret.eq("segredo"),
+            ))
+            .returning(webhook_subscriptions::id)
+            .get_result::<Uuid>(&mut conn)
+            .unwrap();
+        diesel::insert_into(webhook_deliveries::table)
+            .values((
+                webhook_deliveries::subscription_id.eq(subscription),
+                webhook_deliveries::event_type.eq("user.registered"),
+                webhook_deliveries::payload.eq(json!({ "id": Uuid::new_v4(), "type": "user.registered", "data": registered })),
+            ))
+            .execute(&mut conn)
+            .unwrap();
+        let Started::Sent(verification) =
+            guest_service::start_verification(&mut conn, "+55 11 90000-0050", "5511900000050", None, now).unwrap()
+        else {
+            panic!("primeiro pedido não deveria ser limitado");
+        };
+
+        // A exportação acha o `user.registered` (chave `id`) e o evento do agendamento (chave `user_id`)
+        let exported = export(&mut conn, client, now).unwrap();
+        let mut types: Vec<_> = exported.notifications.iter().map(|event| event.event_type.as_str()).collect();
+        types.sort();
+        assert_eq!(types, ["appointment.created", "user.registered"]);
+
+        let erasure = erase(&mut conn, client, now, now).unwrap().unwrap();
+        assert_eq!(erasure.canceled_appointments, 1);
+
+        let registered = outbox_events::table
+            .filter(outbox_events::event_type.eq("user.registered"))
+            .select(outbox_events::payload)
+            .first::<Value>(&mut conn)
+            .unwrap();
+        assert_eq!(registered["name"], "[erased]");
+        assert_eq!(registered["phone"], "[erased]");
+        assert_eq!(registered["id"], json!(client));
+        let delivered = webhook_deliveries::table.select(webhook_deliveries::payload).first::<Value>(&mut conn).unwrap();
+        assert_eq!(delivered["data"]["name"], "[erased]");
+        assert_eq!(delivered["data"]["phone"], "[erased]");
+        let job = background_jobs::table
+            .filter(background_jobs::reference_id.eq(verification.id))
+            .select(background_jobs::payload)
+            .first::<Value>(&mut conn)
+            .unwrap();
+        assert_eq!(job["to"], "[erased]");
+        assert_eq!(guest_verifications::table.count().get_result::<i64>(&mut conn).unwrap(), 0);
+
+        let erased = users::table.find(client).first::<User>(&mut conn).unwrap();
+        assert_eq!(erased.name, ERASED_NAME);
+        assert!(erased.anonymized_at.is_some());
+        assert!(erase(&mut conn, client, now, now).unwrap().is_none()); // Segunda vez não faz nada
+    }
+
+    #[test]
+    fn test_mask_personal_keeps_keys_and_other_fields() {
+        let masked = mask_personal(json!({ "name": "Ana", "phone": "+5511999999999", "role": "client", "bio": null }));
+        assert_eq!(masked, json!({ "name": "[erased]", "phone": "[erased]", "role": "client", "bio": null }));
+    }
+}
@@ -0,0 +1,235 @@
+use async_trait::async_trait;
+use chrono::{Duration as ChronoDuration, FixedOffset, NaiveDateTime};
+use diesel::prelude::*;
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::models::appointment::Appointment;
+use crate::models::attendance_confirmation::{AttendanceConfirmation, NewAttendanceConfirmation};
+use crate::models::background_job::BackgroundJob;
+use crate::notifications::{self, templates, Notifier};
+use crate::schema::{appointments, attendance_confirmations, salon_settings, services};
+use crate::routes::v1;
+use crate::services::job_queue::{self, JobHandler};
+
+pub const REMINDER_JOB: &str = "appointment_reminder";
+
+/// 🔹 Antecedência padrão (horas) quando o profissional não configurou o salão
+const DEFAULT_OFFSETS_HOURS: [i32; 2] = [24, 2];
+
+/// 🔹 Status que ainda recebem lembretes
+const ACTIVE_STATUSES: [&str; 2] = ["pending", "confirmed"];
+
+/// 🔹 Payload do job: o horário permite descartar lembretes de uma marcação antiga
+#[derive(Debug, Serialize, Deserialize)]
+struct ReminderPayload {
+    appointment_id: Uuid,
+    appointment_time: NaiveDateTime,
+    offset_hours: i32,
+}
+
+/// ✅ Horários (UTC) dos lembretes ainda no futuro; `appointment_time` está no fuso do salão
+pub fn reminder_times(
+    appointment_time: NaiveDateTime,
+    offsets_hours: &[i32],
+    utc_offset: FixedOffset,
+    now: NaiveDateTime,
+) -> Vec<(i32, NaiveDateTime)> {
+    let starts_at_utc = appointment_time - ChronoDuration::seconds(utc_offset.local_minus_utc().into());
+
+    let mut offsets = offsets_hours.to_vec();
+    offsets.sort_unstable();
+    offsets.dedup();
+
+    offsets
+        .into_iter()
+        .map(|hours| (hours, starts_at_utc - ChronoDuration::hours(hours.into())))
+        .filter(|(_, run_at)| *run_at > now)
+        .collect()
+}
+
+/// ✅ (Re)agenda os lembretes do agendamento: cancela os pendentes e cria novos se ele ainda estiver ativo
+pub fn schedule_reminders(
+    conn: &mut PgConnection,
+    appointment: &Appointment,
+    utc_offset: FixedOffset,
+    now: NaiveDateTime,
+) -> QueryResult<usize> {
+    job_queue::cancel_pending(conn, REMINDER_JOB, appointment.id)?;
+    if !ACTIVE_STATUSES.contains(&appointment.status.as_str()) {
+        return Ok(0);
+    }
+
+    let offsets = salon_settings::table
+        .filter(salon_settings::professional_id.eq(appointment.professional_id))
+        .select(salon_settings::reminder_offsets_hours)
+        .first::<Vec<i32>>(conn)
+        .optional()?
+        .unwrap_or_else(|| DEFAULT_OFFSETS_HOURS.to_vec());
+
+    diesel::insert_into(attendance_confirmations::table)
+        .values(&NewAttendanceConfirmation {
+            appointment_id: appointment.id,
+            token: generate_token(),
+        })
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+
+    let times = reminder_times(appointment.appointment_time, &offsets, utc_offset, now);
+    for (offset_hours, run_at) in &times {
+        let payload = ReminderPayload {
+            appointment_id: appointment.id,
+            appointment_time: appointment.appointment_time,
+            offset_hours: *offset_hours,
+        };
+        job_queue::enqueue(conn, REMINDER_JOB, serde_json::json!(payload), Some(appointment.id), *run_at)?;
+    }
+
+    Ok(times.len())
+}
+
+/// ✅ Remarcação: a presença confirmada para o horário antigo deixa de valer
+pub fn reset_attendance(conn: &mut PgConnection, appointment_id: Uuid) -> QueryResult<usize> {
+    diesel::update(attendance_confirmations::table.find(appointment_id))
+        .set(attendance_confirmations::confirmed_at.eq(None::<NaiveDateTime>))
+        .execute(conn)
+}
+
+/// ✅ Confirma a presença; agendamentos "pending" passam a "confirmed".
+/// Devolve o agendamento e se o status mudou (`None` se não houver agendamento ativo).
+pub fn confirm_attendance(
+    conn: &mut PgConnection,
+    appointment_id: Uuid,
+    now: NaiveDateTime,
+) -> QueryResult<Option<(Appointment, bool)>> {
+    conn.transaction(|conn| {
+        let Some(appointment) = appointments::table
+            .find(appointment_id)
+            .filter(appointments::status.eq_any(ACTIVE_STATUSES))
+            .filter(appointments::deleted_at.is_null())
+            .for_update()
+            .first::<Appointment>(conn)
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        diesel::update(attendance_confirmations::table.find(appointment_id))
+            .set(attendance_confirmations::confirmed_at.eq(now))
+            .execute(conn)?;
+
+        if appointment.status == "confirmed" {
+            return Ok(Some((appointment, false)));
+        }
+        let updated = diesel::update(appointments::table.find(appointment_id))
+            .set(appointments::status.eq("confirmed"))
+            .get_result::<Appointment>(conn)?;
+        Ok(Some((updated, true)))
+    })
+}
+
+/// 🔹 Executor dos lembretes agendados
+pub struct ReminderJob {
+    pool: Arc<Pool>,
+    notifiers: Vec<Arc<dyn Notifier>>,
+    public_base_url: String,
+}
+
+impl ReminderJob {
+    pub fn new(pool: Arc<Pool>, notifiers: Vec<Arc<dyn Notifier>>, public_base_url: String) -> Self {
+        Self { pool, notifiers, public_base_url }
+    }
+}
+
+#[async_trait]
+impl JobHandler for ReminderJob {
+    fn kind(&self) -> &'static str {
+        REMINDER_JOB
+    }
+
+    async fn run(&self, job: &BackgroundJob) -> Result<(), String> {
+        let payload: ReminderPayload = serde_json::from_value(job.payload.clone()).map_err(|e| e.to_string())?;
+
+        let pool = self.pool.clone();
+        let appointment_id = payload.appointment_id;
+        let loaded = tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get().map_err(|e| e.to_string())?;
+            appointments::table
+                .inner_join(services::table)
+                .inner_join(attendance_confirmations::table)
+                .filter(appointments::id.eq(appointment_id))
+                .filter(appointments::deleted_at.is_null())
+                .select((appointments::all_columns, services::nome, AttendanceConfirmation::as_select()))
+                .first::<(Appointment, String, AttendanceConfirmation)>(&mut conn)
+                .optional()
+                .map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+        // Agendamento removido, cancelado ou remarcado depois do enfileiramento: nada a enviar
+        let Some((appointment, service, attendance)) = loaded else {
+            return Ok(());
+        };
+        if appointment.appointment_time != payload.appointment_time || !ACTIVE_STATUSES.contains(&appointment.status.as_str()) {
+            return Ok(());
+        }
+
+        let confirm_url = format!("{}{}/attendance/{}", self.public_base_url.trim_end_matches('/'), v1::PREFIX, attendance.token);
+        let label = format!("lembrete de {}h", payload.offset_hours);
+        let delivery = notifications::notify_user(self.pool.clone(), &self.notifiers, appointment.client_id, &label, |locale, name| {
+            templates::render_reminder(locale, name, &service, appointment.appointment_time, &confirm_url)
+        })
+        .await?;
+
+        // Só tenta de novo se nenhum canal funcionou (evita lembretes duplicados)
+        if delivery.sent == 0 && delivery.failed > 0 {
+            return Err(format!("Lembrete não entregue em nenhum dos {} canais", delivery.failed));
+        }
+        Ok(())
+    }
+}
+
+/// 🔹 32 bytes aleatórios em hexadecimal
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// ✅ Uma resposta por SMS/WhatsApp confirma presença? ("SIM", "sim!", "Yes", "1"...)
+pub fn is_confirmation_reply(text: &str) -> bool {
+    let normalized: String = text
+        .trim()
+        .trim_end_matches(['.', '!'])
+        .to_lowercase();
+    matches!(normalized.as_str(), "sim" | "s" | "yes" | "y" | "si" | "sí" | "1" | "confirmo" | "ok")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_reminder_times() {
+        let appointment_time = NaiveDate::from_ymd_opt(2025, 5, 10).unwrap().and_hms_opt(14, 0, 0).unwrap();
+        let brt = FixedOffset::west_opt(3 * 3600).unwrap();
+        // 14:00 em -03:00 = 17:00 UTC
+        let now = NaiveDate::from_ymd_opt(2025, 5, 10).unwrap().and_hms_opt(0, 0, 0).unwrap();
+
+        let times = reminder_times(appointment_time, &[2, 24, 2], brt, now);
+        assert_eq!(times, vec![(2, NaiveDate::from_ymd_opt(2025, 5, 10).unwrap().and_hms_opt(15, 0, 0).unwrap())]);
+    }
+
+    #[test]
+    fn test_is_confirmation_reply() {
+        assert!(is_confirmation_reply(" SIM! "));
+        assert!(is_confirmation_reply("Yes"));
+        assert!(!is_confirmation_reply("não"));
+        assert!(!is_confirmation_reply("cancelar"));
+    }
+}
@@ -0,0 +1,264 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::schema::{appointments, availabilities, services};
+
+/// 🔹 Agrupamento dos resultados
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    #[default]
+    Day,
+    Week,  // Semanas começam na segunda-feira
+    Month,
+}
+
+impl GroupBy {
+    /// ✅ Primeiro dia do período que contém a data
+    pub fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            GroupBy::Day => date,
+            GroupBy::Week => date - Duration::days(date.weekday().num_days_from_monday().into()),
+            GroupBy::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
+
+/// 🔹 Filtros do relatório (`to` inclusivo)
+#[derive(Debug, Clone)]
+pub struct ReportFilter {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub professional_id: Option<Uuid>,
+    pub service_id: Option<Uuid>,
+}
+
+/// 🔹 Agendamento com os dados do serviço usados nas métricas
+#[derive(Debug, Clone, Queryable)]
+pub struct BookedAppointment {
+    pub appointment_time: NaiveDateTime,
+    pub status: String,
+    pub preco: f64,
+    pub duracao_min: i32,
+}
+
+/// 🔹 Janela de disponibilidade de um profissional
+#[derive(Debug, Clone, Queryable)]
+pub struct AvailableWindow {
+    pub date: NaiveDate,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+}
+
+/// 🔹 Métricas de um período (ou do intervalo inteiro, nos totais)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ReportMetrics {
+    pub appointments: i64,            // Todos os agendamentos do período, em qualquer status
+    pub completed: i64,
+    pub canceled: i64,
+    pub no_show: i64,
+    pub revenue: f64,                 // Soma de `services.preco` dos concluídos
+    pub average_ticket: f64,          // Receita / concluídos
+    pub cancellation_rate: f64,       // Cancelados / agendamentos
+    pub no_show_rate: f64,            // Faltas / agendamentos
+    pub booked_minutes: i64,          // Duração dos agendamentos não cancelados
+    pub available_minutes: i64,       // Duração das disponibilidades
+    pub utilization: Option<f64>,     // Minutos agendados / minutos disponíveis (sem disponibilidade: null)
+}
+
+/// 🔹 Linha do relatório
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportPeriod {
+    pub period: NaiveDate, // Primeiro dia do período
+    #[serde(flatten)]
+    pub metrics: ReportMetrics,
+}
+
+/// 🔹 Relatório completo
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub group_by: GroupBy,
+    pub professional_id: Option<Uuid>,
+    pub service_id: Option<Uuid>,
+    pub periods: Vec<ReportPeriod>,
+    pub totals: ReportMetrics,
+}
+
+/// ✅ Carrega os dados do intervalo e calcula o relatório
+pub fn generate(conn: &mut PgConnection, filter: &ReportFilter, group_by: GroupBy) -> QueryResult<Report> {
+    let range_start = filter.from.and_time(NaiveTime::MIN);
+    let range_end = (filter.to + Duration::days(1)).and_time(NaiveTime::MIN);
+
+    let mut booked = appointments::table
+        .inner_join(services::table)
+        .filter(appointments::appointment_time.ge(range_start))
+        .filter(appointments::appointment_time.lt(range_end))
+        .filter(appointments::deleted_at.is_null())  // Serviços/profissionais excluídos continuam contando no histórico
+        .select((appointments::appointment_time, appointments::status, services::preco, services::duracao_min))
+        .into_boxed();
+    if let Some(professional_id) = filter.professional_id {
+        booked = booked.filter(appointments::professional_id.eq(professional_id));
+    }
+    if let Some(service_id) = filter.service_id {
+        booked = booked.filter(appointments::service_id.eq(service_id));
+    }
+    let booked = booked.load::<BookedAppointment>(conn)?;
+
+    let mut windows = availabilities::table
+        .filter(availabilities::date.ge(filter.from))
+        .filter(availabilities::date.le(filter.to))
+        .select((availabilities::date, availabilities::start_time, availabilities::end_time))
+        .into_boxed();
+    if let Some(professional_id) = filter.professional_id {
+        windows = windows.filter(availabilities::professional_id.eq(professional_id));
+    }
+    let windows = windows.load::<AvailableWindow>(conn)?;
+
+    let (periods, totals) = aggregate(&booked, &windows, group_by);
+    Ok(Report {
+        from: filter.from,
+        to: filter.to,
+        group_by,
+        professional_id: filter.professional_id,
+        service_id: filter.service_id,
+        periods,
+        totals,
+    })
+}
+
+/// ✅ Agrupa por período e calcula as métricas (períodos sem dados não aparecem)
+pub fn aggregate(booked: &[BookedAppointment], windows: &[AvailableWindow], group_by: GroupBy) -> (Vec<ReportPeriod>, ReportMetrics) {
+    let mut periods: BTreeMap<NaiveDate, ReportMetrics> = BTreeMap::new();
+    let mut totals = ReportMetrics::default();
+
+    for appointment in booked {
+        let period = periods.entry(group_by.period_start(appointment.appointment_time.date())).or_default();
+        for metrics in [&mut *period, &mut totals] {
+            metrics.appointments += 1;
+            match appointment.status.as_str() {
+                "completed" => {
+                    metrics.completed += 1;
+                    metrics.revenue += appointment.preco;
+                }
+                "canceled" => metrics.canceled += 1,
+                "no_show" => metrics.no_show += 1,
+                _ => {}
+            }
+            if appointment.status != "canceled" {
+                metrics.booked_minutes += i64::from(appointment.duracao_min);
+            }
+        }
+    }
+
+    for window in windows {
+        let minutes = (window.end_time - window.start_time).num_minutes().max(0);
+        periods.entry(group_by.period_start(window.date)).or_default().available_minutes += minutes;
+        totals.available_minutes += minutes;
+    }
+
+    let periods = periods
+        .into_iter()
+        .map(|(period, metrics)| ReportPeriod { period, metrics: finish(metrics) })
+        .collect();
+    (periods, finish(totals))
+}
+
+/// 🔹 Calcula as razões a partir das contagens
+fn finish(mut metrics: ReportMetrics) -> ReportMetrics {
+    let ratio = |part: f64, whole: f64| if whole > 0.0 { part / whole } else { 0.0 };
+    metrics.average_ticket = ratio(metrics.revenue, metrics.completed as f64);
+    metrics.cancellation_rate = ratio(metrics.canceled as f64, metrics.appointments as f64);
+    metrics.no_show_rate = ratio(metrics.no_show as f64, metrics.appointments as f64);
+    metrics.utilization = (metrics.available_minutes > 0).then(|| metrics.booked_minutes as f64 / metrics.available_minutes as f64);
+    metrics
+}
+
+/// ✅ Relatório em CSV: uma linha por período e uma linha final `total`
+pub fn to_csv(report: &Report) -> String {
+    let mut csv = String::from(
+        "period,appointments,completed,canceled,no_show,revenue,average_ticket,cancellation_rate,no_show_rate,booked_minutes,available_minutes,utilization\n",
+    );
+    let rows = report
+        .periods
+        .iter()
+        .map(|period| (period.period.to_string(), &period.metrics))
+        .chain(std::iter::once(("total".to_string(), &report.totals)));
+
+    for (label, metrics) in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{:.2},{:.2},{:.4},{:.4},{},{},{}\n",
+            label,
+            metrics.appointments,
+            metrics.completed,
+            metrics.canceled,
+            metrics.no_show,
+            metrics.revenue,
+            metrics.average_ticket,
+            metrics.cancellation_rate,
+            metrics.no_show_rate,
+            metrics.booked_minutes,
+            metrics.available_minutes,
+            metrics.utilization.map(|utilization| format!("{:.4}", utilization)).unwrap_or_default(),
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn booked(day: u32, status: &str, preco: f64) -> BookedAppointment {
+        BookedAppointment {
+            appointment_time: NaiveDate::from_ymd_opt(2025, 5, day).unwrap().and_hms_opt(10, 0, 0).unwrap(),
+            status: status.to_string(),
+            preco,
+            duracao_min: 60,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_by_week() {
+        // 05/05/2025 é segunda-feira; 12/05 começa a semana seguinte
+        let appointments = vec![
+            booked(5, "completed", 50.0),
+            booked(6, "completed", 70.0),
+            booked(7, "canceled", 50.0),
+            booked(8, "no_show", 50.0),
+            booked(12, "pending", 50.0),
+        ];
+        let windows = vec![AvailableWindow {
+            date: NaiveDate::from_ymd_opt(2025, 5, 6).unwrap(),
+            start_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            end_time: NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+        }];
+
+        let (periods, totals) = aggregate(&appointments, &windows, GroupBy::Week);
+        assert_eq!(periods.len(), 2);
+
+        let first = &periods[0];
+        assert_eq!(first.period, NaiveDate::from_ymd_opt(2025, 5, 5).unwrap());
+        assert_eq!((first.metrics.appointments, first.metrics.completed), (4, 2));
+        assert_eq!(first.metrics.revenue, 120.0);
+        assert_eq!(first.metrics.average_ticket, 60.0);
+        assert_eq!(first.metrics.cancellation_rate, 0.25);
+        assert_eq!(first.metrics.booked_minutes, 180);
+        assert_eq!(first.metrics.utilization, Some(0.75));
+
+        assert_eq!(periods[1].metrics.utilization, None);
+        assert_eq!(totals.appointments, 5);
+        assert_eq!(totals.no_show_rate, 0.2);
+    }
+
+    #[test]
+    fn test_period_start() {
+        let date = NaiveDate::from_ymd_opt(2025, 5, 15).unwrap();
+        assert_eq!(GroupBy::Week.period_start(date), NaiveDate::from_ymd_opt(2025, 5, 12).unwrap());
+        assert_eq!(GroupBy::Month.period_start(date), NaiveDate::from_ymd_opt(2025, 5, 1).unwrap());
+    }
+}
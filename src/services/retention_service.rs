@@ -0,0 +1,153 @@
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use diesel::dsl::{exists, not};
+use diesel::prelude::*;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::db::Pool;
+use crate::jobs::JobRegistry;
+use crate::schema::{appointments, professionals, services, users};
+use crate::shutdown::Shutdown;
+
+const PURGE_JOB: &str = "soft_delete_purge";
+
+/// 🔹 Registros removidos de vez em uma execução
+#[derive(Debug, Default, PartialEq)]
+pub struct Purged {
+    pub appointments: usize,
+    pub services: usize,
+    pub professionals: usize,
+    pub users: usize,
+}
+
+impl Purged {
+    fn total(&self) -> usize {
+        self.appointments + self.services + self.professionals + self.users
+    }
+}
+
+/// ✅ Remove de vez o que foi excluído logicamente antes de `cutoff`.
+/// Serviços, profissionais e usuários ainda referenciados por agendamentos ficam (o `ON DELETE CASCADE`
+/// apagaria o histórico); saem numa execução futura, depois que os agendamentos excluídos forem removidos.
+pub fn purge_deleted(conn: &mut PgConnection, cutoff: NaiveDateTime) -> QueryResult<Purged> {
+    conn.transaction(|conn| {
+        let appointments = diesel::delete(appointments::table.filter(appointments::deleted_at.lt(cutoff))).execute(conn)?;
+
+        let services = diesel::delete(
+            services::table
+                .filter(services::deleted_at.lt(cutoff))
+                .filter(not(exists(appointments::table.filter(appointments::service_id.eq(services::id))))),
+        )
+        .execute(conn)?;
+
+        let professionals = diesel::delete(
+            professionals::table
+                .filter(professionals::deleted_at.lt(cutoff))
+                .filter(not(exists(appointments::table.filter(appointments::professional_id.eq(professionals::id))))),
+        )
+        .execute(conn)?;
+
+        let users = diesel::delete(
+            users::table
+                .filter(users::deleted_at.lt(cutoff))
+                .filter(not(exists(professionals::table.filter(professionals::user_id.eq(users::id)))))
+                .filter(not(exists(appointments::table.filter(appointments::client_id.eq(users::id))))),
+        )
+        .execute(conn)?;
+
+        Ok(Purged { appointments, services, professionals, users })
+    })
+}
+
+/// ✅ Worker que aplica a retenção (`SOFT_DELETE_RETENTION_DAYS`) a cada `every`
+pub fn spawn_purge(
+    pool: Arc<Pool>,
+    retention_days: i64,
+    every: Duration,
+    jobs: JobRegistry,
+    shutdown: Shutdown,
+) -> tokio::task::JoinHandle<()> {
+    jobs.register(PURGE_JOB, every);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let pool = pool.clone();
+                    let purged = tokio::task::spawn_blocking(move || {
+                        let mut conn = pool.get().map_err(|e| e.to_string())?;
+                        let cutoff = Utc::now().naive_utc() - ChronoDuration::days(retention_days);
+                        purge_deleted(&mut conn, cutoff).map_err(|e| e.to_string())
+                    })
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|purged| purged);
+                    match purged {
+                        Ok(purged) => {
+                            if purged.total() > 0 {
+                                info!("🧹 Registros excluídos removidos após a retenção: {:?}", purged);
+                            }
+                            jobs.heartbeat(PURGE_JOB);
+                        }
+                        Err(e) => {
+                            warn!("⚠️ Falha ao remover registros excluídos: {}", e);
+                            jobs.failed(PURGE_JOB, &e);
+                        }
+                    }
+                }
+                _ = shutdown.wait() => break,
+            }
+        }
+
+        jobs.stopped(PURGE_JOB);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db::{self, fixtures};
+
+    #[test]
+    fn test_purge_deleted_respects_cutoff_and_references() {
+        let Some(db) = test_db::setup() else { return };
+        let mut conn = db.pool.get().unwrap();
+        let now = Utc::now().naive_utc();
+        let old = now - ChronoDuration::days(40);
+        let cutoff = now - ChronoDuration::days(30);
+
+        let client = fixtures::user(&mut conn, "client", "5511900000001");
+        let professional = fixtures::professional(&mut conn, "5511900000002");
+        let kept_service = fixtures::service(&mut conn, 30);
+        let unused_service = fixtures::service(&mut conn, 30);
+        let purged = fixtures::appointment(&mut conn, client, professional, kept_service, now);
+        let recent = fixtures::appointment(&mut conn, client, professional, kept_service, now);
+
+        diesel::update(appointments::table.find(purged)).set(appointments::deleted_at.eq(old)).execute(&mut conn).unwrap();
+        diesel::update(appointments::table.find(recent)).set(appointments::deleted_at.eq(now)).execute(&mut conn).unwrap();
+        diesel::update(services::table.filter(services::id.eq_any([kept_service, unused_service])))
+            .set(services::deleted_at.eq(old))
+            .execute(&mut conn)
+            .unwrap();
+        diesel::update(professionals::table.find(professional)).set(professionals::deleted_at.eq(old)).execute(&mut conn).unwrap();
+        diesel::update(users::table.find(client)).set(users::deleted_at.eq(old)).execute(&mut conn).unwrap();
+
+        // O agendamento excluído há pouco ainda segura o serviço, o profissional e o cliente
+        assert_eq!(
+            purge_deleted(&mut conn, cutoff).unwrap(),
+            Purged { appointments: 1, services: 1, professionals: 0, users: 0 }
+        );
+        assert!(services::table.find(kept_service).select(services::id).first::<uuid::Uuid>(&mut conn).is_ok());
+
+        // Passada a retenção dele também, o resto sai na execução seguinte
+        diesel::update(appointments::table.find(recent)).set(appointments::deleted_at.eq(old)).execute(&mut conn).unwrap();
+        assert_eq!(
+            purge_deleted(&mut conn, cutoff).unwrap(),
+            Purged { appointments: 1, services: 1, professionals: 1, users: 1 }
+        );
+        // O usuário do profissional não estava excluído
+        assert_eq!(users::table.count().get_result::<i64>(&mut conn).unwrap(), 1);
+    }
+}
@@ -0,0 +1,367 @@
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::models::background_job::BackgroundJob;
+use crate::models::outbox_event::OutboxEvent;
+use crate::models::webhook::{NewWebhookDelivery, WebhookDelivery, WebhookSubscription};
+use crate::schema::{webhook_deliveries, webhook_subscriptions};
+use crate::services::http_client::{self, HttpResponse};
+use crate::services::job_queue::{self, JobHandler};
+use crate::services::outbox::{self, OutboxConsumer};
+
+pub const DELIVERY_JOB: &str = "webhook_delivery";
+const CONSUMER_NAME: &str = "webhooks";
+
+/// 🔹 Eventos que podem ser assinados
+pub const EVENT_TYPES: &[&str] = &[
+    "appointment.created",
+    "appointment.confirmed",
+    "appointment.rescheduled",
+    "appointment.canceled",
+    "reservation.created",
+    "reservation.confirmed",
+    "reservation.rescheduled",
+    "reservation.canceled",
+    "user.registered",
+];
+
+/// 🔹 Evento de teste enviado pelo `POST /webhooks/:id/ping` (não precisa ser assinado)
+pub const PING_EVENT: &str = "webhook.ping";
+
+/// 🔹 Estados de `webhook_deliveries.status`
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_RETRYING: &str = "retrying";
+pub const STATUS_DELIVERED: &str = "delivered";
+pub const STATUS_FAILED: &str = "failed";
+
+/// 🔹 Parte da resposta do receptor guardada no log
+const RESPONSE_BODY_LIMIT: usize = 1000;
+
+/// 🔹 Payload do job: a entrega a (re)tentar
+#[derive(Debug, Serialize, Deserialize)]
+struct DeliveryPayload {
+    delivery_id: Uuid,
+}
+
+/// ✅ Segredo aleatório para assinar as entregas
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    format!("whsec_{}", to_hex(&bytes))
+}
+
+/// ✅ Assinatura HMAC-SHA256 (hex) de `"{timestamp}.{body}"`, enviada em `X-Webhook-Signature: sha256=...`
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC aceita chaves de qualquer tamanho");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    to_hex(&mac.finalize().into_bytes())
+}
+
+/// ✅ Registra uma entrega para cada assinatura ativa do evento do outbox e enfileira o envio
+/// (uma única vez por evento, mesmo que o relay o entregue de novo)
+pub fn emit(conn: &mut PgConnection, event_id: Uuid, event_type: &str, data: serde_json::Value) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        if !outbox::mark_consumed(conn, CONSUMER_NAME, event_id)? {
+            return Ok(0);
+        }
+
+        let subscriptions = webhook_subscriptions::table
+            .filter(webhook_subscriptions::active.eq(true))
+            .filter(webhook_subscriptions::event_types.contains(vec![event_type]))
+            .select(webhook_subscriptions::id)
+            .load::<Uuid>(conn)?;
+
+        let payload = envelope(event_id, event_type, data);
+        for subscription_id in &subscriptions {
+            queue_delivery(conn, *subscription_id, event_type, payload.clone())?;
+        }
+        Ok(subscriptions.len())
+    })
+}
+
+/// ✅ Entrega de teste para uma assinatura específica
+pub fn ping(conn: &mut PgConnection, subscription_id: Uuid) -> QueryResult<WebhookDelivery> {
+    conn.transaction(|conn| {
+        let payload = envelope(Uuid::new_v4(), PING_EVENT, serde_json::json!({ "subscription_id": subscription_id }));
+        queue_delivery(conn, subscription_id, PING_EVENT, payload)
+    })
+}
+
+/// ✅ Reenvia uma entrega (qualquer status): descarta tentativas pendentes e volta para a fila agora
+pub fn redeliver(conn: &mut PgConnection, delivery_id: Uuid) -> QueryResult<WebhookDelivery> {
+    conn.transaction(|conn| {
+        job_queue::cancel_pending(conn, DELIVERY_JOB, delivery_id)?;
+
+        let delivery = diesel::update(webhook_deliveries::table.find(delivery_id))
+            .set((
+                webhook_deliveries::status.eq(STATUS_PENDING),
+                webhook_deliveries::updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .get_result::<WebhookDelivery>(conn)?;
+
+        enqueue_attempt(conn, delivery.id)?;
+        Ok(delivery)
+    })
+}
+
+/// 🔹 Corpo das entregas; `id` é o ID do evento (o mesmo em todas as assinaturas e reentregas)
+fn envelope(event_id: Uuid, event_type: &str, data: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "id": event_id,
+        "type": event_type,
+        "created_at": Utc::now().naive_utc(),
+        "data": data,
+    })
+}
+
+fn queue_delivery(
+    conn: &mut PgConnection,
+    subscription_id: Uuid,
+    event_type: &str,
+    payload: serde_json::Value,
+) -> QueryResult<WebhookDelivery> {
+    let delivery = diesel::insert_into(webhook_deliveries::table)
+        .values(&NewWebhookDelivery {
+            subscription_id,
+            event_type: event_type.to_string(),
+            payload,
+        })
+        .get_result::<WebhookDelivery>(conn)?;
+
+    enqueue_attempt(conn, delivery.id)?;
+    Ok(delivery)
+}
+
+fn enqueue_attempt(conn: &mut PgConnection, delivery_id: Uuid) -> QueryResult<BackgroundJob> {
+    job_queue::enqueue(
+        conn,
+        DELIVERY_JOB,
+        serde_json::json!(DeliveryPayload { delivery_id }),
+        Some(delivery_id),
+        Utc::now().naive_utc(),
+    )
+}
+
+/// ✅ POST assinado para o receptor
+/// Cabeçalhos: `X-Webhook-Id` (entrega), `X-Webhook-Event`, `X-Webhook-Timestamp` (Unix) e `X-Webhook-Signature`.
+pub async fn send_signed(
+    url: &str,
+    secret: &str,
+    delivery_id: Uuid,
+    event_type: &str,
+    body: String,
+    timestamp: i64,
+) -> Result<HttpResponse, String> {
+    let headers = [
+        ("X-Webhook-Id", delivery_id.to_string()),
+        ("X-Webhook-Event", event_type.to_string()),
+        ("X-Webhook-Timestamp", timestamp.to_string()),
+        ("X-Webhook-Signature", format!("sha256={}", sign(secret, timestamp, &body))),
+    ];
+    http_client::post_json(url, &headers, body).await
+}
+
+/// ✅ Consumidor do outbox que transforma eventos em entregas de webhook
+pub struct WebhookConsumer {
+    pool: Arc<Pool>,
+}
+
+impl WebhookConsumer {
+    pub fn new(pool: Arc<Pool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OutboxConsumer for WebhookConsumer {
+    fn name(&self) -> &'static str {
+        CONSUMER_NAME
+    }
+
+    async fn handle(&self, event: &OutboxEvent) -> Result<(), String> {
+        let pool = self.pool.clone();
+        let (event_id, event_type, payload) = (event.id, event.event_type.clone(), event.payload.clone());
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get().map_err(|e| e.to_string())?;
+            emit(&mut conn, event_id, &event_type, payload).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        Ok(())
+    }
+}
+
+/// 🔹 Executor das entregas (as novas tentativas com backoff ficam a cargo da fila de jobs)
+pub struct WebhookJob {
+    pool: Arc<Pool>,
+}
+
+impl WebhookJob {
+    pub fn new(pool: Arc<Pool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl JobHandler for WebhookJob {
+    fn kind(&self) -> &'static str {
+        DELIVERY_JOB
+    }
+
+    async fn run(&self, job: &BackgroundJob) -> Result<(), String> {
+        let payload: DeliveryPayload = serde_json::from_value(job.payload.clone()).map_err(|e| e.to_string())?;
+
+        let pool = self.pool.clone();
+        let loaded = tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get().map_err(|e| e.to_string())?;
+            webhook_deliveries::table
+                .inner_join(webhook_subscriptions::table)
+                .filter(webhook_deliveries::id.eq(payload.delivery_id))
+                .select((WebhookDelivery::as_select(), WebhookSubscription::as_select()))
+                .first::<(WebhookDelivery, WebhookSubscription)>(&mut conn)
+                .optional()
+                .map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+        // Assinatura removida (entregas apagadas em cascata) ou desativada: nada a enviar
+        let Some((delivery, subscription)) = loaded else {
+            return Ok(());
+        };
+        if !subscription.active {
+            return Ok(());
+        }
+
+        let timestamp = Utc::now().timestamp();
+        let result = send_signed(
+            &subscription.url,
+            &subscription.secret,
+            delivery.id,
+            &delivery.event_type,
+            delivery.payload.to_string(),
+            timestamp,
+        )
+        .await;
+
+        let outcome = match &result {
+            Ok(response) if response.is_success() => Ok(()),
+            Ok(response) => Err(format!("Receptor respondeu {}", response.status)),
+            Err(e) => Err(e.clone()),
+        };
+        let status = match &outcome {
+            Ok(()) => STATUS_DELIVERED,
+            Err(_) if job.attempts >= job.max_attempts => STATUS_FAILED,
+            Err(_) => STATUS_RETRYING,
+        };
+        let response = result.ok();
+
+        let pool = self.pool.clone();
+        let error = outcome.clone().err();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get().map_err(|e| e.to_string())?;
+            record_attempt(&mut conn, delivery.id, status, response.as_ref(), error, Utc::now().naive_utc())
+                .map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+        match &outcome {
+            Ok(()) => info!("🪝 Webhook {} entregue para {}", delivery.event_type, subscription.url),
+            Err(e) => warn!("⚠️ Webhook {} para {} falhou ({}): {}", delivery.event_type, subscription.url, status, e),
+        }
+        outcome
+    }
+}
+
+fn record_attempt(
+    conn: &mut PgConnection,
+    delivery_id: Uuid,
+    status: &str,
+    response: Option<&HttpResponse>,
+    error: Option<String>,
+    now: NaiveDateTime,
+) -> QueryResult<usize> {
+    diesel::update(webhook_deliveries::table.find(delivery_id))
+        .set((
+            webhook_deliveries::status.eq(status),
+            webhook_deliveries::attempts.eq(webhook_deliveries::attempts + 1),
+            webhook_deliveries::response_status.eq(response.map(|r| i32::from(r.status))),
+            webhook_deliveries::response_body.eq(response.map(|r| r.body.chars().take(RESPONSE_BODY_LIMIT).collect::<String>())),
+            webhook_deliveries::last_error.eq(error),
+            webhook_deliveries::delivered_at.eq((status == STATUS_DELIVERED).then_some(now)),
+            webhook_deliveries::updated_at.eq(now),
+        ))
+        .execute(conn)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_sign_hmac_sha256() {
+        // RFC 4231, caso 2 (chave "Jefe"); aqui a mensagem é "{timestamp}.{body}"
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"Jefe").unwrap();
+        mac.update(b"what do ya want for nothing?");
+        assert_eq!(
+            to_hex(&mac.finalize().into_bytes()),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        assert_eq!(sign("segredo", 1700000000, "{}"), sign("segredo", 1700000000, "{}"));
+        assert_ne!(sign("segredo", 1700000000, "{}"), sign("segredo", 1700000001, "{}"));
+    }
+
+    #[tokio::test]
+    async fn test_signed_delivery_to_local_receiver() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+
+        let receiver = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            // Lê até o fim do corpo (o JSON do teste termina em "}}")
+            while !String::from_utf8_lossy(&request).ends_with("}}") {
+                let read = socket.read(&mut buffer).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            socket.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nok").await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let delivery_id = Uuid::new_v4();
+        let body = r#"{"type":"appointment.created","data":{"id":1}}"#.to_string();
+        let response = send_signed(&url, "segredo", delivery_id, "appointment.created", body.clone(), 1700000000)
+            .await
+            .unwrap();
+        assert!(response.is_success());
+
+        let request = receiver.await.unwrap().to_lowercase();
+        assert!(request.starts_with("post /hooks http/1.1"));
+        assert!(request.contains(&format!("x-webhook-id: {}", delivery_id)));
+        assert!(request.contains("x-webhook-event: appointment.created"));
+        assert!(request.contains("x-webhook-timestamp: 1700000000"));
+        assert!(request.contains(&format!("x-webhook-signature: sha256={}", sign("segredo", 1700000000, &body))));
+        assert!(request.ends_with(&body.to_lowercase()));
+    }
+}
@@ -0,0 +1,113 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::watch;
+use tracing::info;
+
+/// 🔹 Sinal de encerramento compartilhado entre servidor, readiness e workers
+/// Duas fases: `start_draining` só derruba o readiness; `trigger` para o servidor e os workers
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    sender: Arc<watch::Sender<bool>>,
+    draining: Arc<AtomicBool>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+            draining: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// ✅ Readiness passa a falhar (o balanceador tira a instância de rotação), mas o servidor segue atendendo
+    pub fn start_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    /// ✅ Inicia o encerramento do servidor e dos workers (idempotente; implica `start_draining`)
+    pub fn trigger(&self) {
+        self.start_draining();
+        self.sender.send_replace(true);
+    }
+
+    /// 🔹 Verdadeiro desde `start_draining` (usado pelo readiness)
+    pub fn is_shutting_down(&self) -> bool {
+        self.draining.load(Ordering::SeqCst) || *self.sender.borrow()
+    }
+
+    /// ✅ Aguarda até o encerramento ser iniciado
+    pub async fn wait(&self) {
+        let mut receiver = self.sender.subscribe();
+        // `wait_for` só falha se o sender for descartado, o que não ocorre enquanto `self` existir
+        let _ = receiver.wait_for(|stopping| *stopping).await;
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ✅ Aguarda SIGINT (Ctrl+C) ou SIGTERM
+pub async fn wait_for_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Falha ao instalar handler de Ctrl+C");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Falha ao instalar handler de SIGTERM")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("🛑 SIGINT recebido"),
+        _ = terminate => info!("🛑 SIGTERM recebido"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_trigger_wakes_waiters() {
+        let shutdown = Shutdown::new();
+        assert!(!shutdown.is_shutting_down());
+
+        let waiter = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.wait().await }
+        });
+
+        shutdown.trigger();
+        waiter.await.unwrap();
+        assert!(shutdown.is_shutting_down());
+
+        // ✅ Quem chega depois do sinal não fica bloqueado
+        shutdown.wait().await;
+    }
+
+    #[tokio::test]
+    async fn test_draining_does_not_stop_the_server() {
+        let shutdown = Shutdown::new();
+        shutdown.start_draining();
+        assert!(shutdown.is_shutting_down());
+
+        // Servidor e workers só param no `trigger`
+        let stopped = tokio::time::timeout(std::time::Duration::from_millis(20), shutdown.wait()).await;
+        assert!(stopped.is_err());
+
+        shutdown.trigger();
+        shutdown.wait().await;
+    }
+}
@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use diesel::r2d2::event::{CheckoutEvent, TimeoutEvent};
+use diesel::r2d2::HandleEvent;
+use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram, Unit};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+
+use crate::db::Pool;
+use crate::jobs::JobRegistry;
+use crate::shutdown::Shutdown;
+
+// 🔹 Nomes das métricas expostas em `/metrics`
+const HTTP_REQUESTS_TOTAL: &str = "http_requests_total";
+const HTTP_REQUEST_DURATION: &str = "http_request_duration_seconds";
+const DB_POOL_CONNECTIONS: &str = "db_pool_connections";
+const DB_POOL_IDLE_CONNECTIONS: &str = "db_pool_idle_connections";
+const DB_POOL_WAIT: &str = "db_pool_wait_seconds";
+const DB_POOL_TIMEOUTS_TOTAL: &str = "db_pool_timeouts_total";
+const APPOINTMENTS_CREATED_TOTAL: &str = "appointments_created_total";
+const APPOINTMENTS_CANCELED_TOTAL: &str = "appointments_canceled_total";
+const APPOINTMENTS_NO_SHOW_TOTAL: &str = "appointments_no_show_total";
+const LOGINS_TOTAL: &str = "logins_total";
+const RATE_LIMIT_REJECTIONS_TOTAL: &str = "rate_limit_rejections_total";
+const NOTIFICATIONS_TOTAL: &str = "notifications_total";
+
+/// 🔹 Intervalo da manutenção periódica dos histogramas do recorder
+const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+const UPKEEP_JOB: &str = "metrics_upkeep";
+
+/// 🔹 Buckets (em segundos) dos histogramas de latência
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// ✅ Instala o recorder Prometheus global e descreve as métricas
+pub fn init() -> Result<PrometheusHandle, String> {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Full(HTTP_REQUEST_DURATION.to_string()), LATENCY_BUCKETS)
+        .and_then(|builder| builder.set_buckets_for_metric(Matcher::Full(DB_POOL_WAIT.to_string()), LATENCY_BUCKETS))
+        .and_then(|builder| builder.install_recorder())
+        .map_err(|e| format!("Falha ao configurar métricas: {}", e))?;
+
+    describe_counter!(HTTP_REQUESTS_TOTAL, "Requisições HTTP por método, rota e status");
+    describe_histogram!(HTTP_REQUEST_DURATION, Unit::Seconds, "Latência das requisições HTTP por método, rota e status");
+    describe_gauge!(DB_POOL_CONNECTIONS, "Conexões mantidas pelo pool do banco");
+    describe_gauge!(DB_POOL_IDLE_CONNECTIONS, "Conexões ociosas no pool do banco");
+    describe_histogram!(DB_POOL_WAIT, Unit::Seconds, "Tempo de espera para obter uma conexão do pool");
+    describe_counter!(DB_POOL_TIMEOUTS_TOTAL, "Tentativas de obter conexão que expiraram");
+    describe_counter!(APPOINTMENTS_CREATED_TOTAL, "Agendamentos criados");
+    describe_counter!(APPOINTMENTS_CANCELED_TOTAL, "Agendamentos cancelados");
+    describe_counter!(APPOINTMENTS_NO_SHOW_TOTAL, "Agendamentos marcados como não comparecimento");
+    describe_counter!(LOGINS_TOTAL, "Tentativas de login por resultado");
+    describe_counter!(RATE_LIMIT_REJECTIONS_TOTAL, "Requisições recusadas pelo rate limit");
+    describe_counter!(NOTIFICATIONS_TOTAL, "Notificações enviadas por canal e resultado");
+
+    Ok(handle)
+}
+
+/// ✅ Worker em background que executa o `run_upkeep` do recorder periodicamente
+/// (encerra quando o `Shutdown` é acionado)
+pub fn spawn_upkeep(handle: PrometheusHandle, jobs: JobRegistry, shutdown: Shutdown) -> tokio::task::JoinHandle<()> {
+    jobs.register(UPKEEP_JOB, UPKEEP_INTERVAL);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(UPKEEP_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    handle.run_upkeep();
+                    jobs.heartbeat(UPKEEP_JOB);
+                }
+                _ = shutdown.wait() => break,
+            }
+        }
+
+        jobs.stopped(UPKEEP_JOB);
+    })
+}
+
+/// 🔹 Contador + histograma de latência por rota
+pub fn record_http_request(method: &str, route: &str, status: u16, latency: Duration) {
+    let labels = [
+        ("method", method.to_string()),
+        ("route", route.to_string()),
+        ("status", status.to_string()),
+    ];
+
+    counter!(HTTP_REQUESTS_TOTAL, &labels).increment(1);
+    histogram!(HTTP_REQUEST_DURATION, &labels).record(latency.as_secs_f64());
+}
+
+/// 🔹 Atualiza os gauges do pool (chamado a cada scrape)
+pub fn record_pool_state(pool: &Pool) {
+    let state = pool.state();
+    gauge!(DB_POOL_CONNECTIONS).set(state.connections as f64);
+    gauge!(DB_POOL_IDLE_CONNECTIONS).set(state.idle_connections as f64);
+}
+
+/// 🔹 Eventos do r2d2 → tempo de espera e timeouts do pool
+#[derive(Debug)]
+pub struct PoolMetrics;
+
+impl HandleEvent for PoolMetrics {
+    fn handle_checkout(&self, event: CheckoutEvent) {
+        histogram!(DB_POOL_WAIT).record(event.duration().as_secs_f64());
+    }
+
+    fn handle_timeout(&self, _event: TimeoutEvent) {
+        counter!(DB_POOL_TIMEOUTS_TOTAL).increment(1);
+    }
+}
+
+/// 🔹 Contadores de domínio
+pub fn appointment_created() {
+    counter!(APPOINTMENTS_CREATED_TOTAL).increment(1);
+}
+
+/// 🔹 Conta transições de status relevantes ("canceled", "no_show")
+pub fn appointment_status_changed(new_status: &str) {
+    match new_status {
+        "canceled" => counter!(APPOINTMENTS_CANCELED_TOTAL).increment(1),
+        "no_show" => counter!(APPOINTMENTS_NO_SHOW_TOTAL).increment(1),
+        _ => {}
+    }
+}
+
+pub fn login_attempt(succeeded: bool) {
+    let result = if succeeded { "succeeded" } else { "failed" };
+    counter!(LOGINS_TOTAL, "result" => result).increment(1);
+}
+
+pub fn rate_limit_rejected(limiter: &'static str) {
+    counter!(RATE_LIMIT_REJECTIONS_TOTAL, "limiter" => limiter).increment(1);
+}
+
+pub fn notification_sent(channel: &'static str, succeeded: bool) {
+    let result = if succeeded { "succeeded" } else { "failed" };
+    counter!(NOTIFICATIONS_TOTAL, "channel" => channel, "result" => result).increment(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_counters_and_http_labels() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        metrics::with_local_recorder(&recorder, || {
+            appointment_created();
+            appointment_status_changed("canceled");
+            appointment_status_changed("confirmed");
+            login_attempt(false);
+            record_http_request("GET", "/v1/services/:id", 200, Duration::from_millis(3));
+        });
+
+        let rendered = recorder.handle().render();
+        assert!(rendered.contains("appointments_created_total 1"), "{}", rendered);
+        assert!(rendered.contains("appointments_canceled_total 1"), "{}", rendered);
+        assert!(!rendered.contains("appointments_no_show_total"), "{}", rendered);
+        assert!(rendered.contains(r#"logins_total{result="failed"} 1"#), "{}", rendered);
+        assert!(
+            rendered.contains(r#"http_requests_total{method="GET",route="/v1/services/:id",status="200"} 1"#),
+            "{}",
+            rendered
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! 🔹 Banco para os testes que precisam de Postgres (`TEST_DATABASE_URL`)
+//! Cada chamada cria um schema próprio, apagado ao final. Sem a variável, os testes são pulados.
+
+use diesel::connection::SimpleConnection;
+use diesel::pg::PgConnection;
+use diesel::r2d2::{ConnectionManager, CustomizeConnection, Pool as R2D2Pool};
+use diesel::Connection;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::db::Pool;
+
+/// ✅ Tabelas anteriores às migrações de 2026, no formato de `schema.rs`
+/// (as migrações de 2025 estão defasadas em relação ao banco de produção)
+const BASE_SCHEMA: &str = r#"
+CREATE TABLE users (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL,
+    phone TEXT NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL,
+    role TEXT NOT NULL DEFAULT 'client',
+    sms_verified BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE TABLE clients (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL,
+    phone TEXT NOT NULL,
+    email TEXT
+);
+CREATE TABLE admins (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    master_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    phone TEXT NOT NULL,
+    password_hash TEXT NOT NULL
+);
+CREATE TABLE professionals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    bio TEXT,
+    specialties TEXT[] DEFAULT '{}',
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE TABLE services (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    nome TEXT NOT NULL,
+    descricao TEXT,
+    preco DOUBLE PRECISION NOT NULL,
+    duracao_min INTEGER NOT NULL,
+    ativo BOOLEAN NOT NULL DEFAULT TRUE
+);
+CREATE TABLE availabilities (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    professional_id UUID NOT NULL REFERENCES professionals(id) ON DELETE CASCADE,
+    date DATE NOT NULL,
+    start_time TIME NOT NULL,
+    end_time TIME NOT NULL
+);
+CREATE TABLE salon_settings (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    professional_id UUID NOT NULL REFERENCES professionals(id) ON DELETE CASCADE,
+    opening_hour TIME NOT NULL,
+    closing_hour TIME NOT NULL,
+    working_days TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE TABLE appointments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    client_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    professional_id UUID NOT NULL REFERENCES professionals(id) ON DELETE CASCADE,
+    service_id UUID NOT NULL REFERENCES services(id) ON DELETE CASCADE,
+    appointment_time TIMESTAMP NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+);
+CREATE TABLE reservations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    service TEXT NOT NULL DEFAULT 'General',
+    appointment_time TIMESTAMP NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+);
+"#;
+
+/// 🔹 Schema isolado de um teste (removido no `Drop`)
+pub struct TestDb {
+    pub pool: Arc<Pool>,
+    url: String,
+    schema: String,
+}
+
+#[derive(Debug)]
+struct SearchPath(String);
+
+impl CustomizeConnection<PgConnection, diesel::r2d2::Error> for SearchPath {
+    fn on_acquire(&self, conn: &mut PgConnection) -> Result<(), diesel::r2d2::Error> {
+        conn.batch_execute(&format!("SET search_path TO {}, public", self.0))
+            .map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
+/// ✅ Cria o schema com as tabelas-base e aplica as migrações de 2026; `None` sem `TEST_DATABASE_URL`
+pub fn setup() -> Option<TestDb> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL não definida; teste com banco ignorado");
+        return None;
+    };
+    let schema = format!("test_{}", Uuid::new_v4().simple());
+
+    let mut conn = PgConnection::establish(&url).expect("TEST_DATABASE_URL inválida");
+    conn.batch_execute(&format!("CREATE SCHEMA {0}; SET search_path TO {0}, public;", schema))
+        .expect("Falha ao criar o schema de teste");
+    conn.batch_execute(BASE_SCHEMA).expect("Falha ao criar as tabelas-base");
+
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations");
+    let mut migrations = std::fs::read_dir(&dir)
+        .expect("Diretório de migrações")
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with("2026")))
+        .collect::<Vec<_>>();
+    migrations.sort();
+    for migration in migrations {
+        let sql = std::fs::read_to_string(migration.join("up.sql")).expect("up.sql da migração");
+        conn.batch_execute(&sql).unwrap_or_else(|e| panic!("Migração {:?} falhou: {}", migration, e));
+    }
+
+    let pool = R2D2Pool::builder()
+        .max_size(2)
+        .connection_customizer(Box::new(SearchPath(schema.clone())))
+        .build(ConnectionManager::<PgConnection>::new(&url))
+        .expect("Falha ao criar o pool de teste");
+
+    Some(TestDb { pool: Arc::new(pool), url, schema })
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        if let Ok(mut conn) = PgConnection::establish(&self.url) {
+            let _ = conn.batch_execute(&format!("DROP SCHEMA {} CASCADE", self.schema));
+        }
+    }
+}
+
+/// 🔹 Dados mínimos para os testes
+pub mod fixtures {
+    use chrono::NaiveDateTime;
+    use diesel::prelude::*;
+    use uuid::Uuid;
+
+    use crate::schema::{appointments, professionals, services, users};
+
+    pub fn user(conn: &mut PgConnection, role: &str, phone: &str) -> Uuid {
+        diesel::insert_into(users::table)
+            .values((
+                users::name.eq("Maria Silva"),
+                users::phone.eq(phone),
+                users::password_hash.eq("hash"),
+                users::role.eq(role),
+            ))
+            .returning(users::id)
+            .get_result(conn)
+            .expect("Falha ao criar usuário")
+    }
+
+    pub fn professional(conn: &mut PgConnection, phone: &str) -> Uuid {
+        let user_id = user(conn, "professional", phone);
+        diesel::insert_into(professionals::table)
+            .values(professionals::user_id.eq(user_id))
+            .returning(professionals::id)
+            .get_result(conn)
+            .expect("Falha ao criar profissional")
+    }
+
+    pub fn service(conn: &mut PgConnection, duration_min: i32) -> Uuid {
+        diesel::insert_into(services::table)
+            .values((services::nome.eq("Corte"), services::preco.eq(50.0), services::duracao_min.eq(duration_min)))
+            .returning(services::id)
+            .get_result(conn)
+            .expect("Falha ao criar serviço")
+    }
+
+    pub fn appointment(conn: &mut PgConnection, client: Uuid, professional: Uuid, service: Uuid, at: NaiveDateTime) -> Uuid {
+        diesel::insert_into(appointments::table)
+            .values((
+                appointments::client_id.eq(client),
+                appointments::professional_id.eq(professional),
+                appointments::service_id.eq(service),
+                appointments::appointment_time.eq(at),
+            ))
+            .returning(appointments::id)
+            .get_result(conn)
+            .expect("Falha ao criar agendamento")
+    }
+}